@@ -5,8 +5,134 @@ use crate::util;
 use anyhow::Result;
 use copypasta::{ClipboardContext, ClipboardProvider};
 use ratatui::{backend::CrosstermBackend, Terminal};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex};
 
+/// How long entries added by a refresh are marked NEW, if their feed stays selected
+const REFRESHED_ENTRIES_MARKER_DURATION: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How many characters `<` and `>` scroll a long title by
+const TITLE_SCROLL_STEP: usize = 8;
+
+/// How many related entries are shown under the one being read, each with a number key
+pub const RELATED_ENTRIES_LIMIT: usize = 5;
+
+/// The most entries a search lists, as the best matches come first
+const SEARCH_RESULTS_LIMIT: usize = 200;
+
+/// The most entries the jump list remembers, like vim's
+const JUMP_LIST_LIMIT: usize = 100;
+
+/// How long the screen stays inverted for a visual alert
+const VISUAL_ALERT_DURATION: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// What actions on the selected feed say when there is none, like in an empty database
+const NO_FEED_SELECTED: &str = "No feed selected — press i to add one";
+
+/// What copying or opening a link says when there is nothing with a link selected
+const NO_LINK_SELECTED: &str = "Nothing with a link is selected";
+
+/// When to alert that a refresh finished, for switching away during a long refresh
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RefreshAlert {
+    Never,
+    /// when refreshing all feeds finishes
+    Refreshed,
+    /// when any refresh finds new entries
+    NewEntries,
+}
+
+impl std::str::FromStr for RefreshAlert {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "never" => Ok(RefreshAlert::Never),
+            "refreshed" => Ok(RefreshAlert::Refreshed),
+            "new-entries" => Ok(RefreshAlert::NewEntries),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not one of never, refreshed, or new-entries"
+            )),
+        }
+    }
+}
+
+/// What happens after `--idle-timeout` without any input
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IdleAction {
+    /// hide everything until a key is pressed
+    Lock,
+    Quit,
+}
+
+impl std::str::FromStr for IdleAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lock" => Ok(IdleAction::Lock),
+            "quit" => Ok(IdleAction::Quit),
+            _ => Err(anyhow::anyhow!("{s} is not one of lock or quit")),
+        }
+    }
+}
+
+/// What `Enter` does on a feed, to match what other feed readers do
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FeedEnterAction {
+    /// move into the feed's entries, like `l`
+    Entries,
+    /// open the feed's site in the browser, like `o`
+    OpenSite,
+    /// refresh the feed, like `r`
+    Refresh,
+}
+
+impl std::str::FromStr for FeedEnterAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "entries" => Ok(FeedEnterAction::Entries),
+            "open-site" => Ok(FeedEnterAction::OpenSite),
+            "refresh" => Ok(FeedEnterAction::Refresh),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not one of entries, open-site, or refresh"
+            )),
+        }
+    }
+}
+
+/// How much of the feeds and entries lists each row takes up
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ListDensity {
+    /// one line per row, with only titles
+    Compact,
+    /// one line per row, with details like dates and authors after titles, where they fit
+    Normal,
+    /// two lines per row, with details under titles
+    Comfortable,
+    /// one line per row, with entries' dates, read markers, titles, and authors in columns.
+    /// feeds are listed as with `Normal`
+    Table,
+}
+
+impl std::str::FromStr for ListDensity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "compact" => Ok(ListDensity::Compact),
+            "normal" => Ok(ListDensity::Normal),
+            "comfortable" => Ok(ListDensity::Comfortable),
+            "table" => Ok(ListDensity::Table),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not one of compact, normal, comfortable, or table"
+            )),
+        }
+    }
+}
+
 macro_rules! delegate_to_locked_inner {
     ($(($fn_name:ident, $t:ty)),* $(,)? ) => {
         $(
@@ -37,21 +163,41 @@ pub struct App {
 impl App {
     delegate_to_locked_inner![
         (error_flash_is_empty, bool),
-        (feed_ids, Result<Vec<crate::rss::FeedId>>),
+        (is_locked, bool),
         (force_redraw, Result<()>),
         (http_client, ureq::Agent),
         (mode, Mode),
         (selected, Selected),
-        (open_link_in_browser, Result<()>),
         (should_quit, bool),
-        (refresh_feed, Result<()>),
-        (subscribe_to_feed, Result<()>),
-        (feed_subscription_input_is_empty, bool)
+        (shows_entry_info, bool),
+        (has_suggested_feed_move, bool),
+        (selected_entry_id, Option<crate::rss::EntryId>),
+        (session_state, crate::session::SessionState),
+        (feed_subscription_input_is_empty, bool),
+        (shows_directory_results, bool),
+        (search_input_is_empty, bool),
+        (shows_search_results, bool),
+        (shows_starred_entries, bool),
+        (feed_enter_action, FeedEnterAction),
+        (key_hints, bool),
+        (pending_key, Option<char>),
+        (database_path, std::path::PathBuf),
+        (database_path_input_is_empty, bool)
     ];
 
     delegate_to_locked_mut_inner![
         (clear_error_flash, ()),
-        (clear_flash, ()),
+        (expire_flash, ()),
+        (expire_refreshed_entries_marker, ()),
+        (break_io_thread, Result<()>),
+        (refresh_feed, Result<()>),
+        (refresh_feeds, Result<()>),
+        (preview_refresh_feeds, Result<()>),
+        (rediscover_feed, Result<()>),
+        (open_link_in_browser, Result<()>),
+        (open_feed_link_in_browser, Result<()>),
+        (resolve_current_entry_link, Result<()>),
+        (subscribe_to_feed, Result<()>),
         (on_down, Result<()>),
         (on_left, Result<()>),
         (on_right, Result<()>),
@@ -59,13 +205,57 @@ impl App {
         (page_up, ()),
         (page_down, ()),
         (pop_feed_subscription_input, ()),
+        (toggle_directory_search, ()),
+        (directory_next, ()),
+        (directory_previous, ()),
+        (subscribe_to_directory_feed, Result<()>),
+        (pop_search_input, ()),
+        (search_entries, Result<()>),
+        (clear_search, Result<()>),
         (put_current_link_in_clipboard, Result<()>),
-        (reset_feed_subscription_input, ()),
-        (select_feeds, ()),
+        (put_current_feed_link_in_clipboard, Result<()>),
         (delete_feed, Result<()>),
+        (prompt_suggested_feed_move, ()),
+        (prompt_delete_feed, ()),
+        (enter_switch_database_mode, ()),
+        (pop_database_path_input, ()),
+        (switch_database, Result<()>),
+        (enter_edit_feed_http_settings_mode, Result<()>),
+        (pop_feed_http_settings_input, ()),
+        (save_feed_http_settings, Result<()>),
+        (enter_edit_feed_title_mode, ()),
+        (pop_feed_title_input, ()),
+        (save_feed_title, Result<()>),
+        (clear_pending_key, ()),
         (toggle_help, Result<()>),
         (toggle_read, Result<()>),
         (toggle_read_mode, Result<()>),
+        (toggle_starred, Result<()>),
+        (toggle_starred_entries, Result<()>),
+        (toggle_entry_sort, Result<()>),
+        (toggle_group_feeds_by_domain, Result<()>),
+        (cycle_feed_sort, Result<()>),
+        (toggle_preview, ()),
+        (toggle_offline, ()),
+        (record_activity, ()),
+        (lock_if_idle, ()),
+        (unlock, ()),
+        (scroll_title_left, ()),
+        (scroll_title_right, ()),
+        (toggle_entry_info, Result<()>),
+        (accept_suggested_feed_move, Result<()>),
+        (dismiss_suggested_feed_move, Result<()>),
+        (enqueue_current_entry, Result<()>),
+        (read_next_queued_entry, Result<()>),
+        (toggle_split_entry, Result<()>),
+        (toggle_split_entry_focus, ()),
+        (fetch_full_content, Result<()>),
+        (mark_current_feed_read, Result<()>),
+        (jump_back, Result<()>),
+        (jump_forward, Result<()>),
+        (toggle_time_travel, Result<()>),
+        (time_travel_earlier, Result<()>),
+        (time_travel_later, Result<()>),
         (update_current_feed_and_entries, Result<()>),
         (select_and_show_current_entry, Result<()>)
     ];
@@ -73,7 +263,7 @@ impl App {
     pub fn new(
         options: crate::ReadOptions,
         event_tx: std::sync::mpsc::Sender<crate::Event<crossterm::event::KeyEvent>>,
-        io_tx: std::sync::mpsc::Sender<crate::io::Action>,
+        io_tx: std::sync::mpsc::Sender<(crate::io::RequestId, crate::io::Request)>,
     ) -> Result<App> {
         Ok(App {
             inner: Arc::new(Mutex::new(AppImpl::new(options, event_tx, io_tx)?)),
@@ -84,25 +274,49 @@ impl App {
         let mut inner = self.inner.lock().unwrap();
 
         terminal.draw(|f| {
-            let chunks = crate::ui::predraw(f);
+            inner.terminal_width = f.size().width;
+
+            let chunks = crate::ui::predraw(f, inner.shows_preview());
 
             assert!(
                 chunks.len() >= 2,
                 "There must be at least two chunks in order to draw two columns"
             );
 
-            let new_width = chunks[1].width;
+            let (chunks, split_entry_area) =
+                crate::ui::split_off_entry(chunks, inner.split_entry.is_some());
+
+            // entries are read in the last column, whether there are two or three
+            let new_width = chunks[chunks.len() - 1].width;
 
             if inner.entry_column_width != new_width {
                 inner.entry_column_width = new_width;
-                inner.select_and_show_current_entry().unwrap_or_else(|e| {
+                inner.preview_entry_id = None;
+
+                if matches!(inner.selected, Selected::Entry(_)) {
+                    inner.select_and_show_current_entry().unwrap_or_else(|e| {
+                        inner.error_flash = vec![e];
+                    })
+                }
+
+                inner.update_split_entry_text().unwrap_or_else(|e| {
+                    inner.error_flash = vec![e];
+                });
+            }
+
+            if inner.shows_preview() {
+                inner.update_preview().unwrap_or_else(|e| {
                     inner.error_flash = vec![e];
                 })
             }
 
-            inner.entry_column_width = chunks[1].width;
+            inner.update_entry_info().unwrap_or_else(|e| {
+                inner.error_flash = vec![e];
+            });
+
+            crate::ui::draw(f, chunks, split_entry_area, &mut inner);
 
-            crate::ui::draw(f, chunks, &mut inner);
+            inner.color_support.adapt(f.buffer_mut());
         })?;
 
         Ok(())
@@ -113,14 +327,30 @@ impl App {
         inner.should_quit = should_quit
     }
 
-    pub fn set_flash(&self, flash: String) {
+    /// Shows a message that clears itself after a while
+    pub fn flash(&self, flash: String) {
         let mut inner = self.inner.lock().unwrap();
-        inner.flash = Some(flash)
+        inner.flash(flash)
     }
 
-    pub fn push_error_flash(&self, e: anyhow::Error) {
+    pub fn toggle_entry_read(&self, entry_id: crate::rss::EntryId) -> Result<()> {
         let mut inner = self.inner.lock().unwrap();
-        inner.error_flash.push(e);
+        inner.toggle_entry_read(entry_id)
+    }
+
+    pub fn read_related_entry(&self, index: usize) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.read_related_entry(index)
+    }
+
+    pub(crate) fn restore_session(&self, state: crate::session::SessionState) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.restore_session(state)
+    }
+
+    pub(crate) fn handle_io_response(&self, response: crate::io::Response) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.handle_io_response(response)
     }
 
     pub fn set_mode(&self, mode: Mode) {
@@ -128,30 +358,36 @@ impl App {
         inner.mode = mode;
     }
 
+    /// Waits on the rest of a two-key command like `dd`, which starts with `key`
+    pub fn set_pending_key(&self, key: char) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pending_key = Some(key);
+    }
+
     pub fn push_feed_subscription_input(&self, input: char) {
         let mut inner = self.inner.lock().unwrap();
         inner.feed_subscription_input.push(input);
+        inner.search_directory();
     }
 
-    pub fn set_feeds(&self, feeds: Vec<crate::rss::Feed>) {
+    pub fn push_search_input(&self, input: char) {
         let mut inner = self.inner.lock().unwrap();
-        let feeds = feeds.into();
-        inner.feeds = feeds;
+        inner.search_input.push(input);
     }
 
-    pub(crate) fn refresh_feeds(&self) -> Result<()> {
-        let feed_ids = self.feed_ids()?;
-        let inner = self.inner.lock().unwrap();
-        inner
-            .io_tx
-            .send(crate::io::Action::RefreshFeeds(feed_ids))?;
-        Ok(())
+    pub fn push_database_path_input(&self, input: char) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.database_path_input.push(input);
     }
 
-    pub(crate) fn break_io_thread(&self) -> Result<()> {
-        let inner = self.inner.lock().unwrap();
-        inner.io_tx.send(crate::io::Action::Break)?;
-        Ok(())
+    pub fn push_feed_http_settings_input(&self, input: char) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.feed_http_settings_input.push(input);
+    }
+
+    pub fn push_feed_title_input(&self, input: char) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.feed_title_input.push(input);
     }
 
     pub(crate) fn has_entries(&self) -> bool {
@@ -165,15 +401,54 @@ impl App {
     }
 }
 
+/// A row in the feeds list
+#[derive(Clone, Debug)]
+pub enum FeedsListItem {
+    Feed(Box<crate::rss::Feed>),
+    /// The heading for a group of feeds, when feeds are grouped by folder or domain
+    Group {
+        name: String,
+        feeds_len: usize,
+        collapsed: bool,
+    },
+}
+
+/// An entry kept open beside whatever else is shown, to read two entries side by side
+#[derive(Debug)]
+pub struct SplitEntry {
+    pub entry_meta: crate::rss::EntryMetadata,
+    pub feed_title: Option<String>,
+    pub text: String,
+    pub scroll_position: u16,
+    /// how many lines of the entry fit in its pane, set when it is drawn
+    pub lines_rendered_len: u16,
+}
+
 #[derive(Debug)]
 pub struct AppImpl {
     // database stuff
+    /// for writing, like marking entries read
     pub conn: rusqlite::Connection,
+    /// for everything the UI reads, so reading never waits on the io thread's writes
+    pub read_conn: rusqlite::Connection,
+    database_path: std::path::PathBuf,
+    database_options: crate::rss::DatabaseOptions,
+    /// the path of another database to switch to, while it is being typed
+    pub database_path_input: String,
+    /// requests before this one were for a database that has since been switched away from,
+    /// so what they did is ignored
+    first_request_for_database: crate::io::RequestId,
     // network stuff
     pub http_client: ureq::Agent,
     // feed stuff
     pub current_feed: Option<crate::rss::Feed>,
-    pub feeds: util::StatefulList<crate::rss::Feed>,
+    /// every feed, in the order they are listed when not grouped
+    pub all_feeds: Vec<crate::rss::Feed>,
+    /// what is shown in the feeds list, built from `all_feeds`
+    pub feeds: util::StatefulList<FeedsListItem>,
+    pub group_feeds_by_domain: bool,
+    pub feed_sort: crate::rss::FeedSort,
+    collapsed_groups: HashSet<String>,
     // entry stuff
     pub current_entry_meta: Option<crate::rss::EntryMetadata>,
     pub entries: util::StatefulList<crate::rss::EntryMetadata>,
@@ -183,6 +458,14 @@ pub struct AppImpl {
     pub entry_lines_len: usize,
     pub entry_lines_rendered_len: u16,
     pub entry_column_width: u16,
+    terminal_width: u16,
+    /// whether to show the preview pane, or `None` to show it only on wide terminals
+    preview_override: Option<bool>,
+    /// the selected entry, rendered for the preview pane
+    pub preview_text: String,
+    preview_entry_id: Option<crate::rss::EntryId>,
+    /// everything about the selected entry, while its info popup is open
+    pub entry_info: Option<crate::rss::EntryInfo>,
     // modes
     pub should_quit: bool,
     pub selected: Selected,
@@ -192,27 +475,100 @@ pub struct AppImpl {
     // misc
     pub error_flash: Vec<anyhow::Error>,
     pub feed_subscription_input: String,
+    /// the selected feed's own HTTP headers, while they are being edited
+    pub feed_http_settings_input: String,
+    /// the selected feed's custom title, while it is being edited
+    pub feed_title_input: String,
+    /// the directory feeds matching `feed_subscription_input`, while searching them
+    pub directory_results:
+        Option<crate::util::StatefulList<&'static crate::directory::DirectoryFeed>>,
+    /// what is being typed to search every feed's entries for
+    pub search_input: String,
+    /// the search whose results are listed in place of the current feed's entries
+    pub search_query: Option<String>,
+    /// whether starred entries in every feed are listed in place of the current feed's entries
+    pub shows_starred: bool,
     pub flash: Option<String>,
+    /// whether `flash` is a warning, about something that went wrong without failing
+    pub flash_is_warning: bool,
+    /// when `flash` goes away by itself, if it does
+    flash_expires_at: Option<std::time::Instant>,
+    flash_display_duration: std::time::Duration,
     event_tx: std::sync::mpsc::Sender<crate::Event<crossterm::event::KeyEvent>>,
-    io_tx: std::sync::mpsc::Sender<crate::io::Action>,
-    pub is_wsl: bool,
+    io_tx: std::sync::mpsc::Sender<(crate::io::RequestId, crate::io::Request)>,
+    next_request_id: u64,
+    /// what each request that the io thread has not finished yet is doing
+    pending_requests: HashMap<crate::io::RequestId, String>,
+    /// whether russ is running in WSL, found out the first time the clipboard is used
+    is_wsl: std::cell::OnceCell<bool>,
+    ingest_options: crate::rss::IngestOptions,
+    summarize: bool,
+    /// summaries of long entries, or `None` if a summary is still being made
+    pub entry_summaries: HashMap<crate::rss::EntryId, Option<String>>,
+    /// entries to read next this session, from any feed, in the order they were queued
+    pub reading_queue: VecDeque<crate::rss::EntryMetadata>,
+    pub split_entry: Option<SplitEntry>,
+    /// whether scrolling moves the split entry rather than what else is shown
+    pub split_entry_focused: bool,
+    /// entries read this session, oldest first, to go back and forth between with ctrl-o/ctrl-i
+    jump_list: Vec<crate::rss::EntryMetadata>,
+    /// where in `jump_list` going back and forth has got to
+    jump_list_position: usize,
+    /// entries from any feed on the same topic as the one being read, to jump to with 1-5
+    pub related_entries: Vec<crate::rss::EntryMetadata>,
+    /// how many entries each feed has gained since it was last viewed
+    pub new_entries_counts: HashMap<crate::rss::FeedId, usize>,
+    /// how many unread entries each feed has
+    pub unread_entries_counts: HashMap<crate::rss::FeedId, usize>,
+    /// entries inserted since this were added by the last refresh, and are marked NEW
+    refreshed_entries_since: Option<chrono::DateTime<chrono::Utc>>,
+    refreshed_entries_marker_expires_at: Option<std::time::Instant>,
+    refresh_alert: RefreshAlert,
+    /// invert the screen to alert, rather than ringing the terminal bell
+    visual_alert: bool,
+    /// when the screen stops being inverted, while a visual alert is showing
+    visual_alert_expires_at: Option<std::time::Instant>,
+    /// make no network requests, like on a plane
+    pub offline: bool,
+    /// how each entry is listed
+    pub entry_template: crate::template::EntryTemplate,
+    /// how many characters of the selected feed or entry's title are scrolled past,
+    /// for reading titles too long for their column
+    pub title_scroll_offset: usize,
+    pub list_density: ListDensity,
+    pub theme: crate::colors::Theme,
+    /// how long without input before `idle_action` happens, if ever
+    idle_timeout: Option<std::time::Duration>,
+    idle_action: IdleAction,
+    feed_enter_action: FeedEnterAction,
+    /// whether to flash a hint when a key does nothing
+    key_hints: bool,
+    last_activity_at: std::time::Instant,
+    /// whether the screen is hidden after being idle, until a key is pressed
+    locked: bool,
+    /// the first key of a two-key command like `dd`, while waiting on the second
+    pending_key: Option<char>,
+    /// whether the current feed's entries are being loaded by the io thread
+    pub entries_loading: bool,
+    /// the past day entries are shown as of, without anything published after it
+    pub time_travel_date: Option<chrono::NaiveDate>,
+    color_support: crate::colors::ColorSupport,
 }
 
 impl AppImpl {
     pub fn new(
         options: crate::ReadOptions,
         event_tx: std::sync::mpsc::Sender<crate::Event<crossterm::event::KeyEvent>>,
-        io_tx: std::sync::mpsc::Sender<crate::io::Action>,
+        io_tx: std::sync::mpsc::Sender<(crate::io::RequestId, crate::io::Request)>,
     ) -> Result<AppImpl> {
-        let mut conn = rusqlite::Connection::open(&options.database_path)?;
+        let (conn, read_conn) = open_database(&options.database_path, &options.database_options)?;
 
         let http_client = ureq::AgentBuilder::new()
             .timeout_read(options.network_timeout)
             .user_agent("russ/0.5.0")
             .build();
 
-        crate::rss::initialize_db(&mut conn)?;
-        let feeds: util::StatefulList<crate::rss::Feed> = vec![].into();
+        let feeds: util::StatefulList<FeedsListItem> = vec![].into();
         let entries: util::StatefulList<crate::rss::EntryMetadata> = vec![].into();
         // default to having nothing selected,
         // as it's possible we are starting for the first time,
@@ -220,10 +576,13 @@ impl AppImpl {
         let selected = Selected::None;
         let initial_current_feed = None;
 
-        let is_wsl = wsl::is_wsl();
-
         let mut app = AppImpl {
             conn,
+            read_conn,
+            database_path: options.database_path,
+            database_options: options.database_options,
+            database_path_input: String::new(),
+            first_request_for_database: crate::io::RequestId::from(0),
             http_client,
             should_quit: false,
             error_flash: vec![],
@@ -234,22 +593,81 @@ impl AppImpl {
             entry_lines_len: 0,
             entry_lines_rendered_len: 0,
             entry_column_width: 0,
+            terminal_width: 0,
+            preview_override: None,
+            preview_text: String::new(),
+            preview_entry_id: None,
+            entry_info: None,
             current_entry_meta: None,
             current_entry_text: String::new(),
             current_feed: initial_current_feed,
+            all_feeds: vec![],
+            group_feeds_by_domain: options.group_feeds_by_domain,
+            feed_sort: options.feed_sort,
+            collapsed_groups: HashSet::new(),
             feed_subscription_input: String::new(),
+            feed_http_settings_input: String::new(),
+            feed_title_input: String::new(),
+            directory_results: None,
+            search_input: String::new(),
+            search_query: None,
+            shows_starred: false,
             mode: Mode::Normal,
             read_mode: ReadMode::ShowUnread,
             show_help: true,
             entry_selection_position: 0,
             flash: None,
+            flash_is_warning: false,
+            flash_expires_at: None,
+            flash_display_duration: options.flash_display_duration_seconds,
             event_tx,
-            is_wsl,
+            is_wsl: std::cell::OnceCell::new(),
             io_tx,
+            next_request_id: 0,
+            pending_requests: HashMap::new(),
+            ingest_options: options.ingest_options,
+            summarize: options.summarizer.is_some(),
+            entry_summaries: HashMap::new(),
+            reading_queue: VecDeque::new(),
+            split_entry: None,
+            split_entry_focused: false,
+            jump_list: vec![],
+            jump_list_position: 0,
+            related_entries: vec![],
+            new_entries_counts: HashMap::new(),
+            unread_entries_counts: HashMap::new(),
+            refreshed_entries_since: None,
+            refreshed_entries_marker_expires_at: None,
+            refresh_alert: options.refresh_alert,
+            visual_alert: options.visual_alert,
+            visual_alert_expires_at: None,
+            offline: options.offline,
+            entry_template: options.entry_template,
+            title_scroll_offset: 0,
+            list_density: options.list_density,
+            theme: options.theme,
+            idle_timeout: options.idle_timeout,
+            idle_action: options.idle_action,
+            feed_enter_action: options.feed_enter_action,
+            key_hints: options.key_hints,
+            last_activity_at: std::time::Instant::now(),
+            locked: false,
+            pending_key: None,
+            entries_loading: false,
+            time_travel_date: options.as_of,
+            color_support: if options.monochrome {
+                crate::colors::ColorSupport::NoColor
+            } else {
+                crate::colors::ColorSupport::detect()
+            },
         };
 
         app.update_feeds()?;
-        app.update_current_feed_and_entries()?;
+        app.update_current_feed()?;
+
+        // entries are loaded by the io thread, so the feeds list shows up right away,
+        // even when there are so many entries that loading them takes a while
+        app.load_entries()?;
 
         // we default to having Selected::None,
         // so if there are actually feeds, select them
@@ -260,24 +678,131 @@ impl AppImpl {
         Ok(app)
     }
 
+    /// Starts editing the selected feed's own HTTP headers, from the ones it has
+    pub fn enter_edit_feed_http_settings_mode(&mut self) -> Result<()> {
+        if !matches!(self.selected, Selected::Feeds) {
+            return Ok(());
+        }
+
+        let Some(feed_id) = self.selected_feed_id() else {
+            return Ok(());
+        };
+
+        self.feed_http_settings_input =
+            crate::rss::get_feed_http_settings(&self.read_conn, feed_id)?.to_string();
+        self.mode = Mode::EditingFeedHttpSettings;
+
+        Ok(())
+    }
+
+    pub fn pop_feed_http_settings_input(&mut self) {
+        self.feed_http_settings_input.pop();
+    }
+
+    /// Saves the headers that were typed in as the selected feed's, sent from its next refresh on
+    pub fn save_feed_http_settings(&mut self) -> Result<()> {
+        self.mode = Mode::Normal;
+
+        let Some(feed_id) = self.selected_feed_id() else {
+            return Ok(());
+        };
+
+        match self
+            .feed_http_settings_input
+            .parse::<crate::rss::FeedHttpSettings>()
+        {
+            Ok(http_settings) => {
+                crate::rss::set_feed_http_settings(&self.conn, feed_id, &http_settings)?;
+                self.flash("Saved the feed's headers for its next refresh".to_string());
+            }
+            Err(e) => self
+                .error_flash
+                .push(e.context("Unable to save the feed's headers")),
+        }
+
+        Ok(())
+    }
+
+    /// Starts editing the selected feed's title, from the one it is shown with
+    pub fn enter_edit_feed_title_mode(&mut self) {
+        if !matches!(self.selected, Selected::Feeds) {
+            return;
+        }
+
+        let Some(feed) = &self.current_feed else {
+            return;
+        };
+
+        self.feed_title_input = feed.display_title().unwrap_or_default().to_owned();
+        self.mode = Mode::EditingFeedTitle;
+    }
+
+    pub fn pop_feed_title_input(&mut self) {
+        self.feed_title_input.pop();
+    }
+
+    /// Shows the selected feed with the title that was typed in,
+    /// or with its own title again if none was
+    pub fn save_feed_title(&mut self) -> Result<()> {
+        self.mode = Mode::Normal;
+
+        let Some(feed) = &self.current_feed else {
+            return Ok(());
+        };
+
+        let feed_id = feed.id;
+        let custom_title = crate::util::normalize_text(&self.feed_title_input);
+        // typing the feed's own title back in is the same as clearing it
+        let custom_title = Some(custom_title)
+            .filter(|title| !title.is_empty() && Some(title) != feed.title.as_ref());
+
+        crate::rss::set_feed_custom_title(&self.conn, feed_id, custom_title.as_deref())?;
+
+        self.update_feeds()?;
+        self.update_current_feed_and_entries()?;
+
+        match custom_title {
+            Some(title) => self.flash(format!("Renamed the feed to {title}")),
+            None => self.flash("The feed has its own title again".to_string()),
+        }
+
+        Ok(())
+    }
+
+    /// Asks whether to delete the selected feed, which `delete_feed` does if the answer is yes
+    pub fn prompt_delete_feed(&mut self) {
+        if matches!(self.selected, Selected::Feeds) && self.selected_feed_id().is_some() {
+            self.mode = Mode::DeletingFeed;
+        }
+    }
+
+    pub(crate) fn pending_key(&self) -> Option<char> {
+        self.pending_key
+    }
+
+    pub fn clear_pending_key(&mut self) {
+        self.pending_key = None;
+    }
+
+    /// Deletes the selected feed and its entries, once `prompt_delete_feed` has asked
     pub fn delete_feed(&mut self) -> Result<()> {
-        if matches!(self.selected, Selected::Feeds) && matches!(self.mode(), Mode::Editing) {
-            let feed_id = self.selected_feed_id();
+        if matches!(self.selected, Selected::Feeds) && matches!(self.mode(), Mode::DeletingFeed) {
+            self.mode = Mode::Normal;
+
+            let Some(feed_id) = self.selected_feed_id() else {
+                return Ok(());
+            };
+
             crate::rss::delete_feed(&mut self.conn, feed_id)?;
 
             // Remove the feed in app state
-            let feeds_len = self.feeds.items.len();
-
-            for i in 0..feeds_len {
-                if self.feeds.items[i].id == feed_id {
-                    self.feeds.items.remove(i);
+            let was_last = self.feeds.state.selected() == Some(self.feeds.items.len() - 1);
 
-                    if i == feeds_len - 1 {
-                        self.feeds.previous();
-                    }
+            self.all_feeds.retain(|feed| feed.id != feed_id);
+            self.update_feeds_list();
 
-                    break;
-                }
+            if was_last {
+                self.feeds.previous();
             }
 
             // Remove the entries from the feed in app state
@@ -290,19 +815,144 @@ impl AppImpl {
         Ok(())
     }
 
+    /// Reloads every feed from the database, keeping the current selection
     pub fn update_feeds(&mut self) -> Result<()> {
-        let feeds = crate::rss::get_feeds(&self.conn)?.into();
-        self.feeds = feeds;
+        self.all_feeds = crate::rss::get_feeds(&self.read_conn, self.feed_sort)?;
+        self.update_feeds_list();
+        Ok(())
+    }
+
+    fn set_feeds(&mut self, feeds: Vec<crate::rss::Feed>) {
+        self.all_feeds = feeds;
+        self.update_feeds_list();
+        self.feeds.unselect();
+    }
+
+    /// Rebuilds the feeds list from `all_feeds`,
+    /// keeping the selection where it was if it is still there
+    fn update_feeds_list(&mut self) {
+        let selected_item = self
+            .feeds
+            .state
+            .selected()
+            .and_then(|selected_idx| self.feeds.items.get(selected_idx))
+            .cloned();
+
+        let items = if self.group_feeds_by_domain {
+            let mut feeds_by_group: Vec<(String, Vec<&crate::rss::Feed>)> = vec![];
+
+            let mut feeds = self
+                .all_feeds
+                .iter()
+                .map(|feed| (util::feed_group(feed), feed))
+                .collect::<Vec<_>>();
+            // stable, so feeds stay sorted by title within their group
+            feeds.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            for (group, feed) in feeds {
+                match feeds_by_group.last_mut() {
+                    Some((last_group, group_feeds)) if *last_group == group => {
+                        group_feeds.push(feed)
+                    }
+                    _ => feeds_by_group.push((group, vec![feed])),
+                }
+            }
+
+            let mut items = vec![];
+
+            for (name, feeds) in feeds_by_group {
+                let collapsed = self.collapsed_groups.contains(&name);
+
+                items.push(FeedsListItem::Group {
+                    name,
+                    feeds_len: feeds.len(),
+                    collapsed,
+                });
+
+                if !collapsed {
+                    items.extend(
+                        feeds
+                            .into_iter()
+                            .cloned()
+                            .map(|feed| FeedsListItem::Feed(Box::new(feed))),
+                    );
+                }
+            }
+
+            items
+        } else {
+            self.all_feeds
+                .iter()
+                .cloned()
+                .map(|feed| FeedsListItem::Feed(Box::new(feed)))
+                .collect()
+        };
+
+        self.feeds.items = items;
+
+        let selected_idx = selected_item.and_then(|selected_item| {
+            self.feeds
+                .items
+                .iter()
+                .position(|item| match (&selected_item, item) {
+                    (FeedsListItem::Feed(a), FeedsListItem::Feed(b)) => a.id == b.id,
+                    (
+                        FeedsListItem::Group { name: a, .. },
+                        FeedsListItem::Group { name: b, .. },
+                    ) => a == b,
+                    _ => false,
+                })
+        });
+
+        match selected_idx {
+            Some(selected_idx) => self.feeds.state.select(Some(selected_idx)),
+            None if self.feeds.items.is_empty() => self.feeds.unselect(),
+            None => self.feeds.reset(),
+        }
+    }
+
+    /// Lists the feeds the next way: by title, then updated, unread, and added
+    pub fn cycle_feed_sort(&mut self) -> Result<()> {
+        self.feed_sort = self.feed_sort.next();
+        self.update_feeds()?;
+        self.update_current_feed_and_entries()
+    }
+
+    pub fn toggle_group_feeds_by_domain(&mut self) -> Result<()> {
+        self.group_feeds_by_domain = !self.group_feeds_by_domain;
+        self.update_feeds_list();
+        self.update_current_feed_and_entries()
+    }
+
+    /// Collapses the selected group's feeds if they are shown, and shows them if they are collapsed
+    fn toggle_selected_group_collapsed(&mut self) -> Result<()> {
+        if let Some(FeedsListItem::Group { name, .. }) = self
+            .feeds
+            .state
+            .selected()
+            .and_then(|selected_idx| self.feeds.items.get(selected_idx))
+        {
+            if !self.collapsed_groups.remove(name) {
+                self.collapsed_groups.insert(name.clone());
+            }
+
+            self.update_feeds_list();
+            self.update_current_feed_and_entries()?;
+        }
+
         Ok(())
     }
 
     pub fn update_current_feed_and_entries(&mut self) -> Result<()> {
         self.update_current_feed()?;
         self.update_current_entries()?;
+        self.new_entries_counts = crate::rss::get_new_entries_counts(&self.read_conn)?;
         Ok(())
     }
 
     fn update_current_feed(&mut self) -> Result<()> {
+        let previous_feed_id = self.current_feed.as_ref().map(|feed| feed.id);
+
         self.current_feed = if self.feeds.items.is_empty() {
             self.selected = Selected::None;
             None
@@ -314,24 +964,64 @@ impl AppImpl {
                     0
                 }
             };
-            let feed_id = self.feeds.items[selected_idx].id;
-            Some(crate::rss::get_feed(&self.conn, feed_id)?)
+            match self.feeds.items.get(selected_idx) {
+                Some(FeedsListItem::Feed(feed)) => {
+                    Some(crate::rss::get_feed(&self.read_conn, feed.id)?)
+                }
+                Some(FeedsListItem::Group { .. }) | None => None,
+            }
         };
 
+        // entries only stay marked NEW while looking at the feed they were refreshed in
+        if self.current_feed.as_ref().map(|feed| feed.id) != previous_feed_id {
+            self.refreshed_entries_since = None;
+        }
+
         Ok(())
     }
 
     fn update_current_entries(&mut self) -> Result<()> {
-        let entries = if let Some(feed) = &self.current_feed {
-            crate::rss::get_entries_metas(&self.conn, &self.read_mode, feed.id)?
-                .into_iter()
-                .collect::<Vec<_>>()
-                .into()
+        let entries = if let Some(query) = &self.search_query {
+            crate::rss::search_entries(&self.read_conn, query, SEARCH_RESULTS_LIMIT)?
+        } else if self.shows_starred {
+            crate::rss::get_starred_entries(&self.read_conn)?
+        } else if let Some(feed) = &self.current_feed {
+            crate::rss::get_entries_metas(
+                &self.read_conn,
+                &self.read_mode,
+                feed.id,
+                self.time_travel_bound(),
+            )?
         } else {
-            vec![].into()
+            vec![]
         };
 
-        self.entries = entries;
+        self.set_entries(entries);
+        // whatever changed the entries may have read or unread some
+        self.unread_entries_counts = crate::rss::get_unread_entries_counts(&self.read_conn)?;
+        Ok(())
+    }
+
+    /// Loads the current feed's entries and the new and unread entries counts on the io thread,
+    /// rather than waiting on them
+    fn load_entries(&mut self) -> Result<()> {
+        if let Some(feed) = &self.current_feed {
+            let request = crate::io::Request::LoadEntries(
+                feed.id,
+                self.read_mode.clone(),
+                self.time_travel_bound(),
+            );
+            self.entries_loading = true;
+            self.request(request)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_entries(&mut self, entries: Vec<crate::rss::EntryMetadata>) {
+        self.entries = entries.into();
+        // whatever was being loaded is out of date now
+        self.entries_loading = false;
 
         if self.entry_selection_position < self.entries.items.len() {
             self.entries
@@ -343,7 +1033,6 @@ impl AppImpl {
                 None => self.entries.reset(),
             }
         }
-        Ok(())
     }
 
     fn update_entry_selection_position(&mut self) {
@@ -354,23 +1043,13 @@ impl AppImpl {
         };
     }
 
-    fn get_selected_entry_content(&self) -> Option<Result<crate::rss::EntryContent>> {
-        self.entries.state.selected().and_then(|selected_idx| {
-            self.entries
-                .items
-                .get(selected_idx)
-                .map(|item| item.id)
-                .map(|entry_id| crate::rss::get_entry_content(&self.conn, entry_id))
-        })
-    }
-
     fn get_selected_entry_meta(&self) -> Option<Result<crate::rss::EntryMetadata>> {
         self.entries.state.selected().and_then(|selected_idx| {
             self.entries
                 .items
                 .get(selected_idx)
                 .map(|item| item.id)
-                .map(|entry_id| crate::rss::get_entry_meta(&self.conn, entry_id))
+                .map(|entry_id| crate::rss::get_entry_meta(&self.read_conn, entry_id))
         })
     }
 
@@ -382,7 +1061,31 @@ impl AppImpl {
         Ok(())
     }
 
+    /// Picks up changes to the current entry's metadata,
+    /// including for the entry that is currently being read
+    pub fn reload_current_entry_meta(&mut self) -> Result<()> {
+        self.update_current_feed_and_entries()?;
+        self.update_current_entry_meta()?;
+
+        if let (Selected::Entry(_), Some(entry_meta)) = (&self.selected, &self.current_entry_meta) {
+            self.selected = Selected::Entry(Box::new(entry_meta.clone()));
+        }
+
+        Ok(())
+    }
+
     fn page_up(&mut self) {
+        if let Some(split_entry) = self
+            .split_entry
+            .as_mut()
+            .filter(|_| self.split_entry_focused)
+        {
+            split_entry.scroll_position = split_entry
+                .scroll_position
+                .saturating_sub(split_entry.lines_rendered_len);
+            return;
+        }
+
         if matches!(self.selected, Selected::Entry(_)) {
             self.entry_scroll_position = if let Some(position) = self
                 .entry_scroll_position
@@ -396,6 +1099,17 @@ impl AppImpl {
     }
 
     fn page_down(&mut self) {
+        if let Some(split_entry) = self
+            .split_entry
+            .as_mut()
+            .filter(|_| self.split_entry_focused)
+        {
+            let lines_len = split_entry.text.matches('\n').count() as u16;
+            split_entry.scroll_position =
+                (split_entry.scroll_position + split_entry.lines_rendered_len).min(lines_len);
+            return;
+        }
+
         if matches!(self.selected, Selected::Entry(_)) {
             self.entry_scroll_position = if self.entry_scroll_position
                 + self.entry_lines_rendered_len
@@ -408,112 +1122,1154 @@ impl AppImpl {
         }
     }
 
+    /// The width entries are wrapped to, to fit in the entry column
+    fn entry_line_length(&self) -> u16 {
+        // minimum is 1
+        if self.entry_column_width >= 5 {
+            self.entry_column_width - 4
+        } else {
+            1
+        }
+    }
+
+    /// Renders an entry's content as text, wrapped to fit the entry column
+    fn render_entry_text(&self, entry_id: crate::rss::EntryId) -> Result<String> {
+        let entry = crate::rss::get_entry_content(&self.read_conn, entry_id)?;
+        let empty_string = String::from("No content or description tag provided.");
+
+        // try an article fetched from the entry's link first,
+        // then the content tag,
+        // if there is not content tag,
+        // go to description tag,
+        // if no description tag,
+        // use empty string.
+        // TODO figure out what to actually do if there are neither
+        let html = entry
+            .full_content
+            .as_ref()
+            .or(entry.content.as_ref())
+            .or(entry.description.as_ref())
+            .unwrap_or(&empty_string);
+
+        let mut text = html2text::from_read(html.as_bytes(), self.entry_line_length().into());
+
+        if let Some(asset_cache) = &self.ingest_options.asset_cache {
+            let images = crate::assets::cached_asset_links(&asset_cache.dir, entry_id);
+
+            if !images.is_empty() {
+                text.push_str("\nCached images:\n");
+                for image in images {
+                    text.push_str(&image);
+                    text.push('\n');
+                }
+            }
+        }
+
+        Ok(text)
+    }
+
     pub(crate) fn select_and_show_current_entry(&mut self) -> Result<()> {
         if let Some(entry_meta) = &self.current_entry_meta {
             let entry_meta = entry_meta.clone();
 
-            if let Some(entry) = self.get_selected_entry_content() {
-                let entry = entry?;
-                let empty_string = String::from("No content or description tag provided.");
-
-                // try content tag first,
-                // if there is not content tag,
-                // go to description tag,
-                // if no description tag,
-                // use empty string.
-                // TODO figure out what to actually do if there are neither
-                let entry_html = entry
-                    .content
-                    .as_ref()
-                    .or(entry.description.as_ref())
-                    .or(Some(&empty_string));
-
-                // minimum is 1
-                let line_length = if self.entry_column_width >= 5 {
-                    self.entry_column_width - 4
-                } else {
-                    1
-                };
+            let line_length = self.entry_line_length();
 
-                if let Some(html) = entry_html {
-                    let text = html2text::from_read(html.as_bytes(), line_length.into());
-                    self.entry_lines_len = text.matches('\n').count();
-                    self.current_entry_text = text;
-                } else {
-                    self.current_entry_text = String::new();
+            let mut text = self.render_entry_text(entry_meta.id)?;
+
+            if self.summarize {
+                match self.entry_summaries.get(&entry_meta.id) {
+                    Some(Some(summary)) => {
+                        text.insert_str(0, &render_summary(summary, line_length.into()));
+                    }
+                    Some(None) => (),
+                    None => {
+                        if crate::summary::is_long(&text) {
+                            self.entry_summaries.insert(entry_meta.id, None);
+                            self.request(crate::io::Request::SummarizeEntry(
+                                entry_meta.id,
+                                text.clone(),
+                            ))?;
+                        }
+                    }
                 }
             }
 
-            self.selected = Selected::Entry(entry_meta);
+            self.entry_lines_len = text.matches('\n').count();
+            self.current_entry_text = text;
+
+            self.related_entries = crate::rss::get_related_entries(
+                &self.read_conn,
+                &entry_meta,
+                RELATED_ENTRIES_LIMIT,
+            )?;
+
+            self.record_jump(&entry_meta);
+
+            self.selected = Selected::Entry(Box::new(entry_meta));
         }
 
         Ok(())
     }
 
-    pub(crate) fn refresh_feed(&self) -> Result<()> {
-        let feed_id = self.selected_feed_id();
-        self.io_tx.send(crate::io::Action::RefreshFeed(feed_id))?;
-        Ok(())
+    /// Whether there is a third column, previewing the selected entry
+    pub fn shows_preview(&self) -> bool {
+        self.preview_override
+            .unwrap_or(self.terminal_width >= crate::ui::WIDE_TERMINAL_WIDTH)
     }
 
-    pub(crate) fn subscribe_to_feed(&self) -> Result<()> {
-        let feed_subscription_input = self.feed_subscription_input();
-        self.io_tx
-            .send(crate::io::Action::SubscribeToFeed(feed_subscription_input))?;
-        Ok(())
+    pub fn toggle_preview(&mut self) {
+        self.preview_override = Some(!self.shows_preview());
     }
 
-    pub fn toggle_help(&mut self) -> Result<()> {
-        self.show_help = !self.show_help;
-        Ok(())
+    pub fn shows_entry_info(&self) -> bool {
+        self.entry_info.is_some()
     }
 
-    pub fn clear_error_flash(&mut self) {
-        self.error_flash = vec![];
-    }
+    pub fn toggle_entry_info(&mut self) -> Result<()> {
+        self.entry_info = match (&self.entry_info, self.selected_entry_id()) {
+            (None, Some(entry_id)) => Some(crate::rss::get_entry_info(&self.read_conn, entry_id)?),
+            _ => None,
+        };
 
-    pub fn reset_feed_subscription_input(&mut self) {
-        self.feed_subscription_input.clear();
+        Ok(())
     }
 
-    pub fn pop_feed_subscription_input(&mut self) {
-        self.feed_subscription_input.pop();
-    }
+    /// Keeps the info popup showing whichever entry is selected
+    fn update_entry_info(&mut self) -> Result<()> {
+        let Some(entry_info) = &self.entry_info else {
+            return Ok(());
+        };
 
-    pub fn feed_subscription_input_is_empty(&self) -> bool {
-        self.feed_subscription_input.is_empty()
-    }
+        match self.selected_entry_id() {
+            Some(entry_id) if entry_id == entry_info.meta.id => (),
+            Some(entry_id) => {
+                self.entry_info = Some(crate::rss::get_entry_info(&self.read_conn, entry_id)?);
+            }
+            None => self.entry_info = None,
+        }
 
-    pub fn feed_subscription_input(&self) -> String {
-        self.feed_subscription_input.clone()
+        Ok(())
     }
 
-    pub fn error_flash_is_empty(&self) -> bool {
-        self.error_flash.is_empty()
-    }
+    /// Renders the selected entry for the preview pane, if it is not already
+    fn update_preview(&mut self) -> Result<()> {
+        let entry_id = self
+            .current_entry_meta
+            .as_ref()
+            .map(|entry_meta| entry_meta.id);
+
+        if entry_id != self.preview_entry_id {
+            self.preview_text = match entry_id {
+                Some(entry_id) => self.render_entry_text(entry_id)?,
+                None => String::new(),
+            };
+            self.preview_entry_id = entry_id;
+        }
 
-    pub fn clear_flash(&mut self) {
-        self.flash = None
+        Ok(())
     }
 
-    pub fn select_feeds(&mut self) {
-        self.selected = Selected::Feeds;
+    pub(crate) fn rediscover_feed(&mut self) -> Result<()> {
+        match self.selected_feed_id() {
+            Some(feed_id) => self.request(crate::io::Request::RediscoverFeed(feed_id))?,
+            None => self.flash(NO_FEED_SELECTED.to_string()),
+        }
+        Ok(())
     }
 
-    pub fn selected(&self) -> Selected {
-        self.selected.clone()
+    pub(crate) fn feed_enter_action(&self) -> FeedEnterAction {
+        self.feed_enter_action
     }
 
-    pub fn selected_feed_id(&self) -> crate::rss::FeedId {
-        let selected_idx = self.feeds.state.selected().unwrap();
-        self.feeds.items[selected_idx].id
+    pub(crate) fn key_hints(&self) -> bool {
+        self.key_hints
+    }
+
+    /// Whether the selected feed appears to have moved and is waiting on the user to confirm it
+    pub(crate) fn has_suggested_feed_move(&self) -> bool {
+        matches!(self.selected, Selected::Feeds)
+            && self
+                .current_feed
+                .as_ref()
+                .is_some_and(|feed| feed.suggested_feed_link.is_some())
+    }
+
+    /// Asks whether to move the current feed to where it appears to have moved,
+    /// which `accept_suggested_feed_move` does if the answer is yes
+    pub fn prompt_suggested_feed_move(&mut self) {
+        if self.has_suggested_feed_move() {
+            self.mode = Mode::MovingFeed;
+        }
+    }
+
+    /// Moves the current feed to where it appears to have moved to, and refreshes it from there
+    pub fn accept_suggested_feed_move(&mut self) -> Result<()> {
+        self.mode = Mode::Normal;
+
+        let Some(feed) = &self.current_feed else {
+            return Ok(());
+        };
+
+        let Some(feed_link) = feed.suggested_feed_link.clone() else {
+            return Ok(());
+        };
+
+        let feed_id = feed.id;
+
+        crate::rss::move_feed(&self.conn, feed_id, &feed_link)?;
+
+        self.update_feeds()?;
+        self.update_current_feed_and_entries()?;
+        self.request(crate::io::Request::RefreshFeed(feed_id))
+    }
+
+    pub fn dismiss_suggested_feed_move(&mut self) -> Result<()> {
+        self.mode = Mode::Normal;
+
+        let Some(feed) = &self.current_feed else {
+            return Ok(());
+        };
+
+        crate::rss::dismiss_feed_move(&self.conn, feed.id)?;
+
+        self.update_feeds()?;
+        self.update_current_feed_and_entries()
+    }
+
+    pub(crate) fn refresh_feed(&mut self) -> Result<()> {
+        match self.selected_feed_id() {
+            Some(feed_id) => self.request(crate::io::Request::RefreshFeed(feed_id))?,
+            None => self.flash(NO_FEED_SELECTED.to_string()),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn refresh_feeds(&mut self) -> Result<()> {
+        let feed_ids = self.feed_ids()?;
+        self.request(crate::io::Request::RefreshFeeds(feed_ids))
+    }
+
+    /// Counts the new entries refreshing all feeds would add, without adding them
+    pub(crate) fn preview_refresh_feeds(&mut self) -> Result<()> {
+        let feed_ids = self.feed_ids()?;
+        self.request(crate::io::Request::PreviewRefreshFeeds(feed_ids))
+    }
+
+    pub(crate) fn resolve_current_entry_link(&mut self) -> Result<()> {
+        let entry_meta = match &self.selected {
+            Selected::Entry(entry_meta) => Some(entry_meta.as_ref()),
+            Selected::Entries => self.current_entry_meta.as_ref(),
+            Selected::Feeds | Selected::None => None,
+        };
+
+        if let Some(crate::rss::EntryMetadata {
+            id,
+            link: Some(link),
+            ..
+        }) = entry_meta
+        {
+            let request = crate::io::Request::ResolveEntryLink(*id, link.clone());
+            self.request(request)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fetches the article at the link of the entry being read, and shows it in place of the entry's content
+    pub(crate) fn fetch_full_content(&mut self) -> Result<()> {
+        if let Selected::Entry(entry_meta) = &self.selected {
+            match entry_meta
+                .resolved_link
+                .as_ref()
+                .or(entry_meta.link.as_ref())
+            {
+                Some(link) => {
+                    let request = crate::io::Request::FetchFullContent(entry_meta.id, link.clone());
+                    self.request(request)?;
+                }
+                None => self.flash("This entry has no link to fetch".to_string()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Splits the entry being read off into its own pane, going back to the entries list,
+    /// so another entry can be read beside it. If there is a split entry already, closes it
+    pub fn toggle_split_entry(&mut self) -> Result<()> {
+        if self.split_entry.take().is_some() {
+            self.split_entry_focused = false;
+            return Ok(());
+        }
+
+        let Selected::Entry(entry_meta) = &self.selected else {
+            self.flash("Read an entry to split it off".to_string());
+            return Ok(());
+        };
+
+        self.split_entry = Some(SplitEntry {
+            entry_meta: entry_meta.as_ref().clone(),
+            feed_title: self
+                .current_feed
+                .as_ref()
+                .and_then(|feed| feed.display_title())
+                .map(str::to_owned),
+            text: self.render_entry_text(entry_meta.id)?,
+            scroll_position: self.entry_scroll_position,
+            lines_rendered_len: 0,
+        });
+
+        self.on_left()?;
+        self.flash("Split entry off. Press w to switch which pane scrolls".to_string());
+
+        Ok(())
+    }
+
+    pub fn toggle_split_entry_focus(&mut self) {
+        if self.split_entry.is_some() {
+            self.split_entry_focused = !self.split_entry_focused;
+        }
+    }
+
+    /// Renders the split entry again, to fit its pane
+    fn update_split_entry_text(&mut self) -> Result<()> {
+        if let Some(entry_id) = self
+            .split_entry
+            .as_ref()
+            .map(|split_entry| split_entry.entry_meta.id)
+        {
+            let text = self.render_entry_text(entry_id)?;
+            if let Some(split_entry) = &mut self.split_entry {
+                split_entry.text = text;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn enqueue_current_entry(&mut self) -> Result<()> {
+        let entry_meta = match &self.selected {
+            Selected::Entry(entry_meta) => Some(entry_meta.as_ref()),
+            Selected::Entries => self.current_entry_meta.as_ref(),
+            Selected::Feeds | Selected::None => None,
+        };
+
+        if let Some(entry_meta) = entry_meta {
+            if !self
+                .reading_queue
+                .iter()
+                .any(|queued_entry| queued_entry.id == entry_meta.id)
+            {
+                self.reading_queue.push_back(entry_meta.clone());
+            }
+
+            self.flash(format!("{} queued", self.reading_queue.len()));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the entry at the front of the queue, going to its feed,
+    /// as if it had been navigated to by hand
+    pub fn read_next_queued_entry(&mut self) -> Result<()> {
+        let Some(entry_meta) = self.reading_queue.pop_front() else {
+            self.flash("Queue is empty".to_string());
+            return Ok(());
+        };
+
+        self.read_entry_in_any_feed(&entry_meta)?;
+
+        self.flash(format!("{} left in queue", self.reading_queue.len()));
+
+        Ok(())
+    }
+
+    /// Remembers that `entry_meta` was read, unless it was read by going back or forth to it
+    fn record_jump(&mut self, entry_meta: &crate::rss::EntryMetadata) {
+        if self
+            .jump_list
+            .get(self.jump_list_position)
+            .is_some_and(|jump| jump.id == entry_meta.id)
+        {
+            return;
+        }
+
+        // like vim, an entry is only in the list once, where it was last read
+        self.jump_list.retain(|jump| jump.id != entry_meta.id);
+        self.jump_list.push(entry_meta.clone());
+
+        if self.jump_list.len() > JUMP_LIST_LIMIT {
+            self.jump_list.remove(0);
+        }
+
+        self.jump_list_position = self.jump_list.len() - 1;
+    }
+
+    /// Whether the entry being read is the one going back and forth has got to
+    fn is_reading_jump(&self) -> bool {
+        match (&self.selected, self.jump_list.get(self.jump_list_position)) {
+            (Selected::Entry(entry_meta), Some(jump)) => entry_meta.id == jump.id,
+            _ => false,
+        }
+    }
+
+    /// Reads the entry read before the one being read,
+    /// or the last entry read, after navigating away from it
+    pub fn jump_back(&mut self) -> Result<()> {
+        let position = if self.is_reading_jump() {
+            self.jump_list_position.checked_sub(1)
+        } else {
+            Some(self.jump_list_position).filter(|position| *position < self.jump_list.len())
+        };
+
+        match position {
+            Some(position) => self.jump_to(position),
+            None => {
+                self.flash("Nothing to jump back to".to_string());
+                Ok(())
+            }
+        }
+    }
+
+    /// Reads the entry read after the one being read, after going back
+    pub fn jump_forward(&mut self) -> Result<()> {
+        let position = self.jump_list_position + 1;
+
+        if position < self.jump_list.len() {
+            self.jump_to(position)
+        } else {
+            self.flash("Nothing to jump forward to".to_string());
+            Ok(())
+        }
+    }
+
+    fn jump_to(&mut self, position: usize) -> Result<()> {
+        self.jump_list_position = position;
+        let entry_meta = self.jump_list[position].clone();
+
+        if !self
+            .all_feeds
+            .iter()
+            .any(|feed| feed.id == entry_meta.feed_id)
+        {
+            self.jump_list.remove(position);
+            self.jump_list_position = position.saturating_sub(1);
+            self.flash("That entry's feed has been deleted".to_string());
+            return Ok(());
+        }
+
+        self.search_query = None;
+        self.shows_starred = false;
+        self.read_entry_in_any_feed(&entry_meta)
+    }
+
+    /// Reads the related entry at `index`, counting from 0
+    pub fn read_related_entry(&mut self, index: usize) -> Result<()> {
+        let Some(entry_meta) = self.related_entries.get(index).cloned() else {
+            return Ok(());
+        };
+
+        self.read_entry_in_any_feed(&entry_meta)
+    }
+
+    /// Selects the feed of `entry_meta`, and the entry in it if it is listed, and reads it
+    fn read_entry_in_any_feed(&mut self, entry_meta: &crate::rss::EntryMetadata) -> Result<()> {
+        if let Some(group) = self
+            .all_feeds
+            .iter()
+            .find(|feed| feed.id == entry_meta.feed_id)
+            .map(util::feed_group)
+        {
+            if self.collapsed_groups.remove(&group) {
+                self.update_feeds_list();
+            }
+        }
+
+        if let Some(feed_position) = self.feeds.items.iter().position(
+            |item| matches!(item, FeedsListItem::Feed(feed) if feed.id == entry_meta.feed_id),
+        ) {
+            self.feeds.state.select(Some(feed_position));
+            self.update_current_feed_and_entries()?;
+        }
+
+        if let Some(entry_position) = self
+            .entries
+            .items
+            .iter()
+            .position(|entry| entry.id == entry_meta.id)
+        {
+            self.entry_selection_position = entry_position;
+            self.entries.state.select(Some(entry_position));
+        }
+
+        // the metadata may be stale, like if it has been read since it was queued
+        self.current_entry_meta = Some(crate::rss::get_entry_meta(&self.read_conn, entry_meta.id)?);
+        self.entry_scroll_position = 0;
+        self.select_and_show_current_entry()
+    }
+
+    /// Shows a message that clears itself after the flash display duration
+    fn flash(&mut self, flash: String) {
+        self.flash = Some(flash);
+        self.flash_is_warning = false;
+        self.flash_expires_at = Some(std::time::Instant::now() + self.flash_display_duration);
+    }
+
+    /// Flashes something that went wrong without failing, which is shown less loudly than an error
+    fn warn(&mut self, warning: String) {
+        self.flash(warning);
+        self.flash_is_warning = true;
+    }
+
+    /// Flashes how a request is going, until it finishes
+    fn show_progress(&mut self, progress: String) {
+        self.flash = Some(progress);
+        self.flash_is_warning = false;
+        self.flash_expires_at = None;
+    }
+
+    /// How many warnings the feeds' last refreshes had, between them
+    pub fn refresh_warnings_len(&self) -> usize {
+        self.all_feeds
+            .iter()
+            .map(|feed| feed.refresh_warnings.len())
+            .sum()
+    }
+
+    pub fn expire_refreshed_entries_marker(&mut self) {
+        if self
+            .refreshed_entries_marker_expires_at
+            .is_some_and(|expires_at| std::time::Instant::now() >= expires_at)
+        {
+            self.refreshed_entries_since = None;
+            self.refreshed_entries_marker_expires_at = None;
+        }
+    }
+
+    /// Whether `entry` was added by the last refresh
+    pub fn is_refreshed_entry(&self, entry: &crate::rss::EntryMetadata) -> bool {
+        self.refreshed_entries_since
+            .is_some_and(|refreshed_entries_since| entry.inserted_at >= refreshed_entries_since)
+    }
+
+    /// Rings the terminal bell, or briefly inverts the screen
+    fn alert(&mut self) -> Result<()> {
+        if self.visual_alert {
+            self.visual_alert_expires_at = Some(std::time::Instant::now() + VISUAL_ALERT_DURATION);
+        } else {
+            use std::io::Write;
+            let mut stdout = std::io::stdout();
+            stdout.write_all(b"\x07")?;
+            stdout.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn shows_visual_alert(&self) -> bool {
+        self.visual_alert_expires_at
+            .is_some_and(|expires_at| std::time::Instant::now() < expires_at)
+    }
+
+    pub fn expire_flash(&mut self) {
+        if self
+            .flash_expires_at
+            .is_some_and(|flash_expires_at| std::time::Instant::now() >= flash_expires_at)
+        {
+            self.flash = None;
+            self.flash_is_warning = false;
+            self.flash_expires_at = None;
+        }
+    }
+
+    pub fn record_activity(&mut self) {
+        self.last_activity_at = std::time::Instant::now();
+    }
+
+    /// Locks the screen, or quits, once there has been no input for the idle timeout
+    pub fn lock_if_idle(&mut self) {
+        if self
+            .idle_timeout
+            .is_some_and(|idle_timeout| self.last_activity_at.elapsed() >= idle_timeout)
+        {
+            match self.idle_action {
+                IdleAction::Lock => self.locked = true,
+                IdleAction::Quit => self.should_quit = true,
+            }
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn unlock(&mut self) {
+        self.locked = false;
+    }
+
+    pub fn database_path(&self) -> std::path::PathBuf {
+        self.database_path.clone()
+    }
+
+    /// Starts typing the path of another database to switch to, from the current one's
+    pub fn enter_switch_database_mode(&mut self) {
+        self.database_path_input = self.database_path.display().to_string();
+        self.mode = Mode::SwitchingDatabase;
+    }
+
+    pub fn pop_database_path_input(&mut self) {
+        self.database_path_input.pop();
+    }
+
+    pub fn database_path_input_is_empty(&self) -> bool {
+        self.database_path_input.trim().is_empty()
+    }
+
+    /// Closes the database and opens the one at the path that was typed in,
+    /// creating it if it does not exist, with everything from the old one let go of,
+    /// like russ had just started with it
+    pub fn switch_database(&mut self) -> Result<()> {
+        self.mode = Mode::Normal;
+
+        let database_path = std::path::PathBuf::from(self.database_path_input.trim());
+        if database_path == self.database_path {
+            return Ok(());
+        }
+
+        let (conn, read_conn) = match open_database(&database_path, &self.database_options) {
+            Ok(connections) => connections,
+            Err(e) => {
+                self.error_flash
+                    .push(e.context(format!("Unable to switch to {}", database_path.display())));
+                return Ok(());
+            }
+        };
+
+        self.conn = conn;
+        self.read_conn = read_conn;
+        self.database_path = database_path;
+
+        // the io thread switches before it gets to any of the requests the new database needs
+        self.pending_requests.clear();
+        self.first_request_for_database = crate::io::RequestId::from(self.next_request_id);
+        self.request(crate::io::Request::SwitchDatabase(
+            self.database_path.clone(),
+        ))?;
+
+        self.current_feed = None;
+        self.all_feeds.clear();
+        self.feeds = vec![].into();
+        self.collapsed_groups.clear();
+        self.current_entry_meta = None;
+        self.entries = vec![].into();
+        self.entry_selection_position = 0;
+        self.current_entry_text.clear();
+        self.entry_scroll_position = 0;
+        self.preview_text.clear();
+        self.preview_entry_id = None;
+        self.entry_info = None;
+        self.selected = Selected::None;
+        self.feed_subscription_input.clear();
+        self.directory_results = None;
+        self.search_input.clear();
+        self.search_query = None;
+        self.shows_starred = false;
+        self.entry_summaries.clear();
+        self.reading_queue.clear();
+        self.split_entry = None;
+        self.split_entry_focused = false;
+        self.jump_list.clear();
+        self.jump_list_position = 0;
+        self.related_entries.clear();
+        self.new_entries_counts.clear();
+        self.unread_entries_counts.clear();
+        self.refreshed_entries_since = None;
+        self.refreshed_entries_marker_expires_at = None;
+        self.title_scroll_offset = 0;
+        self.entries_loading = false;
+
+        self.update_feeds()?;
+        self.update_current_feed()?;
+        self.load_entries()?;
+
+        if !self.feeds.items.is_empty() {
+            self.select_feeds()
+        }
+
+        Ok(())
+    }
+
+    pub fn toggle_offline(&mut self) {
+        self.offline = !self.offline;
+
+        if self.offline {
+            self.flash("Offline mode: russ will not use the network".to_string());
+        } else {
+            self.flash("Online mode".to_string());
+        }
+    }
+
+    /// Sends `request` to the io thread, showing what it is doing until it finishes.
+    /// In offline mode, requests that would go to the network are not sent
+    fn request(&mut self, request: crate::io::Request) -> Result<()> {
+        if self.offline && request.uses_network() {
+            self.flash(format!(
+                "{} needs the network, but russ is in offline mode. Press N to go online",
+                request.describe()
+            ));
+            return Ok(());
+        }
+
+        let request_id = crate::io::RequestId::from(self.next_request_id);
+        self.next_request_id += 1;
+
+        let description = request.describe();
+
+        // summaries are made and entries are loaded in the background, without interrupting reading
+        if !matches!(
+            request,
+            crate::io::Request::SummarizeEntry(..) | crate::io::Request::LoadEntries(..)
+        ) {
+            self.show_progress(format!("{description}..."));
+        }
+
+        self.pending_requests.insert(request_id, description);
+        self.io_tx.send((request_id, request))?;
+
+        Ok(())
+    }
+
+    pub(crate) fn break_io_thread(&mut self) -> Result<()> {
+        let request_id = crate::io::RequestId::from(self.next_request_id);
+        self.io_tx.send((request_id, crate::io::Request::Break))?;
+        Ok(())
+    }
+
+    /// Applies what the io thread did for a request
+    fn handle_io_response(&mut self, response: crate::io::Response) -> Result<()> {
+        use crate::io::{Outcome, Response};
+
+        match response {
+            Response::Progress(request_id, progress) => {
+                if self.pending_requests.contains_key(&request_id) {
+                    self.show_progress(progress);
+                }
+            }
+            Response::FeedRefreshed(request_id, progress) => {
+                if self.pending_requests.contains_key(&request_id) {
+                    self.show_progress(progress);
+
+                    // so new entries show up as their feed is refreshed, rather than all at the end
+                    self.update_current_feed_and_entries()?;
+                }
+            }
+            Response::Finished(request_id, result) => {
+                let description = self
+                    .pending_requests
+                    .remove(&request_id)
+                    .unwrap_or_else(|| format!("Request {request_id:?}"));
+
+                if request_id < self.first_request_for_database {
+                    return Ok(());
+                }
+
+                match result {
+                    Ok(Outcome::FeedsRefreshed {
+                        feeds_len,
+                        errors,
+                        elapsed,
+                    }) => {
+                        let refreshed_len = feeds_len - errors.len();
+
+                        // inserted_at only has whole seconds
+                        self.refreshed_entries_since = Some(chrono::SubsecRound::trunc_subsecs(
+                            chrono::Utc::now() - elapsed,
+                            0,
+                        ));
+                        self.refreshed_entries_marker_expires_at =
+                            Some(std::time::Instant::now() + REFRESHED_ENTRIES_MARKER_DURATION);
+
+                        for e in errors {
+                            self.error_flash
+                                .push(e.context(format!("{description} failed")));
+                        }
+
+                        self.update_feeds()?;
+                        self.update_current_feed_and_entries()?;
+
+                        let should_alert = match self.refresh_alert {
+                            RefreshAlert::Never => false,
+                            RefreshAlert::Refreshed => feeds_len > 1,
+                            RefreshAlert::NewEntries => {
+                                self.refreshed_entries_since.is_some_and(|since| {
+                                    crate::rss::count_entries_inserted_since(&self.read_conn, since)
+                                        .is_ok_and(|count| count > 0)
+                                })
+                            }
+                        };
+
+                        if should_alert {
+                            self.alert()?;
+                        }
+
+                        let mut flash = if feeds_len == 1 {
+                            format!("Refreshed feed in {elapsed:?}")
+                        } else {
+                            format!("Refreshed {refreshed_len}/{feeds_len} feeds in {elapsed:?}")
+                        };
+
+                        let skipped_entries =
+                            self.refreshed_entries_since.map_or(Ok(0), |since| {
+                                crate::rss::count_entries_skipped_since(&self.read_conn, since)
+                            })?;
+
+                        if skipped_entries > 0 {
+                            flash.push_str(&format!(
+                                ", leaving out {skipped_entries} entries over the new entries limit"
+                            ));
+                        }
+
+                        let warnings_len: usize = self
+                            .all_feeds
+                            .iter()
+                            .filter(|feed| {
+                                feed.refreshed_at
+                                    .zip(self.refreshed_entries_since)
+                                    .is_some_and(|(refreshed_at, since)| refreshed_at >= since)
+                            })
+                            .map(|feed| feed.refresh_warnings.len())
+                            .sum();
+
+                        match warnings_len {
+                            0 => self.flash(flash),
+                            1 => self.warn(flash + ", with 1 warning in its feed's info"),
+                            _ => self.warn(format!(
+                                "{flash}, with {warnings_len} warnings in their feeds' info"
+                            )),
+                        }
+                    }
+                    Ok(Outcome::RefreshPreviewed {
+                        new_entries_counts,
+                        feeds_len,
+                        errors,
+                        elapsed,
+                    }) => {
+                        for e in errors {
+                            self.error_flash
+                                .push(e.context(format!("{description} failed")));
+                        }
+
+                        let new_entries_len: usize =
+                            new_entries_counts.iter().map(|(_, count)| count).sum();
+
+                        self.flash(format!(
+                            "Refreshing would add {new_entries_len} entries to {}/{feeds_len} feeds (checked in {elapsed:?})",
+                            new_entries_counts.len()
+                        ));
+                    }
+                    Ok(Outcome::SubscribedToFeed { feed_link, elapsed }) => {
+                        self.reset_feed_subscription_input();
+                        self.set_feeds(crate::rss::get_feeds(&self.read_conn, self.feed_sort)?);
+                        self.select_feeds();
+                        self.update_current_feed_and_entries()?;
+                        self.mode = Mode::Normal;
+                        match feed_link {
+                            Some(feed_link) => {
+                                self.flash(format!("Subscribed to {feed_link} in {elapsed:?}"))
+                            }
+                            None => self.flash(format!("Subscribed in {elapsed:?}")),
+                        }
+                    }
+                    Ok(Outcome::FallbackFeedFound(feed_link)) => {
+                        // offered for editing, in case the found feed is not the one that was wanted
+                        self.feed_subscription_input = feed_link.clone();
+                        self.flash(format!(
+                            "Not found, but found a feed at {feed_link}. Press enter to subscribe to it"
+                        ));
+                    }
+                    Ok(Outcome::EntryLinkResolved(resolved_link)) => {
+                        self.reload_current_entry_meta()?;
+                        self.flash(format!("Resolved link to {resolved_link}"));
+                    }
+                    Ok(Outcome::FullContentFetched(entry_id)) => {
+                        // show the article if its entry is still the one being read
+                        if matches!(&self.selected, Selected::Entry(entry_meta) if entry_meta.id == entry_id)
+                        {
+                            self.select_and_show_current_entry()?;
+                        }
+                        self.flash("Fetched the full article".to_string());
+                    }
+                    Ok(Outcome::FeedRediscovered(feed_link)) => {
+                        self.update_feeds()?;
+                        self.update_current_feed_and_entries()?;
+                        self.flash(format!("Feed moved to {feed_link}"));
+                    }
+                    Ok(Outcome::EntriesLoaded {
+                        feed_id,
+                        read_mode,
+                        as_of,
+                        entries,
+                        new_entries_counts,
+                        unread_entries_counts,
+                    }) => {
+                        self.new_entries_counts = new_entries_counts;
+                        self.unread_entries_counts = unread_entries_counts;
+
+                        if self.entries_loading {
+                            let is_current = self
+                                .current_feed
+                                .as_ref()
+                                .is_some_and(|feed| feed.id == feed_id)
+                                && self.read_mode == read_mode
+                                && self.time_travel_bound() == as_of;
+
+                            if is_current {
+                                self.set_entries(entries);
+                            } else {
+                                self.load_entries()?;
+                            }
+                        }
+                    }
+                    Ok(Outcome::EntrySummarized(entry_id, summary)) => {
+                        self.set_entry_summary(entry_id, summary)?;
+                    }
+                    Ok(Outcome::DatabaseSwitched) => {
+                        self.flash(format!("Switched to {}", self.database_path.display()));
+                    }
+                    Err(e) => {
+                        if self.flash_expires_at.is_none() {
+                            self.flash = None;
+                        }
+                        self.error_flash
+                            .push(e.context(format!("{description} failed")));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set_entry_summary(&mut self, entry_id: crate::rss::EntryId, summary: String) -> Result<()> {
+        self.entry_summaries.insert(entry_id, Some(summary));
+
+        // show the summary if its entry is still the one being read
+        if matches!(&self.selected, Selected::Entry(entry_meta) if entry_meta.id == entry_id) {
+            self.select_and_show_current_entry()?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn subscribe_to_feed(&mut self) -> Result<()> {
+        let feed_subscription_input = self.feed_subscription_input();
+        self.request(crate::io::Request::SubscribeToFeed(feed_subscription_input))
+    }
+
+    pub fn toggle_help(&mut self) -> Result<()> {
+        self.show_help = !self.show_help;
+        Ok(())
+    }
+
+    pub fn clear_error_flash(&mut self) {
+        self.error_flash = vec![];
+    }
+
+    pub fn reset_feed_subscription_input(&mut self) {
+        self.feed_subscription_input.clear();
+    }
+
+    pub fn pop_feed_subscription_input(&mut self) {
+        self.feed_subscription_input.pop();
+        self.search_directory();
+    }
+
+    pub fn shows_directory_results(&self) -> bool {
+        self.directory_results.is_some()
+    }
+
+    /// Searches the directory of popular feeds for what has been typed,
+    /// or stops searching it
+    pub fn toggle_directory_search(&mut self) {
+        if self.directory_results.take().is_none() {
+            self.directory_results = Some(vec![].into());
+            self.search_directory();
+        }
+    }
+
+    fn search_directory(&mut self) {
+        if let Some(directory_results) = &mut self.directory_results {
+            *directory_results = crate::directory::search(&self.feed_subscription_input).into();
+
+            if directory_results.items.is_empty() {
+                directory_results.unselect();
+            } else {
+                directory_results.reset();
+            }
+        }
+    }
+
+    pub fn directory_next(&mut self) {
+        if let Some(directory_results) = &mut self.directory_results {
+            if !directory_results.items.is_empty() {
+                directory_results.next();
+            }
+        }
+    }
+
+    pub fn directory_previous(&mut self) {
+        if let Some(directory_results) = &mut self.directory_results {
+            if !directory_results.items.is_empty() {
+                directory_results.previous();
+            }
+        }
+    }
+
+    /// Subscribes to the selected directory feed, like it was typed in
+    pub fn subscribe_to_directory_feed(&mut self) -> Result<()> {
+        let selected_feed = self.directory_results.take().and_then(|directory_results| {
+            directory_results
+                .state
+                .selected()
+                .and_then(|i| directory_results.items.get(i).copied())
+        });
+
+        match selected_feed {
+            Some(feed) => {
+                self.feed_subscription_input = feed.feed_link.to_string();
+                self.subscribe_to_feed()
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn pop_search_input(&mut self) {
+        self.search_input.pop();
+    }
+
+    pub fn search_input_is_empty(&self) -> bool {
+        self.search_input.trim().is_empty()
+    }
+
+    pub fn shows_search_results(&self) -> bool {
+        self.search_query.is_some()
+    }
+
+    pub fn shows_starred_entries(&self) -> bool {
+        self.shows_starred
+    }
+
+    /// Whether the entries listed are from every feed, rather than the current feed's
+    pub fn lists_entries_across_feeds(&self) -> bool {
+        self.search_query.is_some() || self.shows_starred
+    }
+
+    /// Lists the entries in every feed matching what was typed,
+    /// in place of the current feed's entries
+    pub fn search_entries(&mut self) -> Result<()> {
+        self.search_query = Some(self.search_input.clone());
+        self.shows_starred = false;
+        self.mode = Mode::Normal;
+        self.show_searched_entries()?;
+
+        self.flash(format!(
+            "{} entries match \"{}\"",
+            self.entries.items.len(),
+            self.search_input
+        ));
+
+        Ok(())
+    }
+
+    /// Goes back to listing the current feed's entries
+    pub fn clear_search(&mut self) -> Result<()> {
+        if self.search_query.take().is_some() {
+            self.show_searched_entries()?;
+        }
+
+        Ok(())
+    }
+
+    /// Lists the starred entries in every feed in place of the current feed's entries,
+    /// or goes back to listing the current feed's entries
+    pub fn toggle_starred_entries(&mut self) -> Result<()> {
+        self.shows_starred = !self.shows_starred;
+        self.search_query = None;
+        self.show_searched_entries()?;
+
+        if self.shows_starred {
+            self.flash(format!("{} starred entries", self.entries.items.len()));
+        }
+
+        Ok(())
+    }
+
+    fn show_searched_entries(&mut self) -> Result<()> {
+        self.entry_selection_position = 0;
+        self.update_current_entries()?;
+
+        if !self.entries.items.is_empty() {
+            self.selected = Selected::Entries;
+            self.entries.reset();
+        } else {
+            self.entries.unselect();
+        }
+
+        self.update_current_entry_meta()
+    }
+
+    pub fn feed_subscription_input_is_empty(&self) -> bool {
+        self.feed_subscription_input.is_empty()
+    }
+
+    pub fn feed_subscription_input(&self) -> String {
+        self.feed_subscription_input.clone()
+    }
+
+    pub fn error_flash_is_empty(&self) -> bool {
+        self.error_flash.is_empty()
+    }
+
+    pub fn select_feeds(&mut self) {
+        self.selected = Selected::Feeds;
+    }
+
+    pub fn selected(&self) -> Selected {
+        self.selected.clone()
+    }
+
+    /// `None` if nothing, or a group rather than a feed, is selected
+    pub fn selected_feed_id(&self) -> Option<crate::rss::FeedId> {
+        let selected_idx = self.feeds.state.selected()?;
+        match self.feeds.items.get(selected_idx)? {
+            FeedsListItem::Feed(feed) => Some(feed.id),
+            FeedsListItem::Group { .. } => None,
+        }
     }
 
     pub fn feed_ids(&self) -> Result<Vec<crate::rss::FeedId>> {
-        let ids = crate::rss::get_feed_ids(&self.conn)?;
+        let ids = crate::rss::get_refreshable_feed_ids(&self.read_conn)?;
         Ok(ids)
     }
 
+    /// Marks every entry of the current feed read at once
+    pub fn mark_current_feed_read(&mut self) -> Result<()> {
+        let Some(feed) = &self.current_feed else {
+            return Ok(());
+        };
+
+        let title = feed.display_title().unwrap_or("feed").to_owned();
+        let marked = crate::rss::mark_feed_read(&self.conn, feed.id)?;
+
+        self.update_current_entries()?;
+        self.update_current_entry_meta()?;
+        self.update_entry_selection_position();
+
+        self.flash(format!("Marked {marked} entries in {title} read"));
+
+        Ok(())
+    }
+
     pub fn toggle_read(&mut self) -> Result<()> {
         match &self.selected {
             Selected::Entry(entry) => {
@@ -538,22 +2294,249 @@ impl AppImpl {
         Ok(())
     }
 
+    /// Stars the selected entry, or unstars it if it is starred
+    pub fn toggle_starred(&mut self) -> Result<()> {
+        let Some(entry_id) = self.selected_entry_id() else {
+            return Ok(());
+        };
+
+        let entry_meta = crate::rss::get_entry_meta(&self.read_conn, entry_id)?;
+        entry_meta.toggle_starred(&self.conn)?;
+
+        self.update_current_entries()?;
+        self.update_current_entry_meta()?;
+        self.update_entry_selection_position();
+
+        // the entry being read stays open, even when it is unstarred out of the starred entries
+        if let Selected::Entry(_) = self.selected {
+            let entry_meta = crate::rss::get_entry_meta(&self.read_conn, entry_id)?;
+            self.selected = Selected::Entry(Box::new(entry_meta));
+        }
+
+        let title = entry_meta.display_title();
+        if entry_meta.starred_at.is_none() {
+            self.flash(format!("Starred {title}"));
+        } else {
+            self.flash(format!("Unstarred {title}"));
+        }
+
+        Ok(())
+    }
+
+    /// Toggles an entry's read state wherever it is, rather than the selected entry's,
+    /// like when undoing an earlier toggle
+    pub fn toggle_entry_read(&mut self, entry_id: crate::rss::EntryId) -> Result<()> {
+        let entry_meta = crate::rss::get_entry_meta(&self.read_conn, entry_id)?;
+        entry_meta.toggle_read(&self.conn)?;
+
+        self.update_current_entries()?;
+        self.update_current_entry_meta()?;
+        self.update_entry_selection_position();
+
+        let title = entry_meta.display_title();
+        if entry_meta.read_at.is_none() {
+            self.flash(format!("Marked {title} read"));
+        } else {
+            self.flash(format!("Marked {title} unread"));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn session_state(&self) -> crate::session::SessionState {
+        crate::session::SessionState {
+            feed_id: self.selected_feed_id(),
+            entry_id: self
+                .current_entry_meta
+                .as_ref()
+                .map(|entry_meta| entry_meta.id),
+            reading_entry: matches!(self.selected, Selected::Entry(_)),
+            entry_scroll_position: self.entry_scroll_position,
+            read_mode: self.read_mode.clone(),
+            mode: self.mode,
+            feed_subscription_input: self.feed_subscription_input.clone(),
+        }
+    }
+
+    /// Puts the app back the way it was in `state`, as far as the feeds and entries still exist
+    pub(crate) fn restore_session(&mut self, state: crate::session::SessionState) -> Result<()> {
+        self.read_mode = state.read_mode;
+        // a deletion is only ever confirmed right after it is asked for,
+        // and a session is only restored into the database it was for
+        self.mode = match state.mode {
+            Mode::DeletingFeed
+            | Mode::MovingFeed
+            | Mode::SwitchingDatabase
+            | Mode::EditingFeedHttpSettings
+            | Mode::EditingFeedTitle => Mode::Normal,
+            mode => mode,
+        };
+        self.feed_subscription_input = state.feed_subscription_input;
+
+        let Some(feed_id) = state.feed_id else {
+            return Ok(());
+        };
+
+        if let Some(group) = self
+            .all_feeds
+            .iter()
+            .find(|feed| feed.id == feed_id)
+            .map(util::feed_group)
+        {
+            if self.collapsed_groups.remove(&group) {
+                self.update_feeds_list();
+            }
+        }
+
+        let Some(feed_position) = self
+            .feeds
+            .items
+            .iter()
+            .position(|item| matches!(item, FeedsListItem::Feed(feed) if feed.id == feed_id))
+        else {
+            return Ok(());
+        };
+
+        self.feeds.state.select(Some(feed_position));
+        self.update_current_feed_and_entries()?;
+
+        let Some(entry_position) = self
+            .entries
+            .items
+            .iter()
+            .position(|entry_meta| Some(entry_meta.id) == state.entry_id)
+        else {
+            return Ok(());
+        };
+
+        self.selected = Selected::Entries;
+        self.entries.state.select(Some(entry_position));
+        self.entry_selection_position = entry_position;
+        self.update_current_entry_meta()?;
+
+        if state.reading_entry {
+            self.select_and_show_current_entry()?;
+            self.entry_scroll_position = state.entry_scroll_position;
+        }
+
+        Ok(())
+    }
+
+    /// The entry that entry actions like `r` act on
+    pub(crate) fn selected_entry_id(&self) -> Option<crate::rss::EntryId> {
+        match &self.selected {
+            Selected::Entry(entry_meta) => Some(entry_meta.id),
+            Selected::Entries => self
+                .current_entry_meta
+                .as_ref()
+                .map(|entry_meta| entry_meta.id),
+            Selected::Feeds | Selected::None => None,
+        }
+    }
+
     pub fn http_client(&self) -> ureq::Agent {
         // this is cheap because it only clones a struct containing two Arcs
         self.http_client.clone()
     }
 
     pub fn toggle_read_mode(&mut self) -> Result<()> {
-        match (&self.read_mode, &self.selected) {
-            (ReadMode::ShowRead, Selected::Feeds) | (ReadMode::ShowRead, Selected::Entries) => {
-                self.entry_selection_position = 0;
-                self.read_mode = ReadMode::ShowUnread
+        if let Selected::Feeds | Selected::Entries = self.selected {
+            self.entry_selection_position = 0;
+            self.read_mode = self.read_mode.next();
+        }
+        self.update_current_entries()?;
+
+        if !self.entries.items.is_empty() {
+            self.entries.reset();
+        } else {
+            self.entries.unselect();
+        }
+
+        self.update_current_entry_meta()?;
+
+        Ok(())
+    }
+
+    /// Switches the current feed between listing its entries by when they were published
+    /// and by when they were first seen, which the feed keeps from then on
+    pub fn toggle_entry_sort(&mut self) -> Result<()> {
+        let Some(feed) = &self.current_feed else {
+            return Ok(());
+        };
+        if !matches!(self.selected, Selected::Feeds | Selected::Entries) {
+            return Ok(());
+        }
+
+        let entry_sort = feed.entry_sort.next();
+        crate::rss::set_feed_entry_sort(&self.conn, feed.id, entry_sort)?;
+
+        self.entry_selection_position = 0;
+        self.update_current_feed_and_entries()?;
+
+        if !self.entries.items.is_empty() {
+            self.entries.reset();
+        } else {
+            self.entries.unselect();
+        }
+
+        self.update_current_entry_meta()?;
+
+        match entry_sort {
+            crate::rss::EntrySort::Published => {
+                self.flash("Newest published entries first".to_string())
             }
-            (ReadMode::ShowUnread, Selected::Feeds) | (ReadMode::ShowUnread, Selected::Entries) => {
-                self.entry_selection_position = 0;
-                self.read_mode = ReadMode::ShowRead
+            crate::rss::EntrySort::Discovered => {
+                self.flash("Entries first seen most recently first".to_string())
             }
-            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// When entries are shown as of: the start of the day after the time travel date, in UTC
+    fn time_travel_bound(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.time_travel_date
+            .and_then(|date| date.succ_opt())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date| date.and_utc())
+    }
+
+    /// Shows entries as of yesterday, or the present again
+    pub fn toggle_time_travel(&mut self) -> Result<()> {
+        let date = match self.time_travel_date {
+            Some(_) => None,
+            None => chrono::Utc::now().date_naive().pred_opt(),
+        };
+
+        self.time_travel_to(date)
+    }
+
+    pub fn time_travel_earlier(&mut self) -> Result<()> {
+        let date = self
+            .time_travel_date
+            .unwrap_or_else(|| chrono::Utc::now().date_naive());
+
+        self.time_travel_to(date.pred_opt())
+    }
+
+    /// A day later, until it is today, which is the present
+    pub fn time_travel_later(&mut self) -> Result<()> {
+        let Some(date) = self.time_travel_date else {
+            return Ok(());
+        };
+
+        let date = date
+            .succ_opt()
+            .filter(|date| *date < chrono::Utc::now().date_naive());
+
+        self.time_travel_to(date)
+    }
+
+    fn time_travel_to(&mut self, date: Option<chrono::NaiveDate>) -> Result<()> {
+        self.time_travel_date = date;
+
+        if let Selected::Feeds | Selected::Entries = self.selected {
+            self.entry_selection_position = 0;
         }
         self.update_current_entries()?;
 
@@ -565,11 +2548,18 @@ impl AppImpl {
 
         self.update_current_entry_meta()?;
 
+        match date {
+            Some(date) => self.flash(format!("Showing entries as of {date}")),
+            None => self.flash("Showing entries as of now".to_string()),
+        }
+
         Ok(())
     }
 
-    fn get_current_link(&self) -> Option<&str> {
-        match &self.selected {
+    /// links stored before a tracking parameter was configured
+    /// still have it stripped before they are shared
+    fn get_current_link(&self) -> Option<String> {
+        let current_link = match &self.selected {
             Selected::Feeds => self
                 .current_feed
                 .as_ref()
@@ -578,20 +2568,41 @@ impl AppImpl {
                 .entries
                 .items
                 .get(self.entry_selection_position)
-                .and_then(|entry| entry.link.as_deref()),
-            Selected::Entry(e) => e.link.as_deref(),
+                .and_then(|entry| entry.resolved_link.as_deref().or(entry.link.as_deref())),
+            Selected::Entry(e) => e.resolved_link.as_deref().or(e.link.as_deref()),
             Selected::None => None,
-        }
+        };
+
+        current_link.map(|link| {
+            util::strip_tracking_parameters(link, &self.ingest_options.tracking_parameters)
+        })
+    }
+
+    /// The link to the current feed's XML, as opposed to its site
+    fn get_current_feed_link(&self) -> Option<String> {
+        self.current_feed
+            .as_ref()
+            .and_then(|feed| feed.feed_link.clone())
     }
 
     fn put_current_link_in_clipboard(&mut self) -> Result<()> {
-        let current_link = self.get_current_link();
+        self.put_link_in_clipboard(self.get_current_link())
+    }
+
+    fn put_current_feed_link_in_clipboard(&mut self) -> Result<()> {
+        self.put_link_in_clipboard(self.get_current_feed_link())
+    }
+
+    fn is_wsl(&self) -> bool {
+        *self.is_wsl.get_or_init(wsl::is_wsl)
+    }
 
-        if self.is_wsl {
+    fn put_link_in_clipboard(&mut self, link: Option<String>) -> Result<()> {
+        if self.is_wsl() {
             #[cfg(target_os = "linux")]
             {
-                if let Some(current_link) = current_link {
-                    util::set_wsl_clipboard_contents(current_link)
+                if let Some(link) = link {
+                    util::set_wsl_clipboard_contents(&link)
                 } else {
                     Ok(())
                 }
@@ -601,19 +2612,37 @@ impl AppImpl {
             {
                 unreachable!("This should never happen. This code should only be reachable if the target OS is WSL.")
             }
-        } else if let Some(current_link) = current_link {
-            let mut ctx = ClipboardContext::new().map_err(|e| anyhow::anyhow!(e))?;
-            ctx.set_contents(current_link.to_owned())
-                .map_err(|e| anyhow::anyhow!(e))
+        } else if let Some(link) = link {
+            let copied = ClipboardContext::new().and_then(|mut ctx| ctx.set_contents(link.clone()));
+
+            // like over SSH, or anywhere else without a display server
+            if copied.is_err() {
+                util::set_osc52_clipboard_contents(&link)?;
+                self.flash(
+                    "No clipboard here, so copied through the terminal (OSC 52)".to_string(),
+                );
+            }
+
+            Ok(())
         } else {
+            self.flash(NO_LINK_SELECTED.to_string());
             Ok(())
         }
     }
 
-    fn open_link_in_browser(&self) -> Result<()> {
-        if let Some(current_link) = self.get_current_link() {
-            webbrowser::open(current_link).map_err(|e| anyhow::anyhow!(e))
+    fn open_link_in_browser(&mut self) -> Result<()> {
+        self.open_in_browser(self.get_current_link())
+    }
+
+    fn open_feed_link_in_browser(&mut self) -> Result<()> {
+        self.open_in_browser(self.get_current_feed_link())
+    }
+
+    fn open_in_browser(&mut self, link: Option<String>) -> Result<()> {
+        if let Some(link) = link {
+            webbrowser::open(&link).map_err(|e| anyhow::anyhow!(e))
         } else {
+            self.flash(NO_LINK_SELECTED.to_string());
             Ok(())
         }
     }
@@ -622,7 +2651,17 @@ impl AppImpl {
         self.should_quit
     }
 
+    pub fn scroll_title_left(&mut self) {
+        self.title_scroll_offset = self.title_scroll_offset.saturating_sub(TITLE_SCROLL_STEP);
+    }
+
+    /// Scrolls the selected title further, as far as the UI lets it
+    pub fn scroll_title_right(&mut self) {
+        self.title_scroll_offset += TITLE_SCROLL_STEP;
+    }
+
     pub fn on_left(&mut self) -> Result<()> {
+        self.title_scroll_offset = 0;
         match self.selected {
             Selected::Feeds => (),
             Selected::Entries => {
@@ -643,15 +2682,29 @@ impl AppImpl {
     }
 
     pub fn on_up(&mut self) -> Result<()> {
+        self.title_scroll_offset = 0;
+
+        if let Some(split_entry) = self
+            .split_entry
+            .as_mut()
+            .filter(|_| self.split_entry_focused)
+        {
+            split_entry.scroll_position = split_entry.scroll_position.saturating_sub(1);
+            return Ok(());
+        }
+
         match self.selected {
             Selected::Feeds => {
                 self.feeds.previous();
+                self.search_query = None;
+                self.shows_starred = false;
                 self.update_current_feed_and_entries()?;
             }
             Selected::Entries => {
                 if !self.entries.items.is_empty() {
                     self.entries.previous();
-                    self.entry_selection_position = self.entries.state.selected().unwrap();
+                    self.entry_selection_position =
+                        self.entries.state.selected().unwrap_or_default();
                     self.update_current_entry_meta()?;
                 }
             }
@@ -667,13 +2720,23 @@ impl AppImpl {
     }
 
     pub fn on_right(&mut self) -> Result<()> {
+        self.title_scroll_offset = 0;
         match self.selected {
+            Selected::Feeds if self.current_feed.is_none() => {
+                self.toggle_selected_group_collapsed()
+            }
             Selected::Feeds => {
                 if !self.entries.items.is_empty() {
                     self.selected = Selected::Entries;
                     self.entries.reset();
                     self.update_current_entry_meta()?;
                 }
+
+                if let Some(feed) = &self.current_feed {
+                    crate::rss::update_feed_last_viewed_at(&self.conn, feed.id)?;
+                    self.new_entries_counts.remove(&feed.id);
+                }
+
                 Ok(())
             }
             Selected::Entries => self.select_and_show_current_entry(),
@@ -683,15 +2746,29 @@ impl AppImpl {
     }
 
     pub fn on_down(&mut self) -> Result<()> {
+        self.title_scroll_offset = 0;
+
+        if let Some(split_entry) = self
+            .split_entry
+            .as_mut()
+            .filter(|_| self.split_entry_focused)
+        {
+            split_entry.scroll_position = split_entry.scroll_position.saturating_add(1);
+            return Ok(());
+        }
+
         match self.selected {
             Selected::Feeds => {
                 self.feeds.next();
+                self.search_query = None;
+                self.shows_starred = false;
                 self.update_current_feed_and_entries()?;
             }
             Selected::Entries => {
                 if !self.entries.items.is_empty() {
                     self.entries.next();
-                    self.entry_selection_position = self.entries.state.selected().unwrap();
+                    self.entry_selection_position =
+                        self.entries.state.selected().unwrap_or_default();
                     self.update_current_entry_meta()?;
                 }
             }
@@ -714,3 +2791,33 @@ impl AppImpl {
         self.event_tx.send(crate::Event::Tick).map_err(|e| e.into())
     }
 }
+
+/// Opens the database at `database_path`, creating or upgrading it if need be,
+/// with a connection for writing and one for the UI's reads
+fn open_database(
+    database_path: &std::path::Path,
+    database_options: &crate::rss::DatabaseOptions,
+) -> Result<(rusqlite::Connection, rusqlite::Connection)> {
+    let mut conn = crate::rss::open(database_path, rusqlite::OpenFlags::default())?;
+    conn.set_prepared_statement_cache_capacity(crate::rss::STATEMENT_CACHE_CAPACITY);
+    crate::rss::tune_connection(&conn, database_options)?;
+
+    crate::rss::initialize_db(&mut conn)?;
+    crate::rss::enable_wal(&conn)?;
+    let read_conn = crate::rss::open_read_only(database_path, database_options)?;
+
+    Ok((conn, read_conn))
+}
+
+/// Renders `summary` the same way as entry content, so it wraps like the rest of the entry
+fn render_summary(summary: &str, line_length: usize) -> String {
+    let escaped_summary = summary
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    html2text::from_read(
+        format!("<p><b>Summary:</b> {escaped_summary}</p><hr>").as_bytes(),
+        line_length,
+    )
+}
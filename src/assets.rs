@@ -0,0 +1,301 @@
+//! A cache of the images that entries reference, for reading offline.
+//!
+//! Each entry's images are downloaded into their own directory, named by entry id,
+//! so an entry's images can be found from its id, and removed along with it.
+
+use crate::rss::EntryId;
+use crate::PruneCacheOptions;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Larger images are not cached, so one huge image cannot take the whole cache
+const MAX_ASSET_BYTES: u64 = 10 * 1024 * 1024;
+
+#[derive(Clone, Debug)]
+pub struct AssetCacheOptions {
+    pub dir: PathBuf,
+    /// once the cache is bigger than this, the assets of the entries cached longest ago are removed
+    pub max_bytes: u64,
+}
+
+/// Downloads the images `html` references into the entry's cache directory.
+/// This is best-effort, as an entry is still readable without its images,
+/// so images that fail to download are skipped.
+pub(crate) fn cache_entry_assets(
+    http_client: &ureq::Agent,
+    options: &AssetCacheOptions,
+    entry_id: EntryId,
+    html: &str,
+    base_link: Option<&str>,
+) {
+    let entry_dir = entry_dir(&options.dir, entry_id);
+
+    for link in image_links(html, base_link) {
+        let path = entry_dir.join(file_name(&link));
+
+        if path.exists() {
+            continue;
+        }
+
+        let _ = download(http_client, &link, &path);
+    }
+}
+
+/// `file://` links to the cached copies of the entry's images
+pub(crate) fn cached_asset_links(dir: &Path, entry_id: EntryId) -> Vec<String> {
+    cached_assets(dir, entry_id)
+        .into_iter()
+        .filter_map(|path| std::fs::canonicalize(path).ok())
+        .filter_map(|path| url::Url::from_file_path(path).ok())
+        .map(|url| url.to_string())
+        .collect()
+}
+
+/// The cached copies of the entry's images
+fn cached_assets(dir: &Path, entry_id: EntryId) -> Vec<PathBuf> {
+    let Ok(files) = std::fs::read_dir(entry_dir(dir, entry_id)) else {
+        return vec![];
+    };
+
+    let mut paths = files
+        .filter_map(|file| file.ok())
+        .map(|file| file.path())
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    paths
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Pruned {
+    pub(crate) entries: usize,
+    pub(crate) bytes: u64,
+}
+
+/// Removes the assets of entries that no longer exist,
+/// then the assets of the entries cached longest ago, until the cache fits in `max_bytes`
+pub(crate) fn prune(conn: &rusqlite::Connection, options: &AssetCacheOptions) -> Result<Pruned> {
+    let entry_ids = crate::rss::get_entry_ids(conn)?
+        .into_iter()
+        .map(|entry_id| entry_id.to_string())
+        .collect::<HashSet<_>>();
+
+    let mut pruned = Pruned::default();
+
+    let Ok(dirs) = std::fs::read_dir(&options.dir) else {
+        return Ok(pruned);
+    };
+
+    let mut kept = vec![];
+
+    for dir in dirs {
+        let dir = dir?;
+        let path = dir.path();
+        let bytes = dir_size(&path)?;
+
+        if entry_ids.contains(dir.file_name().to_string_lossy().as_ref()) {
+            kept.push((dir.metadata()?.modified()?, path, bytes));
+        } else {
+            remove(&path, bytes, &mut pruned)?;
+        }
+    }
+
+    let mut total_bytes = kept.iter().map(|(_, _, bytes)| bytes).sum::<u64>();
+
+    kept.sort();
+
+    for (_, path, bytes) in kept {
+        if total_bytes <= options.max_bytes {
+            break;
+        }
+
+        remove(&path, bytes, &mut pruned)?;
+        total_bytes -= bytes;
+    }
+
+    Ok(pruned)
+}
+
+pub(crate) fn prune_cache(options: PruneCacheOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let pruned = prune(&conn, &options.asset_cache)?;
+
+    eprintln!(
+        "removed the images of {} entries, {} KiB",
+        pruned.entries,
+        pruned.bytes / 1024
+    );
+
+    Ok(())
+}
+
+fn remove(path: &Path, bytes: u64, pruned: &mut Pruned) -> Result<()> {
+    std::fs::remove_dir_all(path)
+        .with_context(|| format!("unable to remove {}", path.display()))?;
+    pruned.entries += 1;
+    pruned.bytes += bytes;
+    Ok(())
+}
+
+fn dir_size(path: &Path) -> Result<u64> {
+    let mut size = 0;
+    for file in std::fs::read_dir(path)? {
+        size += file?.metadata()?.len();
+    }
+    Ok(size)
+}
+
+fn entry_dir(dir: &Path, entry_id: EntryId) -> PathBuf {
+    dir.join(entry_id.to_string())
+}
+
+fn download(http_client: &ureq::Agent, link: &str, path: &Path) -> Result<()> {
+    let response = http_client.get(link).call()?;
+
+    let mut bytes = vec![];
+    response
+        .into_reader()
+        .take(MAX_ASSET_BYTES + 1)
+        .read_to_end(&mut bytes)?;
+
+    if bytes.len() as u64 > MAX_ASSET_BYTES {
+        anyhow::bail!("{link} is too big to cache");
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(path, bytes)?;
+
+    Ok(())
+}
+
+/// A file name that is stable for `link`, keeping its extension so the file opens as an image
+fn file_name(link: &str) -> String {
+    // FNV-1a, as std's hashers may change between Rust versions
+    let hash = link.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    let extension = url::Url::parse(link)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()?
+                .next_back()?
+                .rsplit_once('.')
+                .map(|(_, extension)| extension.to_ascii_lowercase())
+        })
+        .filter(|extension| {
+            !extension.is_empty()
+                && extension.len() <= 5
+                && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+
+    match extension {
+        Some(extension) => format!("{hash:016x}.{extension}"),
+        None => format!("{hash:016x}"),
+    }
+}
+
+/// The `src` of every `img` in `html`, resolved against `base_link` if they are relative
+fn image_links(html: &str, base_link: Option<&str>) -> Vec<String> {
+    let base_url = base_link.and_then(|link| url::Url::parse(link).ok());
+
+    let mut links = vec![];
+
+    for tag in crate::util::tags(html) {
+        if tag.name != "img" {
+            continue;
+        }
+
+        let Some(src) = tag.attributes().remove("src") else {
+            continue;
+        };
+
+        let url = match &base_url {
+            Some(base_url) => base_url.join(&src),
+            None => url::Url::parse(&src),
+        };
+
+        if let Ok(url) = url {
+            if matches!(url.scheme(), "http" | "https") && !links.contains(&url.to_string()) {
+                links.push(url.to_string());
+            }
+        }
+    }
+
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_image_links() {
+        let html = r#"<p>Hi</p><IMG alt="a" SRC="/a.png"><img src='https://cdn.example.com/b.jpg?w=1&amp;h=2'/>
+            <img src=c.gif><img alt="no source"><img src="data:image/png;base64,AAAA">"#;
+
+        assert_eq!(
+            image_links(html, Some("https://example.com/posts/1")),
+            vec![
+                "https://example.com/a.png",
+                "https://cdn.example.com/b.jpg?w=1&h=2",
+                "https://example.com/posts/c.gif",
+            ]
+        );
+
+        assert_eq!(
+            image_links(html, None),
+            vec!["https://cdn.example.com/b.jpg?w=1&h=2"]
+        );
+
+        assert!(file_name("https://example.com/a.PNG").ends_with(".png"));
+        assert_eq!(
+            file_name("https://example.com/a"),
+            file_name("https://example.com/a")
+        );
+    }
+
+    #[test]
+    fn it_prunes_assets_of_deleted_entries_and_oldest_entries() {
+        let dir = crate::util::TestDir::new("assets");
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO feeds (id, title) VALUES (1, 'feed');
+            INSERT INTO entries (id, feed_id) VALUES (1, 1), (2, 1);",
+        )
+        .unwrap();
+
+        for entry_id in [1, 2, 3] {
+            let entry_dir = entry_dir(&dir, entry_id.into());
+            std::fs::create_dir_all(&entry_dir).unwrap();
+            std::fs::write(entry_dir.join("image.png"), [0; 100]).unwrap();
+            // so entry 1 was cached longest ago
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        let options = AssetCacheOptions {
+            dir: dir.to_path_buf(),
+            max_bytes: 150,
+        };
+
+        assert_eq!(
+            prune(&conn, &options).unwrap(),
+            Pruned {
+                entries: 2,
+                bytes: 200
+            }
+        );
+        assert!(cached_assets(&dir, 1.into()).is_empty());
+        assert_eq!(cached_assets(&dir, 2.into()).len(), 1);
+        assert!(cached_assets(&dir, 3.into()).is_empty());
+    }
+}
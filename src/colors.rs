@@ -0,0 +1,269 @@
+//! Fitting the UI's colors to what the terminal can show.
+//!
+//! The UI is drawn in full color, and then each frame is adapted cell by cell,
+//! so drawing code never has to think about what the terminal supports.
+//! With no color at all, like with `NO_COLOR` set or with `--monochrome`,
+//! colors become bold, underlined, or dim text.
+
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::style::{Color, Modifier};
+
+/// The colors of the parts of the UI that can be changed,
+/// with `--highlight-color`, `--title-color`, `--border-color`, and `--error-color`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    /// the selected feed or entry, and the reading progress gauge
+    pub highlight: Color,
+    /// the titles of panes and popups
+    pub title: Color,
+    pub border: Color,
+    /// the error pane's title and border
+    pub error: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            highlight: Color::Rgb(255, 150, 167),
+            title: Color::Cyan,
+            border: Color::Reset,
+            error: Color::Red,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorSupport {
+    NoColor,
+    /// the 16 standard ANSI colors
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// From `NO_COLOR` (see <https://no-color.org>), `TERM`, `COLORTERM`,
+    /// and failing those, what terminfo says about the terminal
+    pub fn detect() -> Self {
+        let env = |name| std::env::var(name).ok();
+
+        Self::from_env(
+            env("NO_COLOR").as_deref(),
+            env("TERM").as_deref(),
+            env("COLORTERM").as_deref(),
+            terminfo_colors,
+        )
+    }
+
+    fn from_env(
+        no_color: Option<&str>,
+        term: Option<&str>,
+        colorterm: Option<&str>,
+        terminfo_colors: impl FnOnce() -> Option<i32>,
+    ) -> Self {
+        if no_color.is_some_and(|no_color| !no_color.is_empty()) || term == Some("dumb") {
+            return ColorSupport::NoColor;
+        }
+
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorSupport::TrueColor;
+        }
+
+        match terminfo_colors() {
+            Some(colors) if colors >= 256 => ColorSupport::Ansi256,
+            Some(colors) if colors >= 8 => ColorSupport::Basic,
+            Some(_) => ColorSupport::NoColor,
+            None if term.is_some_and(|term| term.contains("256color")) => ColorSupport::Ansi256,
+            // most terminals that do not say otherwise have at least these
+            None => ColorSupport::Basic,
+        }
+    }
+
+    /// Changes every cell of a drawn frame to colors the terminal can show
+    pub fn adapt(self, buffer: &mut Buffer) {
+        if self == ColorSupport::TrueColor {
+            return;
+        }
+
+        for cell in buffer.content.iter_mut() {
+            self.adapt_cell(cell);
+        }
+    }
+
+    fn adapt_cell(self, cell: &mut Cell) {
+        match self {
+            ColorSupport::NoColor => {
+                let modifier = without_color(cell.fg);
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+                cell.modifier |= modifier;
+            }
+            ColorSupport::Basic => {
+                cell.fg = to_basic(cell.fg);
+                cell.bg = to_basic(cell.bg);
+            }
+            ColorSupport::Ansi256 => {
+                cell.fg = to_ansi256(cell.fg);
+                cell.bg = to_ansi256(cell.bg);
+            }
+            ColorSupport::TrueColor => (),
+        }
+    }
+}
+
+/// How many colors terminfo says the terminal has, from `tput colors`
+fn terminfo_colors() -> Option<i32> {
+    let output = std::process::Command::new("tput")
+        .arg("colors")
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// What stands in for a foreground color without color:
+/// greys recede, the accent color stands out most, and other colors stand out
+fn without_color(color: Color) -> Modifier {
+    match color {
+        Color::Reset | Color::White | Color::Black => Modifier::empty(),
+        Color::DarkGray | Color::Gray => Modifier::DIM,
+        Color::Rgb(..) | Color::Indexed(_) => Modifier::BOLD | Modifier::UNDERLINED,
+        _ => Modifier::BOLD,
+    }
+}
+
+/// The 16 standard colors, roughly as most terminals show them
+const BASIC_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn to_basic(color: Color) -> Color {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) => indexed_to_rgb(index),
+        color => return color,
+    };
+
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+        d(r, r2) + d(g, g2) + d(b, b2)
+    };
+
+    let is_gray = |(r, g, b): (u8, u8, u8)| r.max(g).max(b) - r.min(g).min(b) < 40;
+
+    // only near-grays become grays, so colors like pastels keep their hue
+    BASIC_COLORS
+        .iter()
+        .filter(|(_, rgb)| is_gray(*rgb) == is_gray((r, g, b)))
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+/// The levels of each channel in the 6x6x6 color cube of the 256 colors
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn to_ansi256(color: Color) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => {
+            let level = |channel: u8| {
+                CUBE_LEVELS
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, level)| (i32::from(**level) - i32::from(channel)).abs())
+                    .map(|(i, _)| i as u8)
+                    .unwrap_or(0)
+            };
+
+            Color::Indexed(16 + 36 * level(r) + 6 * level(g) + level(b))
+        }
+        color => color,
+    }
+}
+
+fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC_COLORS[usize::from(index)].1,
+        16..=231 => {
+            let index = index - 16;
+            (
+                CUBE_LEVELS[usize::from(index / 36)],
+                CUBE_LEVELS[usize::from(index / 6 % 6)],
+                CUBE_LEVELS[usize::from(index % 6)],
+            )
+        }
+        // the grayscale ramp
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_detects_color_support() {
+        let no_terminfo = || None;
+
+        assert_eq!(
+            ColorSupport::from_env(Some("1"), Some("xterm-256color"), None, no_terminfo),
+            ColorSupport::NoColor
+        );
+        // an empty NO_COLOR does not count
+        assert_eq!(
+            ColorSupport::from_env(Some(""), Some("xterm-256color"), None, no_terminfo),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, Some("xterm"), Some("truecolor"), no_terminfo),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, Some("vt100"), None, || Some(-1)),
+            ColorSupport::NoColor
+        );
+        assert_eq!(
+            ColorSupport::from_env(None, Some("xterm"), None, || Some(8)),
+            ColorSupport::Basic
+        );
+    }
+
+    #[test]
+    fn it_adapts_colors_to_the_terminal() {
+        let pink = Color::Rgb(255, 150, 167);
+
+        assert_eq!(to_ansi256(pink), Color::Indexed(211));
+        assert_eq!(to_basic(pink), Color::Magenta);
+        assert_eq!(to_basic(Color::Rgb(120, 120, 130)), Color::DarkGray);
+        assert_eq!(to_basic(Color::Cyan), Color::Cyan);
+
+        let mut cell = Cell::default();
+        cell.set_fg(Color::DarkGray);
+        ColorSupport::NoColor.adapt_cell(&mut cell);
+        assert_eq!(cell.fg, Color::Reset);
+        assert!(cell.modifier.contains(Modifier::DIM));
+    }
+}
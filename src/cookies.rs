@@ -0,0 +1,317 @@
+//! A cookie jar for feeds that need cookies, like ones behind a login page or a bot check.
+//!
+//! Cookies are kept in a Netscape `cookies.txt` file, the format curl and browser extensions
+//! export, so a logged in session can be copied over from a browser.
+//! Cookies that feeds set are saved back to the file, so sessions carry over between runs.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Cookie {
+    /// without a leading `.`
+    domain: String,
+    /// whether subdomains of `domain` get the cookie too, rather than only `domain` itself
+    include_subdomains: bool,
+    path: String,
+    secure: bool,
+    http_only: bool,
+    /// as a unix timestamp. 0 is a session cookie, which never expires here
+    expires: i64,
+    name: String,
+    value: String,
+}
+
+impl Cookie {
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires != 0 && self.expires <= now
+    }
+
+    fn matches(&self, url: &url::Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+
+        let domain_matches = host == self.domain
+            || (self.include_subdomains && host.ends_with(&format!(".{}", self.domain)));
+
+        domain_matches
+            && url.path().starts_with(&self.path)
+            && (!self.secure || url.scheme() == "https")
+    }
+
+    /// Parses a line of a `cookies.txt` file,
+    /// which is `None` for comments, blank lines, and lines that are not cookies
+    fn from_line(line: &str) -> Option<Cookie> {
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(line) => (line, true),
+            None if line.starts_with('#') => return None,
+            None => (line, false),
+        };
+
+        let fields = line
+            .trim_end_matches(['\r', '\n'])
+            .split('\t')
+            .collect::<Vec<_>>();
+
+        let [domain, include_subdomains, path, secure, expires, name, value] = fields[..] else {
+            return None;
+        };
+
+        Some(Cookie {
+            domain: domain.trim_start_matches('.').to_lowercase(),
+            include_subdomains: include_subdomains.eq_ignore_ascii_case("TRUE"),
+            path: path.to_owned(),
+            secure: secure.eq_ignore_ascii_case("TRUE"),
+            http_only,
+            expires: expires.parse().ok()?,
+            name: name.to_owned(),
+            value: value.to_owned(),
+        })
+    }
+
+    fn to_line(&self) -> String {
+        let bool_field = |b: bool| if b { "TRUE" } else { "FALSE" };
+
+        format!(
+            "{}{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            if self.http_only { "#HttpOnly_" } else { "" },
+            if self.include_subdomains { "." } else { "" },
+            self.domain,
+            bool_field(self.include_subdomains),
+            self.path,
+            bool_field(self.secure),
+            self.expires,
+            self.name,
+            self.value
+        )
+    }
+
+    /// Parses a `Set-Cookie` header `url` responded with
+    fn from_set_cookie(url: &url::Url, header: &str, now: i64) -> Option<Cookie> {
+        let mut parts = header.split(';').map(str::trim);
+
+        let (name, value) = parts.next()?.split_once('=')?;
+
+        let mut cookie = Cookie {
+            domain: url.host_str()?.to_lowercase(),
+            include_subdomains: false,
+            path: "/".to_string(),
+            secure: false,
+            http_only: false,
+            expires: 0,
+            name: name.trim().to_owned(),
+            value: value.trim().to_owned(),
+        };
+
+        let mut max_age = None;
+
+        for attribute in parts {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+
+            match key.to_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    cookie.domain = value.trim_start_matches('.').to_lowercase();
+                    cookie.include_subdomains = true;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_owned(),
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "expires" => {
+                    if let Ok(expires) = chrono::DateTime::parse_from_rfc2822(value) {
+                        cookie.expires = expires.timestamp();
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        // sites can only set cookies for their own domain
+        let host = url.host_str()?;
+        if host != cookie.domain && !host.ends_with(&format!(".{}", cookie.domain)) {
+            return None;
+        }
+
+        // Max-Age wins over Expires, and 0 or less means the cookie is deleted
+        if let Some(max_age) = max_age {
+            cookie.expires = if max_age <= 0 { 1 } else { now + max_age };
+        }
+
+        Some(cookie)
+    }
+}
+
+#[derive(Debug)]
+pub struct CookieJar {
+    path: PathBuf,
+    cookies: Mutex<Vec<Cookie>>,
+}
+
+impl CookieJar {
+    /// Opens the cookie jar at `path`, which starts out empty if there is no file there yet
+    pub fn open(path: &Path) -> Result<CookieJar> {
+        let cookies = match std::fs::read_to_string(path) {
+            Ok(text) => text.lines().filter_map(Cookie::from_line).collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Unable to read cookie jar {}", path.display()))
+            }
+        };
+
+        Ok(CookieJar {
+            path: path.to_owned(),
+            cookies: Mutex::new(cookies),
+        })
+    }
+
+    /// The `Cookie` header to send with a request to `url`, if any cookies go with it
+    pub fn cookie_header(&self, url: &str) -> Option<String> {
+        let url = url::Url::parse(url).ok()?;
+        let now = chrono::Utc::now().timestamp();
+
+        let cookies = self.cookies.lock().unwrap();
+
+        let header = cookies
+            .iter()
+            .filter(|cookie| !cookie.is_expired(now) && cookie.matches(&url))
+            .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        Some(header).filter(|header| !header.is_empty())
+    }
+
+    /// Keeps the cookies `url` set in its response, saving the jar if anything changed
+    pub fn store(&self, url: &str, set_cookie_headers: &[&str]) -> Result<()> {
+        let Ok(url) = url::Url::parse(url) else {
+            return Ok(());
+        };
+        let now = chrono::Utc::now().timestamp();
+
+        let mut cookies = self.cookies.lock().unwrap();
+        let mut changed = false;
+
+        for header in set_cookie_headers {
+            let Some(cookie) = Cookie::from_set_cookie(&url, header, now) else {
+                continue;
+            };
+
+            let existing = cookies.iter().position(|existing| {
+                existing.domain == cookie.domain
+                    && existing.path == cookie.path
+                    && existing.name == cookie.name
+            });
+
+            match existing {
+                Some(i) if cookies[i] == cookie => continue,
+                Some(i) => {
+                    cookies[i] = cookie;
+                }
+                None => cookies.push(cookie),
+            }
+
+            changed = true;
+        }
+
+        if changed {
+            cookies.retain(|cookie| !cookie.is_expired(now));
+            self.save(&cookies)?;
+        }
+
+        Ok(())
+    }
+
+    fn save(&self, cookies: &[Cookie]) -> Result<()> {
+        let mut text = String::from("# Netscape HTTP Cookie File\n");
+
+        for cookie in cookies {
+            text.push_str(&cookie.to_line());
+            text.push('\n');
+        }
+
+        std::fs::write(&self.path, text)
+            .with_context(|| format!("Unable to save cookie jar {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_sends_and_keeps_cookies() {
+        let dir = crate::util::TestDir::new("cookies");
+        let path = dir.join("cookies.txt");
+
+        std::fs::write(
+            &path,
+            "# Netscape HTTP Cookie File\n\
+            .example.com\tTRUE\t/\tTRUE\t0\tsession\tabc\n\
+            #HttpOnly_intranet.example.com\tFALSE\t/feeds\tFALSE\t0\tauth\txyz\n\
+            old.example.com\tFALSE\t/\tFALSE\t1\texpired\tgone\n",
+        )
+        .unwrap();
+
+        let cookie_jar = CookieJar::open(&path).unwrap();
+
+        assert_eq!(
+            cookie_jar
+                .cookie_header("https://intranet.example.com/feeds/rss.xml")
+                .as_deref(),
+            Some("session=abc; auth=xyz")
+        );
+        // the session cookie is secure, and auth is only for /feeds
+        assert_eq!(
+            cookie_jar.cookie_header("http://intranet.example.com/feeds/rss.xml"),
+            Some("auth=xyz".to_string())
+        );
+        assert_eq!(
+            cookie_jar.cookie_header("https://intranet.example.com/other"),
+            Some("session=abc".to_string())
+        );
+        assert_eq!(
+            cookie_jar.cookie_header("https://old.example.com/"),
+            Some("session=abc".to_string())
+        );
+        assert_eq!(cookie_jar.cookie_header("https://example.org/"), None);
+
+        cookie_jar
+            .store(
+                "https://blog.example.org/feed",
+                &[
+                    "challenge=passed; Path=/; Max-Age=3600; HttpOnly",
+                    "stolen=yes; Domain=example.com",
+                ],
+            )
+            .unwrap();
+        cookie_jar
+            .store(
+                "https://intranet.example.com/feeds/rss.xml",
+                &["session=new; Domain=.example.com; Secure"],
+            )
+            .unwrap();
+
+        let cookie_jar = CookieJar::open(&path).unwrap();
+        assert_eq!(
+            cookie_jar
+                .cookie_header("https://blog.example.org/feed")
+                .as_deref(),
+            Some("challenge=passed")
+        );
+        assert_eq!(
+            cookie_jar
+                .cookie_header("https://intranet.example.com/feeds/")
+                .as_deref(),
+            Some("session=new; auth=xyz")
+        );
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("expired"));
+
+        assert!(CookieJar::open(&dir.join("missing.txt"))
+            .unwrap()
+            .cookie_header("https://example.com/")
+            .is_none());
+    }
+}
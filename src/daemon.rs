@@ -0,0 +1,377 @@
+//! `russ daemon` refreshes feeds on a schedule, without a TUI.
+//!
+//! TUI sessions attach to a running daemon over a Unix socket that lives next to
+//! the feeds database. While attached, they hand their refreshes off to the daemon
+//! and reload whenever the daemon finishes a refresh, so any number of TUI sessions
+//! share one fetcher.
+//!
+//! The protocol is line-based text:
+//!
+//! ```text
+//! > REFRESH [feed_id ...]     refresh the given feeds, or all feeds that are not dead if none are given
+//! < ERR <message>             zero or more, one per feed that failed to refresh
+//! < OK <succeeded> <total>
+//!
+//! > WATCH                     subscribe to refresh notifications
+//! < REFRESHED                 sent after every completed refresh
+//! ```
+//!
+//! A request that cannot be understood, or a refresh that cannot start,
+//! gets an `ERR` saying why, followed by `OK 0 0`,
+//! so every request but `WATCH` ends with an `OK`.
+
+use crate::rss::FeedId;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// The socket lives next to the database, so a daemon and a TUI
+/// that were given the same database path always find each other.
+pub(crate) fn socket_path(database_path: &Path) -> PathBuf {
+    database_path.with_extension("sock")
+}
+
+#[cfg(unix)]
+pub(crate) fn run(options: crate::DaemonOptions) -> Result<()> {
+    use std::sync::{Arc, Mutex};
+
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::tune_connection(&conn, &options.database_options)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let connection_pool = crate::io::connection_pool(
+        &options.database_path,
+        &options.database_options,
+        &options.ingest_options,
+    )?;
+
+    let http_client = ureq::AgentBuilder::new()
+        .timeout_read(options.network_timeout)
+        .user_agent("russ/0.5.0")
+        .build();
+
+    let socket_path = socket_path(&options.database_path);
+    let listener = bind(&socket_path)?;
+
+    let metrics = Arc::new(Mutex::new(crate::metrics::Metrics::default()));
+
+    if let Some(metrics_address) = options.metrics_address {
+        let metrics = Arc::clone(&metrics);
+        let connection_pool = connection_pool.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = crate::metrics::serve(metrics_address, metrics, connection_pool) {
+                eprintln!("metrics endpoint failed: {e:#}");
+            }
+        });
+        eprintln!("serving metrics on http://{metrics_address}/metrics");
+    }
+
+    let daemon = Arc::new(Daemon {
+        connection_pool,
+        http_client,
+        ingest_options: options.ingest_options.clone(),
+        watchers: Mutex::new(vec![]),
+        refresh_lock: Mutex::new(()),
+        metrics,
+    });
+
+    {
+        let daemon = Arc::clone(&daemon);
+        std::thread::spawn(move || loop {
+            if let Err(e) = daemon.refresh(&[], |_| ()) {
+                eprintln!("scheduled refresh failed: {e:#}");
+            }
+            std::thread::sleep(options.refresh_interval);
+        });
+    }
+
+    eprintln!("russ daemon listening on {}", socket_path.display());
+
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let daemon = Arc::clone(&daemon);
+        std::thread::spawn(move || {
+            if let Err(e) = daemon.serve(stream) {
+                eprintln!("client connection failed: {e:#}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn run(_options: crate::DaemonOptions) -> Result<()> {
+    anyhow::bail!("russ daemon is only supported on Unix platforms")
+}
+
+/// Binds the daemon socket, cleaning up a stale socket file
+/// left behind by a daemon that did not exit cleanly.
+#[cfg(unix)]
+fn bind(socket_path: &Path) -> Result<std::os::unix::net::UnixListener> {
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if socket_path.exists() {
+        if UnixStream::connect(socket_path).is_ok() {
+            anyhow::bail!(
+                "a russ daemon is already running on {}",
+                socket_path.display()
+            );
+        }
+
+        std::fs::remove_file(socket_path)?;
+    }
+
+    Ok(UnixListener::bind(socket_path)?)
+}
+
+#[cfg(unix)]
+struct Daemon {
+    connection_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+    http_client: ureq::Agent,
+    ingest_options: crate::rss::IngestOptions,
+    watchers: std::sync::Mutex<Vec<std::os::unix::net::UnixStream>>,
+    /// scheduled and client-requested refreshes take turns rather than racing each other
+    refresh_lock: std::sync::Mutex<()>,
+    metrics: std::sync::Arc<std::sync::Mutex<crate::metrics::Metrics>>,
+}
+
+#[cfg(unix)]
+impl Daemon {
+    /// Refreshes `feed_ids`, or all feeds that are not dead if `feed_ids` is empty,
+    /// and then notifies all watching clients.
+    fn refresh<F>(&self, feed_ids: &[FeedId], mut refresh_result_handler: F) -> Result<()>
+    where
+        F: FnMut(Result<()>),
+    {
+        let _refresh_guard = self.refresh_lock.lock().unwrap();
+
+        let now = std::time::Instant::now();
+
+        let feed_ids = if feed_ids.is_empty() {
+            crate::rss::get_refreshable_feed_ids(&*self.connection_pool.get()?)?
+        } else {
+            feed_ids.to_vec()
+        };
+
+        let mut successfully_refreshed_len = 0usize;
+
+        crate::io::refresh_feeds(
+            &self.http_client,
+            &self.connection_pool,
+            &feed_ids,
+            &self.ingest_options,
+            |fetch_result| {
+                if fetch_result.is_ok() {
+                    successfully_refreshed_len += 1;
+                }
+                refresh_result_handler(fetch_result)
+            },
+        )?;
+
+        let elapsed = now.elapsed();
+
+        eprintln!(
+            "refreshed {successfully_refreshed_len}/{} feeds in {elapsed:?}",
+            feed_ids.len(),
+        );
+
+        self.metrics.lock().unwrap().record_refresh(
+            successfully_refreshed_len,
+            feed_ids.len() - successfully_refreshed_len,
+            elapsed,
+        );
+
+        self.notify_watchers();
+
+        Ok(())
+    }
+
+    fn notify_watchers(&self) {
+        use std::io::Write;
+
+        let mut watchers = self.watchers.lock().unwrap();
+        // clients that have gone away are dropped
+        watchers.retain_mut(|watcher| writeln!(watcher, "REFRESHED").is_ok());
+    }
+
+    fn serve(&self, stream: std::os::unix::net::UnixStream) -> Result<()> {
+        use std::io::{BufRead, BufReader, Write};
+
+        let mut writer = stream.try_clone()?;
+        let reader = BufReader::new(stream);
+
+        for line in reader.lines() {
+            let line = line?;
+            let mut words = line.split_whitespace();
+
+            match words.next() {
+                Some("REFRESH") => {
+                    let feed_ids = words
+                        .map(|word| word.parse::<i64>().map(FeedId::from))
+                        .collect::<Result<Vec<_>, _>>();
+
+                    let feed_ids = match feed_ids {
+                        Ok(feed_ids) => feed_ids,
+                        Err(e) => {
+                            writeln!(writer, "ERR invalid feed id: {e}")?;
+                            writeln!(writer, "OK 0 0")?;
+                            continue;
+                        }
+                    };
+
+                    let mut errors = vec![];
+                    let mut total = 0usize;
+
+                    let refreshed = self.refresh(&feed_ids, |fetch_result| {
+                        total += 1;
+                        if let Err(e) = fetch_result {
+                            errors.push(e);
+                        }
+                    });
+
+                    if let Err(e) = refreshed {
+                        writeln!(writer, "ERR {}", format!("{e:#}").replace('\n', " "))?;
+                        writeln!(writer, "OK 0 0")?;
+                        continue;
+                    }
+
+                    for e in &errors {
+                        // errors have to fit on one line
+                        writeln!(writer, "ERR {}", format!("{e:#}").replace('\n', " "))?;
+                    }
+
+                    writeln!(writer, "OK {} {total}", total - errors.len())?;
+                }
+                Some("WATCH") => {
+                    self.watchers.lock().unwrap().push(writer);
+                    return Ok(());
+                }
+                _ => {
+                    writeln!(writer, "ERR unknown command: {line}")?;
+                    writeln!(writer, "OK 0 0")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A TUI session's connection to a running daemon.
+#[cfg(unix)]
+pub(crate) struct Client {
+    reader: std::io::BufReader<std::os::unix::net::UnixStream>,
+    writer: std::os::unix::net::UnixStream,
+}
+
+#[cfg(not(unix))]
+pub(crate) enum Client {}
+
+impl Client {
+    /// Connects to the daemon for `database_path`,
+    /// or returns `None` if no daemon is running.
+    #[cfg(unix)]
+    pub(crate) fn connect(database_path: &Path) -> Option<Client> {
+        let stream = std::os::unix::net::UnixStream::connect(socket_path(database_path)).ok()?;
+        let writer = stream.try_clone().ok()?;
+
+        Some(Client {
+            reader: std::io::BufReader::new(stream),
+            writer,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn connect(_database_path: &Path) -> Option<Client> {
+        None
+    }
+
+    /// Asks the daemon to refresh `feed_ids`, calling `refresh_result_handler`
+    /// once for each feed, like `io::refresh_feeds`.
+    #[cfg(unix)]
+    pub(crate) fn refresh_feeds<F>(
+        &mut self,
+        feed_ids: &[FeedId],
+        mut refresh_result_handler: F,
+    ) -> Result<()>
+    where
+        F: FnMut(Result<()>),
+    {
+        use std::io::{BufRead, Write};
+
+        let mut request = String::from("REFRESH");
+        for feed_id in feed_ids {
+            request.push(' ');
+            request.push_str(&feed_id.to_string());
+        }
+        writeln!(self.writer, "{request}")?;
+
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+
+            if self.reader.read_line(&mut line)? == 0 {
+                anyhow::bail!("the russ daemon closed the connection");
+            }
+
+            let line = line.trim_end();
+
+            if let Some(message) = line.strip_prefix("ERR ") {
+                refresh_result_handler(Err(anyhow::anyhow!(message.to_owned())));
+            } else if let Some(counts) = line.strip_prefix("OK ") {
+                let succeeded = counts
+                    .split_whitespace()
+                    .next()
+                    .and_then(|succeeded| succeeded.parse::<usize>().ok())
+                    .unwrap_or(0);
+
+                for _ in 0..succeeded {
+                    refresh_result_handler(Ok(()));
+                }
+
+                return Ok(());
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn refresh_feeds<F>(
+        &mut self,
+        _feed_ids: &[FeedId],
+        _refresh_result_handler: F,
+    ) -> Result<()>
+    where
+        F: FnMut(Result<()>),
+    {
+        match *self {}
+    }
+}
+
+/// Blocks, calling `on_refreshed` every time the daemon for `database_path` finishes a refresh.
+#[cfg(unix)]
+pub(crate) fn watch<F>(database_path: &Path, mut on_refreshed: F) -> Result<()>
+where
+    F: FnMut() -> Result<()>,
+{
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut stream = std::os::unix::net::UnixStream::connect(socket_path(database_path))?;
+    writeln!(stream, "WATCH")?;
+
+    for line in BufReader::new(stream).lines() {
+        if line? == "REFRESHED" {
+            on_refreshed()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn watch<F>(_database_path: &Path, _on_refreshed: F) -> Result<()>
+where
+    F: FnMut() -> Result<()>,
+{
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! Maintenance commands for the feeds database, under `russ db`,
+//! and commands for feeds' settings, under `russ feed`
+
+use crate::{
+    BackfillGuidsOptions, RecompressOptions, SetMarkReadAfterOptions, SetMaxNewEntriesOptions,
+    SetRetentionOptions, SlowestFeedsOptions,
+};
+use anyhow::Result;
+
+pub(crate) fn recompress(options: RecompressOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let changed = crate::rss::recompress_entries(&mut conn, options.compress)?;
+
+    // sqlite keeps the space freed by compressing for reuse, rather than giving it back
+    if changed > 0 {
+        conn.execute("VACUUM", [])?;
+    }
+
+    eprintln!(
+        "{} {changed} entries in {}",
+        if options.compress {
+            "compressed"
+        } else {
+            "decompressed"
+        },
+        options.database_path.display()
+    );
+
+    Ok(())
+}
+
+pub(crate) fn backfill_guids(options: BackfillGuidsOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let http_client = ureq::AgentBuilder::new()
+        .timeout_read(options.network_timeout)
+        .user_agent("russ/0.5.0")
+        .build();
+
+    let mut matched = 0;
+    let mut missing = 0;
+
+    for feed_id in crate::rss::get_feed_ids(&conn)? {
+        match crate::rss::backfill_guids(&http_client, &mut conn, feed_id, &options.ingest_options)
+        {
+            Ok(backfill) => {
+                matched += backfill.matched;
+                missing += backfill.missing;
+            }
+            Err(e) => eprintln!("skipping feed {feed_id}: {e:#}"),
+        }
+    }
+
+    eprintln!("found guids for {matched} entries, {missing} entries are still without one");
+
+    Ok(())
+}
+
+pub(crate) fn set_max_new_entries(options: SetMaxNewEntriesOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    crate::rss::set_feed_max_new_entries(&conn, &options.feed_link, options.max_new_entries)?;
+
+    match options.max_new_entries {
+        Some(max_new_entries) => eprintln!(
+            "refreshing {} adds at most {max_new_entries} new entries",
+            options.feed_link
+        ),
+        None => eprintln!(
+            "refreshing {} adds at most --max-new-entries new entries, if it is set",
+            options.feed_link
+        ),
+    }
+
+    Ok(())
+}
+
+pub(crate) fn set_mark_read_after(options: SetMarkReadAfterOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    crate::rss::set_feed_mark_read_after_days(&conn, &options.feed_link, options.days)?;
+
+    match options.days {
+        Some(days) => eprintln!(
+            "refreshing {} marks its entries older than {days} days as read",
+            options.feed_link
+        ),
+        None => eprintln!(
+            "refreshing {} no longer marks old entries as read",
+            options.feed_link
+        ),
+    }
+
+    Ok(())
+}
+
+pub(crate) fn set_retention(options: SetRetentionOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    crate::rss::set_feed_retention(&conn, &options.feed_link, options.retention)?;
+
+    match options.retention {
+        Some(crate::rss::Retention::Entries(entries)) => eprintln!(
+            "refreshing {} keeps its newest {entries} entries, and every unread or starred one",
+            options.feed_link
+        ),
+        Some(crate::rss::Retention::Days(days)) => eprintln!(
+            "refreshing {} keeps its entries from the last {days} days, and every unread or starred one",
+            options.feed_link
+        ),
+        None => eprintln!(
+            "refreshing {} keeps entries as --retention says, if it is set",
+            options.feed_link
+        ),
+    }
+
+    Ok(())
+}
+
+pub(crate) fn slowest_feeds(options: SlowestFeedsOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let slowest_feeds = crate::rss::get_slowest_feeds(&conn, options.limit)?;
+
+    if slowest_feeds.is_empty() {
+        eprintln!("no feeds have been refreshed yet");
+        return Ok(());
+    }
+
+    println!("average\tslowest\tfetches\tfailures\tfeed");
+
+    for feed in slowest_feeds {
+        println!(
+            "{:.1}s\t{:.1}s\t{}\t{}\t{}",
+            feed.average.as_secs_f64(),
+            feed.slowest.as_secs_f64(),
+            feed.fetches,
+            feed.failures,
+            feed.name
+        );
+    }
+
+    Ok(())
+}
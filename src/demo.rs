@@ -0,0 +1,180 @@
+//! `russ demo`: the reader, offline, with an in-memory database of a few sample feeds,
+//! for trying russ out, and for screenshots, without the network or your own feeds.
+//! The sample feeds are bundled, like the feed directory.
+
+use anyhow::{Context, Result};
+use clap::Parser;
+
+struct SampleFeed {
+    feed_link: &'static str,
+    content: &'static str,
+}
+
+const SAMPLE_FEEDS: &[SampleFeed] = &[
+    SampleFeed {
+        feed_link: "https://russ.example/releases.xml",
+        content: r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Russ Releases</title>
+    <link>https://russ.example/</link>
+    <description>New versions of russ</description>
+    <item>
+      <title>Keys you can press anywhere</title>
+      <link>https://russ.example/releases/keys</link>
+      <guid>https://russ.example/releases/keys</guid>
+      <pubDate>Mon, 05 Oct 2026 09:00:00 GMT</pubDate>
+      <description><![CDATA[<p>Press <code>?</code> to see every key, and what it does where you are.</p>
+<p>Move between feeds and entries with <code>h</code>, <code>j</code>, <code>k</code>, and <code>l</code>, and read an entry with <code>Enter</code>.</p>]]></description>
+    </item>
+    <item>
+      <title>Reading offline</title>
+      <link>https://russ.example/releases/offline</link>
+      <guid>https://russ.example/releases/offline</guid>
+      <pubDate>Tue, 22 Sep 2026 09:00:00 GMT</pubDate>
+      <description><![CDATA[<p>russ keeps every entry it fetches in a local database, so everything you have already fetched can be read without the network.</p>
+<p>This demo is offline the whole time: refreshing feeds is turned off, and nothing you do here touches your own database.</p>]]></description>
+    </item>
+    <item>
+      <title>Marking entries read</title>
+      <link>https://russ.example/releases/read</link>
+      <guid>https://russ.example/releases/read</guid>
+      <pubDate>Thu, 03 Sep 2026 09:00:00 GMT</pubDate>
+      <description><![CDATA[<p>Press <code>r</code> to mark an entry read or unread, and <code>a</code> to show only unread entries, or all of them.</p>]]></description>
+    </item>
+  </channel>
+</rss>"#,
+    },
+    SampleFeed {
+        feed_link: "https://garden.example/atom.xml",
+        content: r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>A Small Garden</title>
+  <link href="https://garden.example/"/>
+  <id>https://garden.example/</id>
+  <updated>2026-10-12T18:30:00Z</updated>
+  <entry>
+    <title>Saving tomato seeds</title>
+    <link href="https://garden.example/tomato-seeds"/>
+    <id>https://garden.example/tomato-seeds</id>
+    <updated>2026-10-12T18:30:00Z</updated>
+    <author><name>Robin</name></author>
+    <content type="html"><![CDATA[<p>Scoop the seeds of the ripest tomato into a jar with a little water, and leave them for a few days.</p>
+<p>Once the pulp has fermented away, rinse the seeds, dry them on a plate, and keep them somewhere cool until spring.</p>]]></content>
+  </entry>
+  <entry>
+    <title>Putting the beds to sleep</title>
+    <link href="https://garden.example/beds"/>
+    <id>https://garden.example/beds</id>
+    <updated>2026-09-28T08:00:00Z</updated>
+    <author><name>Robin</name></author>
+    <content type="html"><![CDATA[<p>A layer of leaves over the empty beds keeps the soil from washing away over the winter, and feeds it by spring.</p>]]></content>
+  </entry>
+</feed>"#,
+    },
+    SampleFeed {
+        feed_link: "https://weekly.example/rss",
+        content: r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Terminal Weekly</title>
+    <link>https://weekly.example/</link>
+    <description>Things that run in a terminal</description>
+    <item>
+      <title>Issue 3: editors</title>
+      <link>https://weekly.example/3</link>
+      <guid>https://weekly.example/3</guid>
+      <pubDate>Fri, 09 Oct 2026 12:00:00 GMT</pubDate>
+      <description><![CDATA[<p>This week: modal editing, and why so many terminal programs borrow vim's keys.</p>]]></description>
+    </item>
+    <item>
+      <title>Issue 2: colors</title>
+      <link>https://weekly.example/2</link>
+      <guid>https://weekly.example/2</guid>
+      <pubDate>Fri, 02 Oct 2026 12:00:00 GMT</pubDate>
+      <description><![CDATA[<p>How terminals went from 8 colors to 16 million, and how programs find out which they can use.</p>]]></description>
+    </item>
+    <item>
+      <title>Issue 1: hello</title>
+      <link>https://weekly.example/1</link>
+      <guid>https://weekly.example/1</guid>
+      <pubDate>Fri, 25 Sep 2026 12:00:00 GMT</pubDate>
+      <description><![CDATA[<p>The first issue of a newsletter about programs that run in a terminal.</p>]]></description>
+    </item>
+  </channel>
+</rss>"#,
+    },
+];
+
+pub(crate) fn run() -> Result<()> {
+    // the reader's own defaults, offline, against an in-memory database
+    let options = crate::Options::try_parse_from([
+        "russ",
+        "read",
+        "--offline",
+        "--database-path",
+        crate::rss::IN_MEMORY_DATABASE_PATH,
+    ])?;
+    let crate::ValidatedOptions::Read(options) = options.subcommand.validate()? else {
+        unreachable!("russ read always validates to read options")
+    };
+
+    // the database lasts as long as a connection to it is open,
+    // so this one is kept open until the reader exits
+    let mut conn = crate::rss::open(&options.database_path, rusqlite::OpenFlags::default())?;
+    crate::rss::tune_connection(&conn, &options.database_options)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let http_client = ureq::AgentBuilder::new()
+        .timeout_read(options.network_timeout)
+        .user_agent("russ/0.5.0")
+        .build();
+
+    for sample_feed in SAMPLE_FEEDS {
+        crate::rss::ingest_feed(
+            &http_client,
+            &mut conn,
+            sample_feed.feed_link,
+            sample_feed.content,
+            &options.ingest_options,
+        )
+        .with_context(|| format!("unable to load the sample feed {}", sample_feed.feed_link))?;
+    }
+
+    crate::run_reader(options)?;
+
+    drop(conn);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_loads_every_sample_feed() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+
+        let http_client = ureq::AgentBuilder::new().build();
+
+        for sample_feed in SAMPLE_FEEDS {
+            let added = crate::rss::ingest_feed(
+                &http_client,
+                &mut conn,
+                sample_feed.feed_link,
+                sample_feed.content,
+                &crate::rss::IngestOptions::default(),
+            )
+            .unwrap();
+
+            assert!(added > 0, "{} has no entries", sample_feed.feed_link);
+        }
+
+        let feeds: usize = conn
+            .query_row("SELECT COUNT(*) FROM feeds", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(feeds, SAMPLE_FEEDS.len());
+    }
+}
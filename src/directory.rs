@@ -0,0 +1,231 @@
+//! A small directory of popular feeds that comes with russ,
+//! searchable from the add feed input with `Tab`, for finding something to read
+//! without knowing any feed links. It is bundled rather than fetched,
+//! so searching it works offline, like the rest of russ.
+
+#[derive(Debug)]
+pub struct DirectoryFeed {
+    pub title: &'static str,
+    pub feed_link: &'static str,
+    pub description: &'static str,
+}
+
+const fn feed(
+    title: &'static str,
+    feed_link: &'static str,
+    description: &'static str,
+) -> DirectoryFeed {
+    DirectoryFeed {
+        title,
+        feed_link,
+        description,
+    }
+}
+
+pub const DIRECTORY: &[DirectoryFeed] = &[
+    feed(
+        "Hacker News",
+        "https://news.ycombinator.com/rss",
+        "tech news links and discussion",
+    ),
+    feed(
+        "Lobsters",
+        "https://lobste.rs/rss",
+        "computing-focused links and discussion",
+    ),
+    feed(
+        "This Week in Rust",
+        "https://this-week-in-rust.org/rss.xml",
+        "weekly rust programming language newsletter",
+    ),
+    feed(
+        "Rust Blog",
+        "https://blog.rust-lang.org/feed.xml",
+        "rust programming language releases and announcements",
+    ),
+    feed(
+        "Inside Rust Blog",
+        "https://blog.rust-lang.org/inside-rust/feed.xml",
+        "rust programming language project and team updates",
+    ),
+    feed(
+        "The Go Blog",
+        "https://go.dev/blog/feed.atom",
+        "go programming language releases and articles",
+    ),
+    feed(
+        "LWN.net",
+        "https://lwn.net/headlines/rss",
+        "linux and free software news",
+    ),
+    feed(
+        "Phoronix",
+        "https://www.phoronix.com/rss.php",
+        "linux hardware, drivers, and benchmarks news",
+    ),
+    feed(
+        "Linux Kernel Releases",
+        "https://www.kernel.org/feeds/kdist.xml",
+        "linux kernel release announcements",
+    ),
+    feed(
+        "Ars Technica",
+        "https://feeds.arstechnica.com/arstechnica/index",
+        "technology, science, and policy news",
+    ),
+    feed(
+        "The Verge",
+        "https://www.theverge.com/rss/index.xml",
+        "technology and consumer electronics news",
+    ),
+    feed(
+        "Slashdot",
+        "https://rss.slashdot.org/Slashdot/slashdotMain",
+        "tech news links and discussion",
+    ),
+    feed(
+        "Hackaday",
+        "https://hackaday.com/blog/feed/",
+        "hardware hacking, electronics, and maker projects",
+    ),
+    feed(
+        "Julia Evans",
+        "https://jvns.ca/atom.xml",
+        "programming, linux, and networking explained, blog",
+    ),
+    feed(
+        "Dan Luu",
+        "https://danluu.com/atom.xml",
+        "programming, hardware, and the software industry, blog",
+    ),
+    feed(
+        "Simon Willison",
+        "https://simonwillison.net/atom/everything/",
+        "python, datasette, and AI tools, blog",
+    ),
+    feed(
+        "fasterthanlime",
+        "https://fasterthanli.me/index.xml",
+        "long-form rust and systems programming articles, blog",
+    ),
+    feed(
+        "matklad",
+        "https://matklad.github.io/feed.xml",
+        "rust, zig, and programming tools, blog",
+    ),
+    feed(
+        "Drew DeVault",
+        "https://drewdevault.com/blog/index.xml",
+        "free software and systems programming, blog",
+    ),
+    feed(
+        "Eli Bendersky",
+        "https://eli.thegreenplace.net/feeds/all.atom.xml",
+        "go, python, c++, and compilers, blog",
+    ),
+    feed(
+        "Brendan Gregg",
+        "https://www.brendangregg.com/blog/rss.xml",
+        "systems performance and observability, blog",
+    ),
+    feed(
+        "rachelbythebay",
+        "https://rachelbythebay.com/w/atom.xml",
+        "systems administration and production war stories, blog",
+    ),
+    feed(
+        "Martin Fowler",
+        "https://martinfowler.com/feed.atom",
+        "software design and architecture, blog",
+    ),
+    feed(
+        "The Old New Thing",
+        "https://devblogs.microsoft.com/oldnewthing/feed",
+        "windows history and programming, blog",
+    ),
+    feed(
+        "The GitHub Blog",
+        "https://github.blog/feed/",
+        "github product and engineering news",
+    ),
+    feed(
+        "The Cloudflare Blog",
+        "https://blog.cloudflare.com/rss/",
+        "networking, security, and infrastructure engineering",
+    ),
+    feed(
+        "Mozilla Hacks",
+        "https://hacks.mozilla.org/feed/",
+        "web platform and firefox development",
+    ),
+    feed(
+        "Netflix TechBlog",
+        "https://netflixtechblog.com/feed",
+        "distributed systems and infrastructure engineering",
+    ),
+    feed(
+        "Stack Overflow Blog",
+        "https://stackoverflow.blog/feed/",
+        "programming and the software industry",
+    ),
+    feed(
+        "Krebs on Security",
+        "https://krebsonsecurity.com/feed/",
+        "cybercrime and computer security news",
+    ),
+    feed(
+        "Schneier on Security",
+        "https://www.schneier.com/feed/atom/",
+        "security and cryptography, blog",
+    ),
+    feed(
+        "Daring Fireball",
+        "https://daringfireball.net/feeds/main",
+        "apple and technology commentary, blog",
+    ),
+    feed(
+        "xkcd",
+        "https://xkcd.com/atom.xml",
+        "webcomic of romance, sarcasm, math, and language",
+    ),
+];
+
+/// The feeds matching every word of `query` in their title, description, or feed link,
+/// ignoring case. Every feed matches an empty query.
+pub fn search(query: &str) -> Vec<&'static DirectoryFeed> {
+    let words = query
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>();
+
+    DIRECTORY
+        .iter()
+        .filter(|feed| {
+            let haystack =
+                format!("{} {} {}", feed.title, feed.description, feed.feed_link).to_lowercase();
+            words.iter().all(|word| haystack.contains(word.as_str()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_searches_the_directory() {
+        let titles = |query| {
+            search(query)
+                .into_iter()
+                .map(|feed| feed.title)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            titles("RUST blog"),
+            vec!["Rust Blog", "Inside Rust Blog", "fasterthanlime", "matklad"]
+        );
+        assert_eq!(titles("").len(), DIRECTORY.len());
+        assert!(titles("no such feed").is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+//! Finding feeds from a site's HTML, using the `<link rel="alternate">` tags
+//! that sites use to advertise their feeds.
+
+use std::collections::HashMap;
+
+const FEED_TYPES: &[&str] = &[
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+    "application/xml",
+    "text/xml",
+];
+
+/// Returns the absolute URLs of the feeds that `html` advertises, in the order they appear.
+/// Relative links are resolved against `base_url`, the URL `html` was fetched from.
+pub(crate) fn discover_feed_links(html: &str, base_url: &str) -> Vec<String> {
+    let base_url = url::Url::parse(base_url).ok();
+
+    let mut feed_links = vec![];
+
+    for attributes in link_tags(html) {
+        let is_alternate = attributes.get("rel").is_some_and(|rel| {
+            rel.split_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("alternate"))
+        });
+
+        let is_feed = attributes.get("type").is_some_and(|content_type| {
+            FEED_TYPES
+                .iter()
+                .any(|feed_type| content_type.trim().eq_ignore_ascii_case(feed_type))
+        });
+
+        if !is_alternate || !is_feed {
+            continue;
+        }
+
+        let Some(href) = attributes.get("href") else {
+            continue;
+        };
+
+        let feed_link = match &base_url {
+            Some(base_url) => base_url.join(href).map(|url| url.to_string()).ok(),
+            None => url::Url::parse(href).map(|url| url.to_string()).ok(),
+        };
+
+        if let Some(feed_link) = feed_link {
+            if !feed_links.contains(&feed_link) {
+                feed_links.push(feed_link);
+            }
+        }
+    }
+
+    feed_links
+}
+
+/// The attributes of every `<link>` tag in `html`, with lowercased names
+fn link_tags(html: &str) -> Vec<HashMap<String, String>> {
+    crate::util::tags(html)
+        .filter(|tag| tag.name == "link" && !tag.is_closing)
+        .map(|tag| tag.attributes())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_discovers_feed_links() {
+        let html = r#"<!doctype html>
+            <html>
+            <head>
+              <link rel="stylesheet" href="/style.css">
+              <LINK REL="alternate" TYPE="application/rss+xml" title="RSS" href="/feed.xml" />
+              <link type='application/atom+xml' rel='alternate' href='https://example.com/atom.xml?a=1&amp;b=2'>
+              <link rel=alternate type=application/rss+xml href=comments.xml>
+              <link rel="alternate" hreflang="fr" href="/fr/">
+            </head>
+            </html>"#;
+
+        assert_eq!(
+            discover_feed_links(html, "https://example.com/blog/"),
+            vec![
+                "https://example.com/feed.xml",
+                "https://example.com/atom.xml?a=1&b=2",
+                "https://example.com/blog/comments.xml",
+            ]
+        );
+    }
+}
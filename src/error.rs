@@ -0,0 +1,61 @@
+//! The ways fetching a feed can fail that callers handle differently,
+//! like marking a feed dead when its server says it is gone,
+//! or telling a server that cannot be reached right now from a page that is not a feed.
+//!
+//! Everything else is an `anyhow::Error`, with these somewhere in its chain of causes,
+//! so they are found with `downcast_ref`.
+
+/// Why fetching a feed over HTTP failed
+#[derive(Debug, thiserror::Error)]
+pub enum FetchError {
+    /// the server answered, with an error or a status russ does not handle
+    #[error("{url}: status code {status}")]
+    Status { url: String, status: u16 },
+    /// there was no answer, like when the server is down or the connection timed out
+    #[error("unable to reach {url}")]
+    Unreachable {
+        url: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// the server answered with a bot protection page rather than the feed
+    #[error("blocked by bot protection ({provider}) — consider a custom User-Agent or cookies from a browser with --cookie-jar")]
+    BlockedByBotProtection {
+        /// who is blocking the feed, like `Cloudflare`
+        provider: String,
+    },
+    /// the server answered with something that is not an RSS or Atom feed
+    #[error("{url} is not an RSS or Atom feed")]
+    NotAFeed {
+        url: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_says_what_caused_it() {
+        let e = anyhow::Error::from(FetchError::NotAFeed {
+            url: "https://example.com/feed".to_string(),
+            source: anyhow::anyhow!("unexpected end of file").into(),
+        })
+        .context("Unable to refresh feed 3");
+
+        assert_eq!(
+            e.chain().map(|cause| cause.to_string()).collect::<Vec<_>>(),
+            vec![
+                "Unable to refresh feed 3",
+                "https://example.com/feed is not an RSS or Atom feed",
+                "unexpected end of file"
+            ]
+        );
+        assert!(matches!(
+            e.downcast_ref::<FetchError>(),
+            Some(FetchError::NotAFeed { .. })
+        ));
+    }
+}
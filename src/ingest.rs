@@ -0,0 +1,48 @@
+//! Storing feed documents fetched by something other than russ, read from stdin,
+//! for feeds that need something russ cannot do to fetch them, like unusual auth or scraping
+
+use crate::IngestFeedOptions;
+use anyhow::{Context, Result};
+use std::io::Read;
+
+pub(crate) fn ingest(options: IngestFeedOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::tune_connection(&conn, &options.database_options)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let feed_link = match options.feed {
+        IngestFeed::Id(feed_id) => crate::rss::get_feed_url(&conn, feed_id)
+            .with_context(|| format!("no feed with id {feed_id}"))?,
+        IngestFeed::Link(feed_link) => feed_link,
+    };
+
+    let mut content = String::new();
+    std::io::stdin()
+        .read_to_string(&mut content)
+        .context("unable to read a feed from stdin")?;
+
+    let http_client = ureq::AgentBuilder::new()
+        .timeout_read(options.network_timeout)
+        .user_agent("russ/0.5.0")
+        .build();
+
+    let added = crate::rss::ingest_feed(
+        &http_client,
+        &mut conn,
+        &feed_link,
+        &content,
+        &options.ingest_options,
+    )?;
+
+    eprintln!("added {added} entries to {feed_link}");
+
+    Ok(())
+}
+
+/// The feed that the entries read from stdin belong to
+#[derive(Debug)]
+pub(crate) enum IngestFeed {
+    Id(crate::rss::FeedId),
+    /// created if it does not exist yet
+    Link(String),
+}
@@ -1,166 +1,559 @@
-//! This module provides a way to asynchronously refresh feeds, using threads
+//! This module provides a way to asynchronously refresh feeds, using threads.
+//!
+//! The io thread never touches `App`. It takes `Request`s and answers each one
+//! with `Response`s tagged with the request's `RequestId`,
+//! which the update loop applies to `App` in the order they arrive.
+//!
+//! Refreshes are handed to a thread of their own, which does them one after another,
+//! so entries keep loading while feeds refresh.
 
-use crate::app::App;
-use crate::modes::Mode;
+use crate::modes::ReadMode;
+use crate::rss::{EntryId, EntryMetadata, FeedId};
 use crate::ReadOptions;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
 
-pub(crate) enum Action {
+/// r2d2's own default, which is plenty for everything but refreshing on many threads
+const DEFAULT_CONNECTION_POOL_SIZE: u32 = 10;
+
+type ConnectionPool = r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>;
+
+/// Identifies a request, so the responses to it can be matched up with it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct RequestId(u64);
+
+impl From<u64> for RequestId {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+/// Work for the io thread.
+/// Every request other than `Break` is answered with exactly one `Response::Finished`,
+/// and possibly some `Response::Progress` before it.
+#[derive(Debug)]
+pub(crate) enum Request {
     Break,
-    RefreshFeed(crate::rss::FeedId),
-    RefreshFeeds(Vec<crate::rss::FeedId>),
+    RefreshFeed(FeedId),
+    RefreshFeeds(Vec<FeedId>),
+    /// count the new entries refreshing the feeds would add, without adding them
+    PreviewRefreshFeeds(Vec<FeedId>),
     SubscribeToFeed(String),
-    ClearFlash,
+    ResolveEntryLink(EntryId, String),
+    /// fetch the article at an entry's link, for entries that are only summaries
+    FetchFullContent(EntryId, String),
+    RediscoverFeed(FeedId),
+    SummarizeEntry(EntryId, String),
+    /// a feed's entries, published before a time if there is one,
+    /// and how many new entries every feed has
+    LoadEntries(FeedId, ReadMode, Option<DateTime<Utc>>),
+    /// use the database at this path for every request after this one
+    SwitchDatabase(std::path::PathBuf),
+}
+
+impl Request {
+    /// What the request is doing, for flashes and for errors from it
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Request::Break => "Stopping".to_string(),
+            Request::RefreshFeed(_) => "Refreshing feed".to_string(),
+            Request::RefreshFeeds(_) => "Refreshing all feeds".to_string(),
+            Request::PreviewRefreshFeeds(_) => "Checking all feeds for new entries".to_string(),
+            Request::SubscribeToFeed(feed_link) => format!("Subscribing to {feed_link}"),
+            Request::ResolveEntryLink(_, link) => format!("Resolving {link}"),
+            Request::FetchFullContent(_, link) => format!("Fetching the full article from {link}"),
+            Request::RediscoverFeed(_) => "Looking for a new feed on the feed's site".to_string(),
+            Request::SummarizeEntry(_, _) => "Summarizing entry".to_string(),
+            Request::LoadEntries(..) => "Loading entries".to_string(),
+            Request::SwitchDatabase(database_path) => {
+                format!("Switching to {}", database_path.display())
+            }
+        }
+    }
+
+    /// Whether the request goes to the network, which it may not in offline mode.
+    /// Summarizing runs locally, or with a command the user chose
+    pub(crate) fn uses_network(&self) -> bool {
+        !matches!(
+            self,
+            Request::Break
+                | Request::SummarizeEntry(..)
+                | Request::LoadEntries(..)
+                | Request::SwitchDatabase(..)
+        )
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum Response {
+    /// an update on a request that is still being worked on
+    Progress(RequestId, String),
+    /// a refresh has refreshed another of its feeds, with how far along it is
+    FeedRefreshed(RequestId, String),
+    Finished(RequestId, Result<Outcome>),
 }
 
-/// A loop to process `io::Action` messages.
-pub(crate) fn io_loop(
-    app: App,
-    io_tx: std::sync::mpsc::Sender<Action>,
-    io_rx: std::sync::mpsc::Receiver<Action>,
+/// What a request did, when it did not fail outright
+#[derive(Debug)]
+pub(crate) enum Outcome {
+    FeedsRefreshed {
+        feeds_len: usize,
+        /// the errors of the feeds that failed to refresh, if some did
+        errors: Vec<anyhow::Error>,
+        elapsed: Duration,
+    },
+    RefreshPreviewed {
+        /// how many new entries each feed that would gain some has
+        new_entries_counts: Vec<(FeedId, usize)>,
+        feeds_len: usize,
+        errors: Vec<anyhow::Error>,
+        elapsed: Duration,
+    },
+    SubscribedToFeed {
+        /// where the feed was found, which is not what was typed for a site's page, or a Mastodon account
+        feed_link: Option<String>,
+        elapsed: Duration,
+    },
+    /// the feed to subscribe to was not found, but there is one at this more usual location
+    FallbackFeedFound(String),
+    EntryLinkResolved(String),
+    FullContentFetched(EntryId),
+    FeedRediscovered(String),
+    EntrySummarized(EntryId, String),
+    EntriesLoaded {
+        feed_id: FeedId,
+        /// the read mode the entries were loaded for, which may have changed since
+        read_mode: ReadMode,
+        as_of: Option<DateTime<Utc>>,
+        entries: Vec<EntryMetadata>,
+        new_entries_counts: HashMap<FeedId, usize>,
+        unread_entries_counts: HashMap<FeedId, usize>,
+    },
+    DatabaseSwitched,
+}
+
+/// A loop to process `Request`s, sending their responses to `respond`.
+///
+/// If `daemon` is `Some`, refreshes are handed off to the running `russ daemon`
+/// rather than being performed by this process.
+pub(crate) fn io_loop<F>(
+    io_rx: std::sync::mpsc::Receiver<(RequestId, Request)>,
+    respond: F,
+    http_client: ureq::Agent,
     options: &ReadOptions,
-) -> Result<()> {
-    let manager = r2d2_sqlite::SqliteConnectionManager::file(&options.database_path);
-    let connection_pool = r2d2::Pool::new(manager)?;
-
-    while let Ok(event) = io_rx.recv() {
-        match event {
-            Action::Break => break,
-            Action::RefreshFeed(feed_id) => {
-                let now = std::time::Instant::now();
+    daemon: Option<crate::daemon::Client>,
+) -> Result<()>
+where
+    F: Fn(Response) -> Result<()> + Clone + Send + 'static,
+{
+    let mut connection_pool = connection_pool(
+        &options.database_path,
+        &options.database_options,
+        &options.ingest_options,
+    )?;
 
-                app.set_flash("Refreshing feed...".to_string());
-                app.force_redraw()?;
+    // the refreshing thread stops once this loop has, and this is dropped,
+    // but it is not waited on, so quitting does not wait for a refresh to finish
+    let (refresh_tx, refresh_rx) = std::sync::mpsc::channel();
+    {
+        let respond = respond.clone();
+        let http_client = http_client.clone();
+        let ingest_options = options.ingest_options.clone();
+        let connection_pool = connection_pool.clone();
 
-                refresh_feeds(&app, &connection_pool, &[feed_id], |_app, fetch_result| {
-                    if let Err(e) = fetch_result {
-                        app.push_error_flash(e)
-                    }
-                })?;
+        std::thread::spawn(move || {
+            refresh_loop(
+                refresh_rx,
+                respond,
+                http_client,
+                ingest_options,
+                connection_pool,
+                daemon,
+            )
+        });
+    }
 
-                app.update_current_feed_and_entries()?;
-                let elapsed = now.elapsed();
-                app.set_flash(format!("Refreshed feed in {elapsed:?}"));
-                app.force_redraw()?;
-                clear_flash_after(io_tx.clone(), options.flash_display_duration_seconds);
+    while let Ok((request_id, request)) = io_rx.recv() {
+        let result = match request {
+            Request::Break => break,
+            Request::RefreshFeed(_)
+            | Request::RefreshFeeds(_)
+            | Request::PreviewRefreshFeeds(_) => {
+                refresh_tx.send(RefreshWork::Request(request_id, request))?;
+                continue;
             }
-            Action::RefreshFeeds(feed_ids) => {
+            Request::SubscribeToFeed(feed_subscription_input) => {
                 let now = std::time::Instant::now();
 
-                app.set_flash("Refreshing all feeds...".to_string());
-                app.force_redraw()?;
+                connection_pool
+                    .get()
+                    .map_err(anyhow::Error::from)
+                    .and_then(|mut conn| {
+                        crate::rss::subscribe_to_feed(
+                            &http_client,
+                            &mut conn,
+                            &feed_subscription_input,
+                            &options.ingest_options,
+                            options.archive_pages,
+                            |page| {
+                                respond(Response::Progress(
+                                    request_id,
+                                    format!(
+                                        "Subscribing to feed... fetching archive page {page}/{}",
+                                        options.archive_pages
+                                    ),
+                                ))
+                            },
+                        )
+                        .and_then(|feed_id| crate::rss::get_feed(&conn, feed_id))
+                    })
+                    .map(|feed| Outcome::SubscribedToFeed {
+                        feed_link: feed.feed_link,
+                        elapsed: now.elapsed(),
+                    })
+                    .or_else(|e| {
+                        if !crate::rss::is_feed_not_found(&e) {
+                            return Err(e);
+                        }
 
-                let all_feeds_len = feed_ids.len();
-                let mut successfully_refreshed_len = 0usize;
+                        respond(Response::Progress(
+                            request_id,
+                            "Feed not found, trying common feed locations...".to_string(),
+                        ))?;
 
-                refresh_feeds(&app, &connection_pool, &feed_ids, |app, fetch_result| {
-                    match fetch_result {
-                        Ok(_) => successfully_refreshed_len += 1,
-                        Err(e) => app.push_error_flash(e),
-                    }
-                })?;
+                        match crate::rss::find_fallback_feed_link(
+                            &http_client,
+                            &feed_subscription_input,
+                            &options.ingest_options,
+                        ) {
+                            Some(feed_link) => Ok(Outcome::FallbackFeedFound(feed_link)),
+                            None => Err(e),
+                        }
+                    })
+            }
+            Request::ResolveEntryLink(entry_id, link) => {
+                crate::rss::resolve_link(&http_client, &link, &options.ingest_options).and_then(
+                    |resolved_link| {
+                        let conn = connection_pool.get()?;
+                        crate::rss::update_entry_resolved_link(&conn, entry_id, &resolved_link)?;
+                        Ok(Outcome::EntryLinkResolved(resolved_link))
+                    },
+                )
+            }
+            Request::FetchFullContent(entry_id, link) => {
+                crate::rss::fetch_full_content(&http_client, &link, &options.ingest_options)
+                    .and_then(|full_content| {
+                        let conn = connection_pool.get()?;
+                        crate::rss::update_entry_full_content(
+                            &conn,
+                            entry_id,
+                            full_content,
+                            &options.ingest_options,
+                        )?;
+                        Ok(Outcome::FullContentFetched(entry_id))
+                    })
+            }
+            Request::RediscoverFeed(feed_id) => connection_pool
+                .get()
+                .map_err(anyhow::Error::from)
+                .and_then(|mut conn| {
+                    crate::rss::rediscover_feed(
+                        &http_client,
+                        &mut conn,
+                        feed_id,
+                        &options.ingest_options,
+                    )
+                })
+                .map(Outcome::FeedRediscovered),
+            Request::LoadEntries(feed_id, read_mode, as_of) => connection_pool
+                .get()
+                .map_err(anyhow::Error::from)
+                .and_then(|conn| {
+                    Ok(Outcome::EntriesLoaded {
+                        feed_id,
+                        entries: crate::rss::get_entries_metas(&conn, &read_mode, feed_id, as_of)?,
+                        new_entries_counts: crate::rss::get_new_entries_counts(&conn)?,
+                        unread_entries_counts: crate::rss::get_unread_entries_counts(&conn)?,
+                        read_mode,
+                        as_of,
+                    })
+                }),
+            Request::SummarizeEntry(entry_id, text) => match &options.summarizer {
+                Some(summarizer) => summarizer
+                    .summarize(&text)
+                    .map(|summary| Outcome::EntrySummarized(entry_id, summary)),
+                None => Err(anyhow!("Summaries are not turned on")),
+            },
+            // the pool's name shadows the function that makes one
+            Request::SwitchDatabase(database_path) => self::connection_pool(
+                &database_path,
+                &options.database_options,
+                &options.ingest_options,
+            )
+            .and_then(|pool| {
+                connection_pool = pool;
+                // a daemon only refreshes the database it was started for
+                let daemon = crate::daemon::Client::connect(&database_path);
+                refresh_tx.send(RefreshWork::SwitchDatabase(connection_pool.clone(), daemon))?;
+                Ok(Outcome::DatabaseSwitched)
+            }),
+        };
+
+        respond(Response::Finished(request_id, result))?;
+    }
 
-                {
-                    app.update_current_feed_and_entries()?;
+    Ok(())
+}
 
-                    let elapsed = now.elapsed();
-                    app.set_flash(format!(
-                        "Refreshed {successfully_refreshed_len}/{all_feeds_len} feeds in {elapsed:?}"
-                    ));
-                    app.force_redraw()?;
-                }
+/// What the refreshing thread is sent
+enum RefreshWork {
+    Request(RequestId, Request),
+    /// refresh from now on with this pool, and daemon, if there is one for the new database
+    SwitchDatabase(ConnectionPool, Option<crate::daemon::Client>),
+}
 
-                clear_flash_after(io_tx.clone(), options.flash_display_duration_seconds);
+/// Does the refreshes `io_loop` hands off, one after another
+fn refresh_loop<F>(
+    refresh_rx: std::sync::mpsc::Receiver<RefreshWork>,
+    respond: F,
+    http_client: ureq::Agent,
+    ingest_options: crate::rss::IngestOptions,
+    mut connection_pool: ConnectionPool,
+    mut daemon: Option<crate::daemon::Client>,
+) -> Result<()>
+where
+    F: Fn(Response) -> Result<()>,
+{
+    while let Ok(work) = refresh_rx.recv() {
+        let (request_id, request) = match work {
+            RefreshWork::Request(request_id, request) => (request_id, request),
+            RefreshWork::SwitchDatabase(pool, new_daemon) => {
+                connection_pool = pool;
+                daemon = new_daemon;
+                continue;
             }
-            Action::SubscribeToFeed(feed_subscription_input) => {
+        };
+
+        let result = match request {
+            Request::RefreshFeed(feed_id) => refresh(
+                &http_client,
+                &connection_pool,
+                &[feed_id],
+                &ingest_options,
+                &mut daemon,
+                |progress| respond(Response::FeedRefreshed(request_id, progress)),
+            ),
+            Request::RefreshFeeds(feed_ids) => refresh(
+                &http_client,
+                &connection_pool,
+                &feed_ids,
+                &ingest_options,
+                &mut daemon,
+                |progress| respond(Response::FeedRefreshed(request_id, progress)),
+            ),
+            Request::PreviewRefreshFeeds(feed_ids) => {
                 let now = std::time::Instant::now();
 
-                app.set_flash("Subscribing to feed...".to_string());
-                app.force_redraw()?;
+                let mut new_entries_counts = vec![];
+                let mut errors = vec![];
 
-                let mut conn = connection_pool.get()?;
-                let r = crate::rss::subscribe_to_feed(
-                    &app.http_client(),
-                    &mut conn,
-                    &feed_subscription_input,
-                );
+                preview_refresh_feeds(
+                    &http_client,
+                    &connection_pool,
+                    &feed_ids,
+                    &ingest_options,
+                    |feed_id, preview_result| match preview_result {
+                        Ok(0) => (),
+                        Ok(count) => new_entries_counts.push((feed_id, count)),
+                        Err(e) => errors.push(e),
+                    },
+                )
+                .map(|_| Outcome::RefreshPreviewed {
+                    new_entries_counts,
+                    feeds_len: feed_ids.len(),
+                    errors,
+                    elapsed: now.elapsed(),
+                })
+            }
+            request => Err(anyhow!("{} is not a refresh", request.describe())),
+        };
 
-                if let Err(e) = r {
-                    app.push_error_flash(e);
-                    continue;
-                }
+        respond(Response::Finished(request_id, result))?;
+    }
 
-                match crate::rss::get_feeds(&conn) {
-                    Ok(feeds) => {
-                        {
-                            app.reset_feed_subscription_input();
-                            app.set_feeds(feeds);
-                            app.select_feeds();
-                            app.update_current_feed_and_entries()?;
-
-                            let elapsed = now.elapsed();
-                            app.set_flash(format!("Subscribed in {elapsed:?}"));
-                            app.set_mode(Mode::Normal);
-                            app.force_redraw()?;
-                        }
+    Ok(())
+}
 
-                        clear_flash_after(io_tx.clone(), options.flash_display_duration_seconds);
-                    }
-                    Err(e) => {
-                        app.push_error_flash(e);
-                    }
-                }
-            }
-            Action::ClearFlash => {
-                app.clear_flash();
-            }
+/// Refreshes the feeds of `feed_ids`, calling `on_progress` with how many are done as each one is.
+/// If the `daemon` has gone away, it is forgotten, and the feeds are refreshed here instead.
+fn refresh<P>(
+    http_client: &ureq::Agent,
+    connection_pool: &ConnectionPool,
+    feed_ids: &[FeedId],
+    ingest_options: &crate::rss::IngestOptions,
+    daemon: &mut Option<crate::daemon::Client>,
+    on_progress: P,
+) -> Result<Outcome>
+where
+    P: Fn(String) -> Result<()>,
+{
+    let now = std::time::Instant::now();
+
+    let mut errors = vec![];
+    let mut refreshed_len = 0;
+
+    let refresh_result_handler = |refresh_result: Result<()>| {
+        refreshed_len += 1;
+
+        if let Err(e) = refresh_result {
+            errors.push(e)
+        }
+
+        // the app is gone if this fails, and the refresh may as well finish anyway
+        let _ = on_progress(format!(
+            "Refreshing feeds... {refreshed_len}/{} refreshed",
+            feed_ids.len()
+        ));
+    };
+
+    // the daemon's results are only counted once it has finished,
+    // so none are counted twice if it goes away partway through
+    let daemon_results = daemon.as_mut().and_then(|client| {
+        let mut results = vec![];
+        client
+            .refresh_feeds(feed_ids, |result| results.push(result))
+            .ok()
+            .map(|()| results)
+    });
+
+    match daemon_results {
+        Some(results) => results.into_iter().for_each(refresh_result_handler),
+        None => {
+            *daemon = None;
+            refresh_feeds(
+                http_client,
+                connection_pool,
+                feed_ids,
+                ingest_options,
+                refresh_result_handler,
+            )?;
         }
     }
 
-    Ok(())
+    Ok(Outcome::FeedsRefreshed {
+        feeds_len: feed_ids.len(),
+        errors,
+        elapsed: now.elapsed(),
+    })
 }
 
 /// Refreshes the feeds of the given `feed_ids` by splitting them into
-/// chunks based on the number of available CPUs.
+/// as many chunks as `IngestOptions::refresh_threads`.
 /// Each chunk is then passed to its own thread,
 /// where each feed_id in the chunk has its feed refreshed synchronously on that thread.
-fn refresh_feeds<F>(
-    app: &App,
-    connection_pool: &r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+pub(crate) fn refresh_feeds<F>(
+    http_client: &ureq::Agent,
+    connection_pool: &ConnectionPool,
     feed_ids: &[crate::rss::FeedId],
+    ingest_options: &crate::rss::IngestOptions,
     mut refresh_result_handler: F,
 ) -> Result<()>
 where
-    F: FnMut(&App, anyhow::Result<()>),
+    F: FnMut(anyhow::Result<()>),
+{
+    on_feeds_in_parallel(
+        http_client,
+        connection_pool,
+        feed_ids,
+        ingest_options,
+        crate::rss::refresh_feed,
+        |_feed_id, refresh_result| refresh_result_handler(refresh_result),
+    )
+}
+
+/// Counts the entries refreshing each of the feeds of `feed_ids` would add,
+/// in parallel like `refresh_feeds`, without storing anything
+pub(crate) fn preview_refresh_feeds<F>(
+    http_client: &ureq::Agent,
+    connection_pool: &ConnectionPool,
+    feed_ids: &[crate::rss::FeedId],
+    ingest_options: &crate::rss::IngestOptions,
+    preview_result_handler: F,
+) -> Result<()>
+where
+    F: FnMut(FeedId, anyhow::Result<usize>),
 {
-    let chunks = chunkify_for_threads(feed_ids, num_cpus::get() * 2);
+    on_feeds_in_parallel(
+        http_client,
+        connection_pool,
+        feed_ids,
+        ingest_options,
+        crate::rss::preview_refresh_feed,
+        preview_result_handler,
+    )
+}
+
+type FeedWork<T> = fn(
+    &ureq::Agent,
+    &mut rusqlite::Connection,
+    FeedId,
+    &crate::rss::IngestOptions,
+) -> anyhow::Result<T>;
+
+fn on_feeds_in_parallel<T, F>(
+    http_client: &ureq::Agent,
+    connection_pool: &ConnectionPool,
+    feed_ids: &[crate::rss::FeedId],
+    ingest_options: &crate::rss::IngestOptions,
+    work: FeedWork<T>,
+    mut result_handler: F,
+) -> Result<()>
+where
+    T: Send + 'static,
+    F: FnMut(FeedId, anyhow::Result<T>),
+{
+    let chunks = chunkify_for_threads(feed_ids, ingest_options.refresh_threads());
+
+    // results are handled as each feed is done, rather than once all of them are
+    let (results_tx, results_rx) = std::sync::mpsc::channel();
 
     let join_handles: Vec<_> = chunks
         .map(|chunk| {
             let pool_get_result = connection_pool.get();
-            let http_client = app.http_client();
+            let http_client = http_client.clone();
+            let ingest_options = ingest_options.clone();
             let chunk = chunk.to_owned();
+            let results_tx = results_tx.clone();
 
-            std::thread::spawn(move || -> Result<Vec<Result<(), anyhow::Error>>> {
+            std::thread::spawn(move || -> Result<()> {
                 let mut conn = pool_get_result?;
 
-                let results = chunk
-                    .into_iter()
-                    .map(|feed_id| crate::rss::refresh_feed(&http_client, &mut conn, feed_id))
-                    .collect();
+                for feed_id in chunk {
+                    let result = work(&http_client, &mut conn, feed_id, &ingest_options);
+                    // nothing is receiving results any more, so there is no point going on
+                    if results_tx.send((feed_id, result)).is_err() {
+                        break;
+                    }
+                }
 
-                Ok::<Vec<Result<(), anyhow::Error>>, anyhow::Error>(results)
+                Ok(())
             })
         })
         .collect();
 
+    // so receiving ends once every thread is done
+    drop(results_tx);
+
+    for (feed_id, result) in results_rx {
+        result_handler(feed_id, result)
+    }
+
     for join_handle in join_handles {
-        let chunk_results = join_handle
+        join_handle
             .join()
-            .expect("unable to join worker thread to io thread");
-        for chunk_result in chunk_results? {
-            refresh_result_handler(app, chunk_result)
-        }
+            .expect("unable to join worker thread to io thread")?;
     }
 
     Ok(())
@@ -168,30 +561,223 @@ where
 
 /// split items into chunks,
 /// with the idea being that each chunk will be run on its own thread
-fn chunkify_for_threads<T>(
-    items: &[T],
-    minimum_number_of_threads: usize,
-) -> impl Iterator<Item = &[T]> {
-    // example: 25 items / 16 threads = chunk size of 1
-    // example: 100 items / 16 threads = chunk size of 6
-    // example: 10 items / 16 threads = chunk size of 0 (handled later)
+fn chunkify_for_threads<T>(items: &[T], number_of_threads: usize) -> impl Iterator<Item = &[T]> {
+    // example: 25 items / 16 threads = chunk size of 2
+    // example: 100 items / 16 threads = chunk size of 7
+    // example: 10 items / 16 threads = chunk size of 1
+    // example: 0 items / 16 threads = chunk size of 0 (handled later)
     //
-    // due to usize floor division, it's possible chunk_size would be 0,
-    // so ensure it is at least 1
-    let chunk_size = (items.len() / minimum_number_of_threads).max(1);
+    // rounding up means there are never more chunks than threads,
+    // and `chunks` panics on 0, so ensure it is at least 1
+    let chunk_size = items.len().div_ceil(number_of_threads).max(1);
 
-    // now we have (len / chunk_size) chunks,
+    // now we have (len / chunk_size) chunks, rounded up,
     // example:
-    // 25 items / chunks size of 1 = 25 chunks
-    // 100 items / chunk size of 6 = 16 chunks
+    // 25 items / chunks size of 2 = 13 chunks
+    // 100 items / chunk size of 7 = 15 chunks
     items.chunks(chunk_size)
 }
 
-/// clear the flash after a given duration
-fn clear_flash_after(tx: std::sync::mpsc::Sender<Action>, duration: std::time::Duration) {
-    std::thread::spawn(move || {
-        std::thread::sleep(duration);
-        tx.send(Action::ClearFlash)
-            .expect("Unable to send IOCommand::ClearFlash");
-    });
+/// A connection pool big enough for every thread refreshing feeds to have its own connection,
+/// on a database that is set up before the pool is returned
+pub(crate) fn connection_pool(
+    database_path: &std::path::Path,
+    database_options: &crate::rss::DatabaseOptions,
+    ingest_options: &crate::rss::IngestOptions,
+) -> Result<ConnectionPool> {
+    let manager = crate::rss::connection_manager(database_path, database_options);
+
+    // and one more, so loading entries does not wait for a refresh
+    let max_size = u32::try_from(ingest_options.refresh_threads() + 1)
+        .unwrap_or(u32::MAX)
+        .max(DEFAULT_CONNECTION_POOL_SIZE);
+
+    let pool = r2d2::Pool::builder().max_size(max_size).build(manager)?;
+
+    // the pool's connections expect the schema to be there already,
+    // so it is set up, once, before anything can take one
+    let mut conn = pool.get()?;
+    crate::rss::initialize_db(&mut conn)?;
+    drop(conn);
+
+    Ok(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_never_uses_more_threads_than_asked() {
+        let feed_ids = (1..=100).collect::<Vec<i64>>();
+
+        for number_of_threads in [1, 3, 16, 100, 200] {
+            let chunks = chunkify_for_threads(&feed_ids, number_of_threads).collect::<Vec<_>>();
+
+            assert!(chunks.len() <= number_of_threads);
+            assert_eq!(chunks.concat(), feed_ids);
+        }
+
+        assert_eq!(chunkify_for_threads::<i64>(&[], 4).count(), 0);
+    }
+
+    #[test]
+    fn it_answers_requests_with_their_ids() {
+        let dir = crate::util::TestDir::new("io");
+        let database_path = dir.join("feeds.db");
+
+        let mut conn = rusqlite::Connection::open(&database_path).unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+
+        let options = ReadOptions {
+            database_path,
+            tick_rate: 250,
+            flash_display_duration_seconds: Duration::from_secs(4),
+            network_timeout: Duration::from_secs(5),
+            ingest_options: crate::rss::IngestOptions::default(),
+            database_options: crate::rss::DatabaseOptions::default(),
+            archive_pages: 0,
+            summarizer: Some(crate::summary::Summarizer::Extractive),
+            group_feeds_by_domain: false,
+            feed_sort: crate::rss::FeedSort::default(),
+            action_log_path: None,
+            replay_path: None,
+            refresh_alert: crate::app::RefreshAlert::Never,
+            visual_alert: false,
+            offline: false,
+            entry_template: crate::template::EntryTemplate::default(),
+            list_density: crate::app::ListDensity::Compact,
+            theme: crate::colors::Theme::default(),
+            monochrome: false,
+            idle_timeout: None,
+            idle_action: crate::app::IdleAction::Lock,
+            feed_enter_action: crate::app::FeedEnterAction::Entries,
+            key_hints: true,
+            as_of: None,
+        };
+
+        let (io_tx, io_rx) = std::sync::mpsc::channel();
+        let (response_tx, response_rx) = std::sync::mpsc::channel();
+
+        io_tx
+            .send((
+                1.into(),
+                Request::SummarizeEntry(10.into(), "Not much to summarize here.".to_string()),
+            ))
+            .unwrap();
+        // there is no such feed, so it fails without going to the network
+        io_tx
+            .send((2.into(), Request::RefreshFeed(20.into())))
+            .unwrap();
+        io_tx.send((3.into(), Request::Break)).unwrap();
+
+        io_loop(
+            io_rx,
+            move |response| Ok(response_tx.send(response)?),
+            ureq::agent(),
+            &options,
+            None,
+        )
+        .unwrap();
+
+        // refreshes finish on their own thread, which this waits for by waiting for every response
+        let responses = response_rx.iter().collect::<Vec<_>>();
+
+        assert!(matches!(
+            &responses[..],
+            [
+                Response::Finished(summary_id, Ok(Outcome::EntrySummarized(entry_id, _))),
+                Response::FeedRefreshed(progress_id, progress),
+                Response::Finished(refresh_id, Ok(Outcome::FeedsRefreshed { feeds_len: 1, errors, .. })),
+            ] if *summary_id == 1.into()
+                && *progress_id == 2.into()
+                && progress.ends_with("1/1 refreshed")
+                && *entry_id == 10.into()
+                && *refresh_id == 2.into()
+                && errors.len() == 1
+        ));
+    }
+
+    #[test]
+    fn it_refreshes_right_after_first_launch() {
+        let dir = crate::util::TestDir::new("first-launch");
+        let database_path = dir.join("feeds.db");
+
+        // a few readers starting on a database that does not exist yet,
+        // each refreshing as soon as it has a pool, like on launch
+        let launches = (0..4)
+            .map(|_| {
+                let database_path = database_path.clone();
+                std::thread::spawn(move || -> Result<Outcome> {
+                    let database_options = crate::rss::DatabaseOptions::default();
+                    let ingest_options = crate::rss::IngestOptions::default();
+
+                    let mut conn = rusqlite::Connection::open(&database_path)?;
+                    crate::rss::tune_connection(&conn, &database_options)?;
+                    crate::rss::initialize_db(&mut conn)?;
+
+                    let pool = connection_pool(&database_path, &database_options, &ingest_options)?;
+
+                    // there is no such feed, so it fails without going to the network
+                    refresh(
+                        &ureq::agent(),
+                        &pool,
+                        &[20.into()],
+                        &ingest_options,
+                        &mut None,
+                        |_| Ok(()),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for launch in launches {
+            let outcome = launch.join().unwrap().unwrap();
+            assert!(matches!(
+                outcome,
+                Outcome::FeedsRefreshed { feeds_len: 1, errors, .. } if errors.len() == 1
+            ));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_refreshes_on_its_own_once_the_daemon_is_gone() {
+        let dir = crate::util::TestDir::new("daemon-gone");
+        let database_path = dir.join("feeds.db");
+        let database_options = crate::rss::DatabaseOptions::default();
+        let ingest_options = crate::rss::IngestOptions::default();
+
+        let mut conn = rusqlite::Connection::open(&database_path).unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+        let pool = connection_pool(&database_path, &database_options, &ingest_options).unwrap();
+
+        // a daemon that goes away right after the session attaches to it
+        let listener =
+            std::os::unix::net::UnixListener::bind(crate::daemon::socket_path(&database_path))
+                .unwrap();
+        let mut daemon = crate::daemon::Client::connect(&database_path);
+        assert!(daemon.is_some());
+        drop(listener.accept().unwrap());
+        drop(listener);
+
+        for _ in 0..2 {
+            // there is no such feed, so it fails without going to the network
+            let outcome = refresh(
+                &ureq::agent(),
+                &pool,
+                &[20.into()],
+                &ingest_options,
+                &mut daemon,
+                |_| Ok(()),
+            )
+            .unwrap();
+
+            assert!(matches!(
+                outcome,
+                Outcome::FeedsRefreshed { feeds_len: 1, errors, .. } if errors.len() == 1
+            ));
+            assert!(daemon.is_none());
+        }
+    }
 }
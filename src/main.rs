@@ -2,8 +2,8 @@
 
 use crate::modes::{Mode, Selected};
 use anyhow::Result;
-use app::App;
-use clap::{Parser, Subcommand};
+use app::{App, FeedEnterAction};
+use clap::{ArgGroup, Parser, Subcommand};
 use crossterm::event::{self, KeyEvent, KeyEventKind};
 use crossterm::event::{Event as CEvent, KeyCode, KeyModifiers};
 use crossterm::execute;
@@ -14,26 +14,76 @@ use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use std::io::stdout;
 use std::path::PathBuf;
+use std::process::ExitCode;
 use std::sync::mpsc;
 use std::{thread, time};
 
 mod app;
+mod assets;
+mod colors;
+mod cookies;
+mod daemon;
+mod db;
+mod demo;
+mod directory;
+mod discovery;
+mod error;
+mod ingest;
 mod io;
+mod metrics;
+mod middleware;
+mod migrate;
 mod modes;
+#[cfg(feature = "imap")]
+mod newsletters;
+mod notes;
 mod opml;
+mod progress;
+mod readability;
+mod refresh;
 mod rss;
+mod session;
+mod summary;
+mod template;
 mod ui;
 mod util;
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     let options = Options::parse();
 
     let validated_options = options.subcommand.validate()?;
 
     match validated_options {
-        ValidatedOptions::Import(options) => crate::opml::import(options),
-        ValidatedOptions::Read(options) => run_reader(options),
+        ValidatedOptions::Import(options) => {
+            return match options.source.clone() {
+                ImportSource::Opml(opml_path) => crate::opml::import(options, &opml_path),
+                ImportSource::MinifluxExport(path) => {
+                    crate::migrate::import_miniflux_export(options, &path)
+                }
+                ImportSource::NewsboatCache(path) => {
+                    crate::migrate::import_newsboat_cache(options, &path)
+                }
+                ImportSource::Bookmarks(path) => crate::migrate::import_bookmarks(options, &path),
+            };
+        }
+        ValidatedOptions::Read(options) => run_reader(options)?,
+        ValidatedOptions::Demo => crate::demo::run()?,
+        ValidatedOptions::Daemon(options) => crate::daemon::run(options)?,
+        ValidatedOptions::Refresh(options) => return crate::refresh::refresh(options),
+        ValidatedOptions::ExportNotes(options) => crate::notes::export_notes(options)?,
+        ValidatedOptions::IngestFeed(options) => crate::ingest::ingest(options)?,
+        #[cfg(feature = "imap")]
+        ValidatedOptions::Newsletters(options) => crate::newsletters::fetch(options)?,
+        ValidatedOptions::Recompress(options) => crate::db::recompress(options)?,
+        ValidatedOptions::BackfillGuids(options) => crate::db::backfill_guids(options)?,
+        ValidatedOptions::PruneCache(options) => crate::assets::prune_cache(options)?,
+        ValidatedOptions::SetMaxNewEntries(options) => crate::db::set_max_new_entries(options)?,
+        ValidatedOptions::SetMarkReadAfter(options) => crate::db::set_mark_read_after(options)?,
+        ValidatedOptions::SetRetention(options) => crate::db::set_retention(options)?,
+        ValidatedOptions::SlowestFeeds(options) => crate::db::slowest_feeds(options)?,
     }
+
+    Ok(ExitCode::SUCCESS)
 }
 
 /// A TUI RSS reader with vim-like controls and a local-first, offline-first focus
@@ -54,6 +104,7 @@ enum Command {
         /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
         /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
         /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        /// `:memory:` is a database that lasts only as long as this session.
         #[arg(short, long)]
         database_path: Option<PathBuf>,
         /// time in ms between two ticks
@@ -65,8 +116,91 @@ enum Command {
         /// RSS/Atom network request timeout in seconds
         #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
         network_timeout: time::Duration,
+        #[command(flatten)]
+        ingest: IngestArgs,
+        #[command(flatten)]
+        database: DatabaseArgs,
+        /// maximum number of RFC 5005 archive pages to follow when subscribing to a feed.
+        /// 0 disables archive traversal
+        #[arg(long, default_value = "0")]
+        archive_pages: usize,
+        /// show a short summary of long entries, made from their most important sentences
+        #[arg(long)]
+        summarize: bool,
+        /// group feeds by the folder they were imported into from OPML,
+        /// or for feeds in no folder, by the domain they are on, like all of your github.com release feeds.
+        /// this can also be toggled with `g`
+        #[arg(long)]
+        group_feeds_by_domain: bool,
+        /// how to list feeds: title, updated (the feed with the newest entry first),
+        /// unread (the feed with the most unread entries first), or added (the newest feed first).
+        /// this can also be cycled through with `F`
+        #[arg(long, default_value = "title")]
+        feed_sort: crate::rss::FeedSort,
+        /// summarize long entries with this shell command instead.
+        /// it receives the entry's text on stdin and prints a summary to stdout
+        #[arg(long)]
+        summary_command: Option<String>,
+        /// write every action taken in this session to this file, one JSON object per line
+        #[arg(long)]
+        action_log: Option<PathBuf>,
+        /// replay the actions in an action log written by `--action-log` when starting,
+        /// before taking keyboard input. useful for reproducing bugs
+        #[arg(long)]
+        replay: Option<PathBuf>,
+        /// ring the terminal bell when a refresh finishes, so you can switch away while it runs:
+        /// never, refreshed (when refreshing all feeds finishes), or new-entries (when any refresh finds new entries)
+        #[arg(long, default_value = "never")]
+        refresh_alert: crate::app::RefreshAlert,
+        /// alert by briefly inverting the screen rather than ringing the bell
+        #[arg(long)]
+        visual_alert: bool,
+        /// start in offline mode, where russ makes no network requests,
+        /// so refreshing and subscribing do not wait on timeouts. this can also be toggled with `N`
+        #[arg(long)]
+        offline: bool,
+        /// how to list entries, with the fields {unread_marker}, {date}, {title}, and {author}.
+        /// {date} takes a strftime format, like {date:%m-%d}
+        #[arg(long, default_value = crate::template::DEFAULT_ENTRY_TEMPLATE)]
+        entry_template: crate::template::EntryTemplate,
+        /// how much room each feed and entry takes up in their lists:
+        /// compact (one line, with only titles), normal (one line, with dates and authors where they fit),
+        /// comfortable (two lines, with dates and authors under titles),
+        /// or table (one line, with entries' dates, read markers, titles, and authors in columns)
+        #[arg(long, default_value = "compact")]
+        list_density: crate::app::ListDensity,
+        /// number of minutes without input before hiding the screen until a key is pressed,
+        /// for reading private feeds on a shared machine
+        #[arg(long, value_parser = parse_minutes)]
+        idle_timeout: Option<time::Duration>,
+        /// what to do after `--idle-timeout`: lock (hide the screen until a key is pressed) or quit
+        #[arg(long, default_value = "lock")]
+        idle_action: crate::app::IdleAction,
+        #[command(flatten)]
+        theme: ThemeArgs,
+        /// what `Enter` does on a feed: entries (move into its entries),
+        /// open-site (open its site in the browser), or refresh (refresh it)
+        #[arg(long, default_value = "entries")]
+        feed_enter_action: crate::app::FeedEnterAction,
+        /// don't flash a hint pointing to `?` when a key does nothing where it is pressed
+        #[arg(long)]
+        no_key_hints: bool,
+        /// start out showing entries as they were on a past day, like `2024-05-01`,
+        /// without anything published after it. `[` and `]` go a day earlier or later
+        #[arg(long)]
+        as_of: Option<chrono::NaiveDate>,
     },
-    /// Import feeds from an OPML document
+    /// Try russ out, offline, with a few sample feeds in a throwaway database.
+    /// Your own database is never touched
+    Demo,
+    /// Import feeds from an OPML document,
+    /// or feeds, entries, and read/starred state from another feed reader,
+    /// or a browser's bookmarks to read later
+    #[command(group(
+        ArgGroup::new("source")
+            .required(true)
+            .args(["opml_path", "from_miniflux_export", "from_newsboat_cache", "from_bookmarks"])
+    ))]
     Import {
         /// Override where `russ` stores and reads feeds.
         /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
@@ -75,43 +209,762 @@ enum Command {
         #[arg(short, long)]
         database_path: Option<PathBuf>,
         #[arg(short, long)]
-        opml_path: PathBuf,
+        opml_path: Option<PathBuf>,
+        /// a JSON file of entries exported from Miniflux's `/v1/entries` API
+        #[arg(long)]
+        from_miniflux_export: Option<PathBuf>,
+        /// a newsboat `cache.db`
+        #[arg(long)]
+        from_newsboat_cache: Option<PathBuf>,
+        /// a browser's bookmarks exported as HTML, whose links become starred entries
+        /// of a "Bookmarks" feed
+        #[arg(long)]
+        from_bookmarks: Option<PathBuf>,
+        /// RSS/Atom network request timeout in seconds
+        #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
+        network_timeout: time::Duration,
+        #[command(flatten)]
+        ingest: IngestArgs,
+        #[command(flatten)]
+        database: DatabaseArgs,
+        /// maximum number of RFC 5005 archive pages to follow when subscribing to a feed.
+        /// 0 disables archive traversal
+        #[arg(long, default_value = "0")]
+        archive_pages: usize,
+        /// text, for progress and a summary on stderr,
+        /// or json, for only a summary of every feed imported, on stdout
+        #[arg(long, default_value = "text")]
+        output: progress::OutputFormat,
+    },
+    /// Refresh all of your feeds once, without a TUI
+    Refresh {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// RSS/Atom network request timeout in seconds
+        #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
+        network_timeout: time::Duration,
+        #[command(flatten)]
+        ingest: IngestArgs,
+        #[command(flatten)]
+        database: DatabaseArgs,
+        /// fetch feeds, but only print how many new entries each would add, without adding them
+        #[arg(long)]
+        dry_run: bool,
+        /// text, for a summary on stderr, or json, for a summary on stdout
+        #[arg(long, default_value = "text")]
+        output: progress::OutputFormat,
+    },
+    /// Refresh your feeds on a schedule, without a TUI.
+    /// While a daemon is running, `russ read` sessions using the same database
+    /// hand their refreshes off to it and reload when it refreshes.
+    Daemon {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// RSS/Atom network request timeout in seconds
+        #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
+        network_timeout: time::Duration,
+        #[command(flatten)]
+        ingest: IngestArgs,
+        #[command(flatten)]
+        database: DatabaseArgs,
+        /// number of minutes between scheduled refreshes of all feeds
+        #[arg(short, long, default_value = "60", value_parser = parse_minutes)]
+        refresh_interval: time::Duration,
+        /// serve Prometheus metrics at `/metrics` and a health check at `/health` on this address,
+        /// for example `127.0.0.1:9090`
+        #[arg(short, long)]
+        metrics_address: Option<std::net::SocketAddr>,
+    },
+    /// Export starred entries as Markdown files with frontmatter, for tools like Obsidian.
+    /// Exporting again overwrites previously exported files
+    ExportNotes {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// the directory to write notes to. it is created if it does not exist
+        #[arg(long)]
+        dir: PathBuf,
+        /// embed the entries' images cached in this directory by `--asset-cache-dir`
+        #[arg(long)]
+        asset_cache_dir: Option<PathBuf>,
+    },
+    /// Read a feed document from stdin and add its new entries to a feed,
+    /// for feeds fetched by something else, like curl with unusual auth, or a scraper
+    #[command(group(
+        ArgGroup::new("feed")
+            .required(true)
+            .args(["feed_id", "feed_url"])
+    ))]
+    Ingest {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// the id of the feed to add entries to
+        #[arg(long)]
+        feed_id: Option<i64>,
+        /// the feed link of the feed to add entries to,
+        /// which is created if there is no feed with this link yet
+        #[arg(long)]
+        feed_url: Option<String>,
+        /// network request timeout in seconds, for resolving entry links
+        #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
+        network_timeout: time::Duration,
+        #[command(flatten)]
+        ingest: IngestArgs,
+        #[command(flatten)]
+        database: DatabaseArgs,
+    },
+    /// Add unread email newsletters from an IMAP mailbox to a feed for each sender,
+    /// and mark them read. The mailbox password is read from `RUSS_IMAP_PASSWORD`
+    #[cfg(feature = "imap")]
+    Newsletters {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// the IMAP server, which must support TLS
+        #[arg(long)]
+        imap_host: String,
+        #[arg(long, default_value = "993")]
+        imap_port: u16,
+        #[arg(long)]
+        imap_user: String,
+        #[arg(long, default_value = "INBOX")]
+        mailbox: String,
+        /// only messages from senders containing this, like `substack.com`. can be given more than once
+        #[arg(long = "from", required = true)]
+        senders: Vec<String>,
+        /// network timeout in seconds
+        #[arg(short, long, default_value = "30", value_parser = parse_seconds)]
+        network_timeout: time::Duration,
+        #[command(flatten)]
+        ingest: IngestArgs,
+        #[command(flatten)]
+        database: DatabaseArgs,
+    },
+    /// Maintain the feeds database
+    Db {
+        #[command(subcommand)]
+        command: DbCommand,
+    },
+    /// Maintain the cache of entries' images
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Change a feed's settings
+    Feed {
+        #[command(subcommand)]
+        command: FeedCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum FeedCommand {
+    /// Limit how many new entries a refresh of a feed may add, keeping the newest,
+    /// for firehose feeds. This overrides `--max-new-entries` for the feed
+    SetMaxNewEntries {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// the feed's feed link, as it was subscribed to
+        feed_link: String,
+        /// the most new entries a refresh may add. leave it out to go back to `--max-new-entries`
+        max_new_entries: Option<usize>,
+    },
+    /// Have refreshes of a feed mark its entries published more than some days ago as read,
+    /// so firehose feeds don't pile up a backlog
+    SetMarkReadAfter {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// the feed's feed link, as it was subscribed to
+        feed_link: String,
+        /// how many days old entries may get before they are marked read. leave it out to stop marking them
+        days: Option<u32>,
+    },
+    /// Have refreshes of a feed delete its old entries, keeping the newest some number of them,
+    /// or the ones published in the last some days. This overrides `--retention` for the feed
+    SetRetention {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// the feed's feed link, as it was subscribed to
+        feed_link: String,
+        /// a number of entries, like `200`, or of days, like `30d`. leave it out to go back to `--retention`
+        retention: Option<crate::rss::Retention>,
+    },
+    /// List the feeds whose recent refreshes took the longest, slowest first,
+    /// to find the ones holding up refreshing every feed
+    Slowest {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// how many feeds to list
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum CacheCommand {
+    /// Remove the images of entries that no longer exist,
+    /// then the images of the entries cached longest ago, until the cache fits in its size
+    Prune {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// the asset cache directory
+        #[arg(long)]
+        asset_cache_dir: PathBuf,
+        /// the most the asset cache can hold, in MiB
+        #[arg(long, default_value = "512")]
+        asset_cache_size: u64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum DbCommand {
+    /// Compress the content of every entry already stored,
+    /// for databases from before `--compress-content` was used
+    Recompress {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// decompress every entry's content instead
+        #[arg(long)]
+        decompress: bool,
+    },
+    /// Fetch every feed again to find guids for entries stored before russ kept them,
+    /// matching entries by link. Entries no longer in their feed cannot be matched
+    BackfillGuids {
+        /// Override where `russ` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/russ/feeds.db` or `$HOME/.local/share/russ/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/russ/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/russ/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
         /// RSS/Atom network request timeout in seconds
         #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
         network_timeout: time::Duration,
+        #[command(flatten)]
+        ingest: IngestArgs,
     },
 }
 
 impl Command {
-    fn validate(&self) -> std::io::Result<ValidatedOptions> {
+    fn validate(&self) -> Result<ValidatedOptions> {
         match self {
             Command::Read {
                 database_path,
                 tick_rate,
                 flash_display_duration_seconds,
                 network_timeout,
+                ingest,
+                database,
+                archive_pages,
+                summarize,
+                group_feeds_by_domain,
+                feed_sort,
+                summary_command,
+                action_log,
+                replay,
+                refresh_alert,
+                visual_alert,
+                offline,
+                entry_template,
+                list_density,
+                theme,
+                idle_timeout,
+                idle_action,
+                feed_enter_action,
+                no_key_hints,
+                as_of,
             } => {
                 let database_path = get_database_path(database_path)?;
 
+                let summarizer = match (summarize, summary_command) {
+                    (_, Some(command)) => Some(summary::Summarizer::Command(command.to_owned())),
+                    (true, None) => Some(summary::Summarizer::Extractive),
+                    (false, None) => None,
+                };
+
                 Ok(ValidatedOptions::Read(ReadOptions {
                     database_path,
                     tick_rate: *tick_rate,
                     flash_display_duration_seconds: *flash_display_duration_seconds,
                     network_timeout: *network_timeout,
+                    ingest_options: ingest.to_ingest_options()?,
+                    database_options: database.to_database_options(),
+                    archive_pages: *archive_pages,
+                    summarizer,
+                    group_feeds_by_domain: *group_feeds_by_domain,
+                    feed_sort: *feed_sort,
+                    action_log_path: action_log.to_owned(),
+                    replay_path: replay.to_owned(),
+                    refresh_alert: *refresh_alert,
+                    visual_alert: *visual_alert,
+                    offline: *offline,
+                    entry_template: entry_template.clone(),
+                    list_density: *list_density,
+                    theme: theme.to_theme(),
+                    monochrome: theme.monochrome,
+                    idle_timeout: *idle_timeout,
+                    idle_action: *idle_action,
+                    feed_enter_action: *feed_enter_action,
+                    key_hints: !no_key_hints,
+                    as_of: *as_of,
                 }))
             }
+            Command::Demo => Ok(ValidatedOptions::Demo),
             Command::Import {
                 database_path,
                 opml_path,
+                from_miniflux_export,
+                from_newsboat_cache,
+                from_bookmarks,
                 network_timeout,
+                ingest,
+                database,
+                archive_pages,
+                output,
             } => {
                 let database_path = get_database_path(database_path)?;
+
+                // clap guarantees exactly one of these is present
+                let source = match (
+                    opml_path,
+                    from_miniflux_export,
+                    from_newsboat_cache,
+                    from_bookmarks,
+                ) {
+                    (Some(path), _, _, _) => ImportSource::Opml(path.to_owned()),
+                    (_, Some(path), _, _) => ImportSource::MinifluxExport(path.to_owned()),
+                    (_, _, Some(path), _) => ImportSource::NewsboatCache(path.to_owned()),
+                    (_, _, _, Some(path)) => ImportSource::Bookmarks(path.to_owned()),
+                    (None, None, None, None) => unreachable!("clap requires an import source"),
+                };
+
                 Ok(ValidatedOptions::Import(ImportOptions {
                     database_path,
-                    opml_path: opml_path.to_owned(),
+                    source,
+                    network_timeout: *network_timeout,
+                    ingest_options: ingest.to_ingest_options()?,
+                    database_options: database.to_database_options(),
+                    archive_pages: *archive_pages,
+                    output: *output,
+                }))
+            }
+            Command::Daemon {
+                database_path,
+                network_timeout,
+                ingest,
+                database,
+                refresh_interval,
+                metrics_address,
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::Daemon(DaemonOptions {
+                    database_path,
+                    network_timeout: *network_timeout,
+                    ingest_options: ingest.to_ingest_options()?,
+                    database_options: database.to_database_options(),
+                    refresh_interval: *refresh_interval,
+                    metrics_address: *metrics_address,
+                }))
+            }
+            Command::Refresh {
+                database_path,
+                network_timeout,
+                ingest,
+                database,
+                dry_run,
+                output,
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::Refresh(RefreshOptions {
+                    database_path,
+                    network_timeout: *network_timeout,
+                    ingest_options: ingest.to_ingest_options()?,
+                    database_options: database.to_database_options(),
+                    dry_run: *dry_run,
+                    output: *output,
+                }))
+            }
+            Command::ExportNotes {
+                database_path,
+                dir,
+                asset_cache_dir,
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::ExportNotes(ExportNotesOptions {
+                    database_path,
+                    dir: dir.to_owned(),
+                    asset_cache_dir: asset_cache_dir.to_owned(),
+                }))
+            }
+            Command::Ingest {
+                database_path,
+                feed_id,
+                feed_url,
+                network_timeout,
+                ingest,
+                database,
+            } => {
+                let database_path = get_database_path(database_path)?;
+
+                // clap guarantees exactly one of these is present
+                let feed = match (feed_id, feed_url) {
+                    (Some(feed_id), _) => ingest::IngestFeed::Id((*feed_id).into()),
+                    (_, Some(feed_url)) => ingest::IngestFeed::Link(feed_url.to_owned()),
+                    (None, None) => unreachable!("clap requires a feed"),
+                };
+
+                Ok(ValidatedOptions::IngestFeed(IngestFeedOptions {
+                    database_path,
+                    feed,
+                    network_timeout: *network_timeout,
+                    ingest_options: ingest.to_ingest_options()?,
+                    database_options: database.to_database_options(),
+                }))
+            }
+            #[cfg(feature = "imap")]
+            Command::Newsletters {
+                database_path,
+                imap_host,
+                imap_port,
+                imap_user,
+                mailbox,
+                senders,
+                network_timeout,
+                ingest,
+                database,
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::Newsletters(NewslettersOptions {
+                    database_path,
+                    imap_host: imap_host.to_owned(),
+                    imap_port: *imap_port,
+                    imap_user: imap_user.to_owned(),
+                    mailbox: mailbox.to_owned(),
+                    senders: senders.to_owned(),
+                    network_timeout: *network_timeout,
+                    ingest_options: ingest.to_ingest_options()?,
+                    database_options: database.to_database_options(),
+                }))
+            }
+            Command::Db {
+                command:
+                    DbCommand::Recompress {
+                        database_path,
+                        decompress,
+                    },
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::Recompress(RecompressOptions {
+                    database_path,
+                    compress: !decompress,
+                }))
+            }
+            Command::Db {
+                command:
+                    DbCommand::BackfillGuids {
+                        database_path,
+                        network_timeout,
+                        ingest,
+                    },
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::BackfillGuids(BackfillGuidsOptions {
+                    database_path,
                     network_timeout: *network_timeout,
+                    ingest_options: ingest.to_ingest_options()?,
                 }))
             }
+            Command::Cache {
+                command:
+                    CacheCommand::Prune {
+                        database_path,
+                        asset_cache_dir,
+                        asset_cache_size,
+                    },
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::PruneCache(PruneCacheOptions {
+                    database_path,
+                    asset_cache: crate::assets::AssetCacheOptions {
+                        dir: asset_cache_dir.to_owned(),
+                        max_bytes: asset_cache_size * 1024 * 1024,
+                    },
+                }))
+            }
+            Command::Feed {
+                command:
+                    FeedCommand::SetMaxNewEntries {
+                        database_path,
+                        feed_link,
+                        max_new_entries,
+                    },
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::SetMaxNewEntries(
+                    SetMaxNewEntriesOptions {
+                        database_path,
+                        feed_link: feed_link.to_owned(),
+                        max_new_entries: *max_new_entries,
+                    },
+                ))
+            }
+            Command::Feed {
+                command:
+                    FeedCommand::SetMarkReadAfter {
+                        database_path,
+                        feed_link,
+                        days,
+                    },
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::SetMarkReadAfter(
+                    SetMarkReadAfterOptions {
+                        database_path,
+                        feed_link: feed_link.to_owned(),
+                        days: *days,
+                    },
+                ))
+            }
+            Command::Feed {
+                command:
+                    FeedCommand::SetRetention {
+                        database_path,
+                        feed_link,
+                        retention,
+                    },
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::SetRetention(SetRetentionOptions {
+                    database_path,
+                    feed_link: feed_link.to_owned(),
+                    retention: *retention,
+                }))
+            }
+            Command::Feed {
+                command:
+                    FeedCommand::Slowest {
+                        database_path,
+                        limit,
+                    },
+            } => {
+                let database_path = get_database_path(database_path)?;
+                Ok(ValidatedOptions::SlowestFeeds(SlowestFeedsOptions {
+                    database_path,
+                    limit: *limit,
+                }))
+            }
+        }
+    }
+}
+
+const DEFAULT_TRACKING_PARAMETERS: &str =
+    "utm_*,fbclid,gclid,dclid,msclkid,yclid,mc_cid,mc_eid,igshid,_hsenc,_hsmi,mkt_tok";
+
+const DEFAULT_STRIP_ELEMENTS: &str = "script,style,noscript,iframe,object,embed";
+
+/// Options for how incoming entries are cleaned up, shared by every command that stores entries
+#[derive(Debug, clap::Args)]
+struct IngestArgs {
+    /// query parameters to strip from entry links before they are stored or opened.
+    /// a trailing `*` matches any parameter with that prefix. pass "" to strip nothing
+    #[arg(long, value_delimiter = ',', default_value = DEFAULT_TRACKING_PARAMETERS)]
+    tracking_parameters: Vec<String>,
+    /// HTML elements to remove from entry content before it is stored, along with everything inside them.
+    /// add `img` to strip images. pass "" to strip nothing
+    #[arg(long, value_delimiter = ',', default_value = DEFAULT_STRIP_ELEMENTS)]
+    strip_elements: Vec<String>,
+    /// follow redirects on new entries' links (like FeedBurner or aggregator links) when storing them,
+    /// keeping both the original and the resolved link
+    #[arg(long)]
+    resolve_redirects: bool,
+    /// compress new entries' content with zstd before storing it, which can shrink the database a lot.
+    /// entries that are already stored can be compressed with `russ db recompress`
+    #[arg(long)]
+    compress_content: bool,
+    #[command(flatten)]
+    asset_cache: AssetCacheArgs,
+    /// the most new entries a refresh may add to a feed, keeping the newest,
+    /// for firehose feeds that add hundreds at a time.
+    /// `russ feed set-max-new-entries` sets a limit for a single feed
+    #[arg(long)]
+    max_new_entries: Option<usize>,
+    /// send the cookies in this Netscape cookies.txt file, like one exported from a browser,
+    /// with feed requests, for feeds behind a login page or a bot check.
+    /// cookies feeds set are saved back to it. it is created if it does not exist
+    #[arg(long)]
+    cookie_jar: Option<PathBuf>,
+    /// how many feeds to refresh at once. by default, twice the number of CPUs
+    #[arg(long)]
+    refresh_concurrency: Option<std::num::NonZeroUsize>,
+    /// have refreshes delete old entries, keeping the newest some number of them, like `200`,
+    /// or the ones published in the last some days, like `30d`.
+    /// unread and starred entries are always kept.
+    /// `russ feed set-retention` sets a retention for a single feed
+    #[arg(long)]
+    retention: Option<crate::rss::Retention>,
+    /// store new entries dated in the future as published when they are stored,
+    /// so they do not stay at the top of their feed. they are marked either way
+    #[arg(long)]
+    clamp_future_dates: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct AssetCacheArgs {
+    /// download the images new entries reference into this directory, for reading offline
+    #[arg(long)]
+    asset_cache_dir: Option<PathBuf>,
+    /// the most the asset cache can hold, in MiB.
+    /// past this, the images of the entries cached longest ago are removed
+    #[arg(long, default_value = "512")]
+    asset_cache_size: u64,
+}
+
+impl AssetCacheArgs {
+    fn to_asset_cache_options(&self) -> Option<crate::assets::AssetCacheOptions> {
+        self.asset_cache_dir
+            .as_ref()
+            .map(|dir| crate::assets::AssetCacheOptions {
+                dir: dir.to_owned(),
+                max_bytes: self.asset_cache_size * 1024 * 1024,
+            })
+    }
+}
+
+impl IngestArgs {
+    fn to_ingest_options(&self) -> Result<crate::rss::IngestOptions> {
+        let cookie_jar = self
+            .cookie_jar
+            .as_deref()
+            .map(crate::cookies::CookieJar::open)
+            .transpose()?
+            .map(std::sync::Arc::new);
+
+        Ok(crate::rss::IngestOptions {
+            tracking_parameters: self
+                .tracking_parameters
+                .iter()
+                .filter(|tracking_parameter| !tracking_parameter.is_empty())
+                .cloned()
+                .collect(),
+            strip_elements: self
+                .strip_elements
+                .iter()
+                .filter(|element| !element.is_empty())
+                .cloned()
+                .collect(),
+            resolve_redirects: self.resolve_redirects,
+            compress_content: self.compress_content,
+            asset_cache: self.asset_cache.to_asset_cache_options(),
+            max_new_entries: self.max_new_entries,
+            cookie_jar,
+            refresh_concurrency: self.refresh_concurrency,
+            retention: self.retention,
+            clamp_future_dates: self.clamp_future_dates,
+        })
+    }
+}
+
+/// The UI's colors, as names like `cyan` or `light-red`, `#rrggbb`, or 256-color indexes like `213`.
+/// Terminals without truecolor get the nearest color they have
+#[derive(Debug, clap::Args)]
+struct ThemeArgs {
+    /// the color of the selected feed or entry
+    #[arg(long, default_value = "#ff96a7")]
+    highlight_color: ratatui::style::Color,
+    /// the color of pane and popup titles
+    #[arg(long, default_value = "cyan")]
+    title_color: ratatui::style::Color,
+    /// the color of pane borders. `reset` is the terminal's own foreground color
+    #[arg(long, default_value = "reset")]
+    border_color: ratatui::style::Color,
+    /// the color of the error pane
+    #[arg(long, default_value = "red")]
+    error_color: ratatui::style::Color,
+    /// draw without color, using bold, underlined, and dim text instead,
+    /// as with `NO_COLOR` set
+    #[arg(long)]
+    monochrome: bool,
+}
+
+impl ThemeArgs {
+    fn to_theme(&self) -> crate::colors::Theme {
+        crate::colors::Theme {
+            highlight: self.highlight_color,
+            title: self.title_color,
+            border: self.border_color,
+            error: self.error_color,
+        }
+    }
+}
+
+/// SQLite tunables for large archives, applied to every database connection
+#[derive(Debug, clap::Args)]
+struct DatabaseArgs {
+    /// the size of SQLite's page cache for each database connection, in KiB
+    #[arg(long, default_value = "8192")]
+    database_cache_size: u32,
+    /// how many bytes of the database to memory-map, which can speed up reads of large databases.
+    /// 0 disables memory-mapping
+    #[arg(long, default_value = "0")]
+    database_mmap_size: u64,
+    /// SQLite's `synchronous` setting: off, normal, full, or extra.
+    /// lower is faster, but risks losing the most recent writes if the machine loses power
+    #[arg(long, default_value = "full")]
+    database_synchronous: crate::rss::Synchronous,
+    /// the database page size in bytes, a power of two from 512 to 65536.
+    /// only takes effect for a new database, or on an existing one after running `VACUUM` on it
+    #[arg(long)]
+    database_page_size: Option<u32>,
+}
+
+impl DatabaseArgs {
+    fn to_database_options(&self) -> crate::rss::DatabaseOptions {
+        crate::rss::DatabaseOptions {
+            cache_size_kib: self.database_cache_size,
+            mmap_size: self.database_mmap_size,
+            synchronous: self.database_synchronous,
+            page_size: self.database_page_size,
         }
     }
 }
@@ -121,11 +974,30 @@ fn parse_seconds(s: &str) -> Result<time::Duration, std::num::ParseIntError> {
     Ok(time::Duration::from_secs(as_u64))
 }
 
+fn parse_minutes(s: &str) -> Result<time::Duration, std::num::ParseIntError> {
+    let as_u64 = s.parse::<u64>()?;
+    Ok(time::Duration::from_secs(as_u64 * 60))
+}
+
 /// internal, validated options for the normal reader mode
 #[derive(Debug)]
 enum ValidatedOptions {
     Read(ReadOptions),
+    Demo,
     Import(ImportOptions),
+    Daemon(DaemonOptions),
+    Refresh(RefreshOptions),
+    ExportNotes(ExportNotesOptions),
+    IngestFeed(IngestFeedOptions),
+    #[cfg(feature = "imap")]
+    Newsletters(NewslettersOptions),
+    Recompress(RecompressOptions),
+    BackfillGuids(BackfillGuidsOptions),
+    PruneCache(PruneCacheOptions),
+    SetMaxNewEntries(SetMaxNewEntriesOptions),
+    SetMarkReadAfter(SetMarkReadAfterOptions),
+    SetRetention(SetRetentionOptions),
+    SlowestFeeds(SlowestFeedsOptions),
 }
 
 #[derive(Clone, Debug)]
@@ -134,13 +1006,144 @@ struct ReadOptions {
     tick_rate: u64,
     flash_display_duration_seconds: time::Duration,
     network_timeout: time::Duration,
+    ingest_options: crate::rss::IngestOptions,
+    database_options: crate::rss::DatabaseOptions,
+    archive_pages: usize,
+    summarizer: Option<summary::Summarizer>,
+    group_feeds_by_domain: bool,
+    feed_sort: crate::rss::FeedSort,
+    action_log_path: Option<PathBuf>,
+    replay_path: Option<PathBuf>,
+    refresh_alert: crate::app::RefreshAlert,
+    visual_alert: bool,
+    offline: bool,
+    entry_template: crate::template::EntryTemplate,
+    list_density: crate::app::ListDensity,
+    theme: crate::colors::Theme,
+    /// draw without color, whatever the terminal supports
+    monochrome: bool,
+    idle_timeout: Option<time::Duration>,
+    idle_action: crate::app::IdleAction,
+    feed_enter_action: crate::app::FeedEnterAction,
+    key_hints: bool,
+    as_of: Option<chrono::NaiveDate>,
 }
 
 #[derive(Debug)]
 struct ImportOptions {
     database_path: PathBuf,
-    opml_path: PathBuf,
+    source: ImportSource,
+    network_timeout: time::Duration,
+    ingest_options: crate::rss::IngestOptions,
+    database_options: crate::rss::DatabaseOptions,
+    archive_pages: usize,
+    output: progress::OutputFormat,
+}
+
+#[derive(Clone, Debug)]
+enum ImportSource {
+    Opml(PathBuf),
+    MinifluxExport(PathBuf),
+    NewsboatCache(PathBuf),
+    Bookmarks(PathBuf),
+}
+
+#[derive(Debug)]
+struct DaemonOptions {
+    database_path: PathBuf,
+    network_timeout: time::Duration,
+    ingest_options: crate::rss::IngestOptions,
+    database_options: crate::rss::DatabaseOptions,
+    refresh_interval: time::Duration,
+    metrics_address: Option<std::net::SocketAddr>,
+}
+
+#[derive(Debug)]
+struct RefreshOptions {
+    database_path: PathBuf,
+    network_timeout: time::Duration,
+    ingest_options: crate::rss::IngestOptions,
+    database_options: crate::rss::DatabaseOptions,
+    dry_run: bool,
+    output: progress::OutputFormat,
+}
+
+#[derive(Debug)]
+struct ExportNotesOptions {
+    database_path: PathBuf,
+    dir: PathBuf,
+    asset_cache_dir: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+struct IngestFeedOptions {
+    database_path: PathBuf,
+    feed: ingest::IngestFeed,
+    network_timeout: time::Duration,
+    ingest_options: crate::rss::IngestOptions,
+    database_options: crate::rss::DatabaseOptions,
+}
+
+#[cfg(feature = "imap")]
+#[derive(Debug)]
+struct NewslettersOptions {
+    database_path: PathBuf,
+    imap_host: String,
+    imap_port: u16,
+    imap_user: String,
+    mailbox: String,
+    /// what the senders of the messages to fetch contain
+    senders: Vec<String>,
+    network_timeout: time::Duration,
+    ingest_options: crate::rss::IngestOptions,
+    database_options: crate::rss::DatabaseOptions,
+}
+
+#[derive(Debug)]
+struct RecompressOptions {
+    database_path: PathBuf,
+    /// compress, or otherwise decompress
+    compress: bool,
+}
+
+#[derive(Debug)]
+struct PruneCacheOptions {
+    database_path: PathBuf,
+    asset_cache: crate::assets::AssetCacheOptions,
+}
+
+#[derive(Debug)]
+struct SetMaxNewEntriesOptions {
+    database_path: PathBuf,
+    feed_link: String,
+    max_new_entries: Option<usize>,
+}
+
+#[derive(Debug)]
+struct SetMarkReadAfterOptions {
+    database_path: PathBuf,
+    feed_link: String,
+    days: Option<u32>,
+}
+
+#[derive(Debug)]
+struct SetRetentionOptions {
+    database_path: PathBuf,
+    feed_link: String,
+    retention: Option<crate::rss::Retention>,
+}
+
+#[derive(Debug)]
+struct SlowestFeedsOptions {
+    database_path: PathBuf,
+    limit: usize,
+}
+
+#[derive(Debug)]
+struct BackfillGuidsOptions {
+    database_path: PathBuf,
     network_timeout: time::Duration,
+    ingest_options: crate::rss::IngestOptions,
 }
 
 fn get_database_path(database_path: &Option<PathBuf>) -> std::io::Result<PathBuf> {
@@ -162,12 +1165,30 @@ fn get_database_path(database_path: &Option<PathBuf>) -> std::io::Result<PathBuf
     Ok(database_path)
 }
 
-pub enum Event<I> {
+pub(crate) enum Event<I> {
     Input(I),
     Tick,
+    /// the io thread's response to a request
+    Io(io::Response),
 }
 
 fn run_reader(options: ReadOptions) -> Result<()> {
+    let mut middlewares = middleware::middlewares(options.action_log_path.as_deref())?;
+
+    let replay = options
+        .replay_path
+        .as_deref()
+        .map(middleware::read_action_log)
+        .transpose()?
+        .unwrap_or_default();
+
+    let mut journal_path = session::journal_path(&options.database_path);
+
+    let restored_session = match journal_path.as_deref().map(session::load).transpose()? {
+        Some(Some(state)) if session::confirm_restore()? => Some(state),
+        _ => None,
+    };
+
     enable_raw_mode()?;
 
     let mut stdout = stdout();
@@ -183,6 +1204,8 @@ fn run_reader(options: ReadOptions) -> Result<()> {
 
     let event_tx_clone = event_tx.clone();
 
+    let io_event_tx = event_tx.clone();
+
     let tick_rate = time::Duration::from_millis(options.tick_rate);
 
     thread::spawn(move || {
@@ -209,19 +1232,48 @@ fn run_reader(options: ReadOptions) -> Result<()> {
 
     let (io_tx, io_rx) = mpsc::channel();
 
-    let io_tx_clone = io_tx.clone();
-
     let mut app = App::new(options, event_tx_clone, io_tx)?;
 
-    let cloned_app = app.clone();
+    if let Some(state) = restored_session {
+        app.restore_session(state)?;
+    }
+
+    let http_client = app.http_client();
 
     terminal.clear()?;
 
+    // if a `russ daemon` is running against this database, let it do our refreshing
+    let daemon = daemon::Client::connect(&options_clone.database_path);
+
+    if daemon.is_some() {
+        let watching_app = app.clone();
+        let database_path = options_clone.database_path.clone();
+
+        thread::spawn(move || {
+            daemon::watch(&database_path, || {
+                watching_app.update_current_feed_and_entries()?;
+                watching_app.force_redraw()
+            })
+        });
+    }
+
     // spawn this thread to handle receiving messages to performing blocking network and db IO
     let io_thread = thread::spawn(move || -> Result<()> {
-        io::io_loop(cloned_app, io_tx_clone, io_rx, &options_clone)
+        io::io_loop(
+            io_rx,
+            move |response| Ok(io_event_tx.send(Event::Io(response))?),
+            http_client,
+            &options_clone,
+            daemon,
+        )
     });
 
+    // replayed actions come in one per tick, so it is possible to watch what happens
+    let mut replay = replay.into_iter();
+
+    let mut journaled_session = None;
+    let mut last_journaled_at = time::Instant::now();
+
     // this is basically "the Elm Architecture".
     //
     // more or less:
@@ -233,10 +1285,47 @@ fn run_reader(options: ReadOptions) -> Result<()> {
 
         let event = event_rx.recv()?;
 
-        let action = get_action(&app, event);
+        let action = match event {
+            // responses are applied as they arrive rather than being actions,
+            // as they are not something the user did
+            Event::Io(response) => {
+                app.handle_io_response(response)?;
+                None
+            }
+            Event::Tick => replay.next().or_else(|| get_action(&app, event)),
+            event => get_action(&app, event),
+        };
 
         if let Some(action) = action {
-            update(&mut app, action)?;
+            let switches_database = action == Action::SwitchDatabase;
+
+            middleware::dispatch(&mut app, &mut middlewares, action)?;
+
+            // the session is journaled next to the database it is for
+            if switches_database {
+                let database_journal_path = session::journal_path(&app.database_path());
+
+                if database_journal_path != journal_path {
+                    if let Some(journal_path) = &journal_path {
+                        session::remove(journal_path)?;
+                    }
+                    journal_path = database_journal_path;
+                    journaled_session = None;
+                }
+            }
+        }
+
+        if let Some(journal_path) = &journal_path {
+            if last_journaled_at.elapsed() >= session::JOURNAL_INTERVAL {
+                let session_state = app.session_state();
+
+                if journaled_session.as_ref() != Some(&session_state) {
+                    session::save(journal_path, &session_state)?;
+                    journaled_session = Some(session_state);
+                }
+
+                last_journaled_at = time::Instant::now();
+            }
         }
 
         if app.should_quit() {
@@ -252,9 +1341,14 @@ fn run_reader(options: ReadOptions) -> Result<()> {
         .join()
         .expect("Unable to join IO thread to main thread")?;
 
+    if let Some(journal_path) = &journal_path {
+        session::remove(journal_path)?;
+    }
+
     Ok(())
 }
 
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 enum Action {
     Quit,
     MoveLeft,
@@ -265,32 +1359,121 @@ enum Action {
     PageDown,
     RefreshAll,
     RefreshFeed,
+    PreviewRefreshAll,
     ToggleHelp,
     ToggleReadMode,
     EnterEditingMode,
     OpenLinkInBrowser,
+    OpenFeedLinkInBrowser,
     CopyLinkToClipboard,
+    CopyFeedLinkToClipboard,
+    ResolveLink,
+    RediscoverFeed,
+    EnqueueEntry,
+    ToggleGroupFeedsByDomain,
+    /// lists the feeds the next way: by title, updated, unread, or added
+    CycleFeedSort,
+    TogglePreview,
+    ToggleEntryInfo,
+    ToggleOfflineMode,
+    ScrollTitleLeft,
+    ScrollTitleRight,
+    /// show the screen again after it was locked for being idle
+    Unlock,
+    ReadNextQueuedEntry,
+    MarkFeedRead,
+    ToggleSplitEntry,
+    ToggleSplitEntryFocus,
+    FetchFullContent,
+    JumpBack,
+    JumpForward,
+    ToggleTimeTravel,
+    TimeTravelEarlier,
+    TimeTravelLater,
+    /// counting from 0
+    ReadRelatedEntry(usize),
+    /// flashes that a key, by this name, does nothing where it was pressed
+    HintUnboundKey(String),
+    /// asks whether to move the selected feed to where it appears to have moved
+    PromptSuggestedFeedMove,
+    AcceptSuggestedFeedMove,
+    DismissSuggestedFeedMove,
+    ToggleMacroRecording,
+    ReplayMacro,
+    Undo,
+    ToggleEntryRead(crate::rss::EntryId),
     Tick,
     SubscribeToFeed,
+    ToggleDirectorySearch,
+    DirectoryNext,
+    DirectoryPrevious,
+    SubscribeToDirectoryFeed,
+    EnterSearchMode,
+    PushSearchChar(char),
+    DeleteSearchChar,
+    SearchEntries,
+    ClearSearch,
     PushInputChar(char),
     DeleteInputChar,
+    /// asks whether to delete the selected feed
+    PromptDeleteFeed,
     DeleteFeed,
+    /// the first key of a two-key command like `dd`
+    PendKey(char),
+    EnterSwitchDatabaseMode,
+    PushDatabasePathChar(char),
+    DeleteDatabasePathChar,
+    SwitchDatabase,
+    EnterEditFeedHttpSettingsMode,
+    PushFeedHttpSettingsChar(char),
+    DeleteFeedHttpSettingsChar,
+    SaveFeedHttpSettings,
+    EnterEditFeedTitleMode,
+    PushFeedTitleChar(char),
+    DeleteFeedTitleChar,
+    SaveFeedTitle,
     EnterNormalMode,
     ClearErrorFlash,
     SelectAndShowCurrentEntry,
     ToggleReadStatus,
+    ToggleStarred,
+    /// lists the starred entries in every feed, or goes back to the current feed's entries
+    ToggleStarredEntries,
+    /// lists the current feed's entries by when they were published or first seen
+    ToggleEntrySort,
 }
 
 fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
+    // the key that unlocks the screen does nothing else, as what it would do cannot be seen
+    if app.is_locked() {
+        return match event {
+            Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
+                Some(Action::Unlock)
+            }
+            Event::Tick => Some(Action::Tick),
+            _ => None,
+        };
+    }
+
     match app.mode() {
         Mode::Normal => match event {
             Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
-                match (key_event.code, key_event.modifiers) {
+                let action = match (key_event.code, key_event.modifiers) {
                     (KeyCode::Char('q'), _)
                     | (KeyCode::Char('c'), KeyModifiers::CONTROL)
                     | (KeyCode::Esc, _) => {
                         if !app.error_flash_is_empty() {
                             Some(Action::ClearErrorFlash)
+                        } else if app.shows_entry_info() {
+                            Some(Action::ToggleEntryInfo)
+                        } else if app.shows_search_results()
+                            && !matches!(app.selected(), Selected::Entry(_))
+                        {
+                            Some(Action::ClearSearch)
+                        } else if app.shows_starred_entries()
+                            && !matches!(app.selected(), Selected::Entry(_))
+                        {
+                            Some(Action::ToggleStarredEntries)
                         } else {
                             Some(Action::Quit)
                         }
@@ -300,6 +1483,7 @@ fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
                         _ => Some(Action::ToggleReadStatus),
                     },
                     (KeyCode::Char('x'), KeyModifiers::NONE) => Some(Action::RefreshAll),
+                    (KeyCode::Char('X'), _) => Some(Action::PreviewRefreshAll),
                     (KeyCode::Left, _) | (KeyCode::Char('h'), _) => Some(Action::MoveLeft),
                     (KeyCode::Right, _) | (KeyCode::Char('l'), _) => Some(Action::MoveRight),
                     (KeyCode::Down, _) | (KeyCode::Char('j'), _) => Some(Action::MoveDown),
@@ -311,6 +1495,11 @@ fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
                         Some(Action::PageDown)
                     }
                     (KeyCode::Enter, _) => match app.selected() {
+                        Selected::Feeds => match app.feed_enter_action() {
+                            FeedEnterAction::Entries => Some(Action::MoveRight),
+                            FeedEnterAction::OpenSite => Some(Action::OpenLinkInBrowser),
+                            FeedEnterAction::Refresh => Some(Action::RefreshFeed),
+                        },
                         Selected::Entries | Selected::Entry(_) => {
                             if app.has_entries() && app.has_current_entry() {
                                 Some(Action::SelectAndShowCurrentEntry)
@@ -322,20 +1511,109 @@ fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
                     },
                     (KeyCode::Char('?'), _) => Some(Action::ToggleHelp),
                     (KeyCode::Char('a'), _) => Some(Action::ToggleReadMode),
+                    (KeyCode::Char('A'), _) => match app.selected() {
+                        Selected::Feeds | Selected::Entries => Some(Action::MarkFeedRead),
+                        _ => None,
+                    },
+                    (KeyCode::Char('/'), _) => Some(Action::EnterSearchMode),
+                    (KeyCode::Char('s'), KeyModifiers::NONE) => match app.selected() {
+                        Selected::Entries | Selected::Entry(_) => Some(Action::ToggleStarred),
+                        _ => None,
+                    },
+                    (KeyCode::Char('*'), _) => Some(Action::ToggleStarredEntries),
+                    (KeyCode::Char('t'), KeyModifiers::NONE) => match app.selected() {
+                        Selected::Feeds | Selected::Entries => Some(Action::ToggleEntrySort),
+                        _ => None,
+                    },
+                    (KeyCode::Char('o'), KeyModifiers::CONTROL) => Some(Action::JumpBack),
+                    // terminals send ctrl-i as tab
+                    (KeyCode::Tab, _) | (KeyCode::Char('i'), KeyModifiers::CONTROL) => {
+                        Some(Action::JumpForward)
+                    }
                     (KeyCode::Char('e'), _) | (KeyCode::Char('i'), _) => {
                         Some(Action::EnterEditingMode)
                     }
                     (KeyCode::Char('c'), _) => Some(Action::CopyLinkToClipboard),
                     (KeyCode::Char('o'), _) => Some(Action::OpenLinkInBrowser),
+                    (KeyCode::Char('C'), _) => Some(Action::CopyFeedLinkToClipboard),
+                    (KeyCode::Char('O'), _) => Some(Action::OpenFeedLinkInBrowser),
+                    (KeyCode::Char('L'), _) => Some(Action::ResolveLink),
+                    (KeyCode::Char('R'), _) => match app.selected() {
+                        Selected::Feeds => Some(Action::RediscoverFeed),
+                        _ => None,
+                    },
+                    (KeyCode::Char('+'), _) => Some(Action::EnqueueEntry),
+                    (KeyCode::Char('S'), _) => Some(Action::ToggleSplitEntry),
+                    (KeyCode::Char('w'), KeyModifiers::NONE) => Some(Action::ToggleSplitEntryFocus),
+                    (KeyCode::Char('f'), KeyModifiers::NONE) => match app.selected() {
+                        Selected::Entry(_) => Some(Action::FetchFullContent),
+                        _ => None,
+                    },
+                    (KeyCode::Char('g'), KeyModifiers::NONE) => {
+                        Some(Action::ToggleGroupFeedsByDomain)
+                    }
+                    (KeyCode::Char('F'), _) => Some(Action::CycleFeedSort),
+                    (KeyCode::Char('M'), _) if app.has_suggested_feed_move() => {
+                        Some(Action::PromptSuggestedFeedMove)
+                    }
+                    (KeyCode::Char('n'), KeyModifiers::NONE) => Some(Action::ReadNextQueuedEntry),
+                    (KeyCode::Char(c @ '1'..='9'), KeyModifiers::NONE)
+                        if matches!(app.selected(), Selected::Entry(_)) =>
+                    {
+                        let index = c.to_digit(10).unwrap_or_default() as usize - 1;
+                        (index < crate::app::RELATED_ENTRIES_LIMIT)
+                            .then_some(Action::ReadRelatedEntry(index))
+                    }
+                    (KeyCode::Char('m'), KeyModifiers::NONE) => Some(Action::ToggleMacroRecording),
+                    (KeyCode::Char('p'), KeyModifiers::NONE) => Some(Action::TogglePreview),
+                    (KeyCode::Char('I'), _) => Some(Action::ToggleEntryInfo),
+                    (KeyCode::Char('N'), _) => Some(Action::ToggleOfflineMode),
+                    (KeyCode::Char('<'), _) => Some(Action::ScrollTitleLeft),
+                    (KeyCode::Char('>'), _) => Some(Action::ScrollTitleRight),
+                    (KeyCode::Char('T'), _) => Some(Action::ToggleTimeTravel),
+                    (KeyCode::Char('['), _) => Some(Action::TimeTravelEarlier),
+                    (KeyCode::Char(']'), _) => Some(Action::TimeTravelLater),
+                    (KeyCode::Char('@'), _) => Some(Action::ReplayMacro),
+                    (KeyCode::Char('u'), KeyModifiers::NONE) => Some(Action::Undo),
+                    (KeyCode::Char('D'), _) => Some(Action::EnterSwitchDatabaseMode),
+                    (KeyCode::Char('H'), _) => match app.selected() {
+                        Selected::Feeds => Some(Action::EnterEditFeedHttpSettingsMode),
+                        _ => None,
+                    },
+                    (KeyCode::Char('E'), _) => match app.selected() {
+                        Selected::Feeds => Some(Action::EnterEditFeedTitleMode),
+                        _ => None,
+                    },
+                    (KeyCode::Char('d'), KeyModifiers::NONE) if app.pending_key() == Some('d') => {
+                        Some(Action::PromptDeleteFeed)
+                    }
+                    (KeyCode::Char('d'), KeyModifiers::NONE) => match app.selected() {
+                        Selected::Feeds => Some(Action::PendKey('d')),
+                        _ => None,
+                    },
                     _ => None,
-                }
+                };
+
+                action.or_else(|| {
+                    app.key_hints()
+                        .then(|| Action::HintUnboundKey(key_name(&key_event)))
+                })
             }
-            Event::Input(_) => None,
+            Event::Input(_) | Event::Io(_) => None,
             Event::Tick => Some(Action::Tick),
         },
         Mode::Editing => match event {
             Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
+                let shows_directory_results = app.shows_directory_results();
+
                 match key_event.code {
+                    KeyCode::Tab => Some(Action::ToggleDirectorySearch),
+                    KeyCode::Down if shows_directory_results => Some(Action::DirectoryNext),
+                    KeyCode::Up if shows_directory_results => Some(Action::DirectoryPrevious),
+                    KeyCode::Enter if shows_directory_results => {
+                        Some(Action::SubscribeToDirectoryFeed)
+                    }
+                    KeyCode::Esc if shows_directory_results => Some(Action::ToggleDirectorySearch),
                     KeyCode::Enter => {
                         if !app.feed_subscription_input_is_empty() {
                             Some(Action::SubscribeToFeed)
@@ -345,23 +1623,143 @@ fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
                     }
                     KeyCode::Char(c) => Some(Action::PushInputChar(c)),
                     KeyCode::Backspace => Some(Action::DeleteInputChar),
-                    KeyCode::Delete => Some(Action::DeleteFeed),
+                    KeyCode::Delete => Some(Action::PromptDeleteFeed),
+                    KeyCode::Esc => Some(Action::EnterNormalMode),
+                    _ => None,
+                }
+            }
+            Event::Input(_) | Event::Io(_) => None,
+            Event::Tick => Some(Action::Tick),
+        },
+        Mode::MovingFeed => match event {
+            Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Char('y') => Some(Action::AcceptSuggestedFeedMove),
+                    KeyCode::Char('n') => Some(Action::DismissSuggestedFeedMove),
+                    _ => Some(Action::EnterNormalMode),
+                }
+            }
+            Event::Input(_) | Event::Io(_) => None,
+            Event::Tick => Some(Action::Tick),
+        },
+        Mode::DeletingFeed => match event {
+            Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Char('y') => Some(Action::DeleteFeed),
+                    _ => Some(Action::EnterNormalMode),
+                }
+            }
+            Event::Input(_) | Event::Io(_) => None,
+            Event::Tick => Some(Action::Tick),
+        },
+        Mode::SwitchingDatabase => match event {
+            Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Enter => {
+                        if !app.database_path_input_is_empty() {
+                            Some(Action::SwitchDatabase)
+                        } else {
+                            None
+                        }
+                    }
+                    KeyCode::Char(c) => Some(Action::PushDatabasePathChar(c)),
+                    KeyCode::Backspace => Some(Action::DeleteDatabasePathChar),
+                    KeyCode::Esc => Some(Action::EnterNormalMode),
+                    _ => None,
+                }
+            }
+            Event::Input(_) | Event::Io(_) => None,
+            Event::Tick => Some(Action::Tick),
+        },
+        Mode::EditingFeedHttpSettings => match event {
+            Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Enter => Some(Action::SaveFeedHttpSettings),
+                    KeyCode::Char(c) => Some(Action::PushFeedHttpSettingsChar(c)),
+                    KeyCode::Backspace => Some(Action::DeleteFeedHttpSettingsChar),
+                    KeyCode::Esc => Some(Action::EnterNormalMode),
+                    _ => None,
+                }
+            }
+            Event::Input(_) | Event::Io(_) => None,
+            Event::Tick => Some(Action::Tick),
+        },
+        Mode::EditingFeedTitle => match event {
+            Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Enter => Some(Action::SaveFeedTitle),
+                    KeyCode::Char(c) => Some(Action::PushFeedTitleChar(c)),
+                    KeyCode::Backspace => Some(Action::DeleteFeedTitleChar),
                     KeyCode::Esc => Some(Action::EnterNormalMode),
                     _ => None,
                 }
             }
-            Event::Input(_) => None,
+            Event::Input(_) | Event::Io(_) => None,
+            Event::Tick => Some(Action::Tick),
+        },
+        Mode::Searching => match event {
+            Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Enter => {
+                        if !app.search_input_is_empty() {
+                            Some(Action::SearchEntries)
+                        } else {
+                            None
+                        }
+                    }
+                    KeyCode::Char(c) => Some(Action::PushSearchChar(c)),
+                    KeyCode::Backspace => Some(Action::DeleteSearchChar),
+                    KeyCode::Esc => Some(Action::EnterNormalMode),
+                    _ => None,
+                }
+            }
+            Event::Input(_) | Event::Io(_) => None,
             Event::Tick => Some(Action::Tick),
         },
     }
 }
 
+/// How a key is written in the help, like `j`, `ctrl-o`, or `Enter`
+fn key_name(key_event: &KeyEvent) -> String {
+    let key = match key_event.code {
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        KeyCode::BackTab => "Shift-Tab".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        code => format!("{code:?}"),
+    };
+
+    if key_event.modifiers.contains(KeyModifiers::CONTROL) {
+        format!("ctrl-{key}")
+    } else if key_event.modifiers.contains(KeyModifiers::ALT) {
+        format!("alt-{key}")
+    } else {
+        key
+    }
+}
+
 fn update(app: &mut App, action: Action) -> Result<()> {
+    if action != Action::Tick {
+        app.record_activity();
+    }
+
+    // the second key of a two-key command has to come right after the first
+    if !matches!(action, Action::Tick | Action::PendKey(_)) {
+        app.clear_pending_key();
+    }
+
     match action {
-        Action::Tick => (),
+        Action::Tick => {
+            app.expire_flash();
+            app.expire_refreshed_entries_marker();
+            app.lock_if_idle();
+        }
+        Action::Unlock => app.unlock(),
         Action::Quit => app.set_should_quit(true),
         Action::RefreshAll => app.refresh_feeds()?,
         Action::RefreshFeed => app.refresh_feed()?,
+        Action::PreviewRefreshAll => app.preview_refresh_feeds()?,
         Action::MoveLeft => app.on_left()?,
         Action::MoveDown => app.on_down()?,
         Action::MoveUp => app.on_up()?,
@@ -371,13 +1769,71 @@ fn update(app: &mut App, action: Action) -> Result<()> {
         Action::ToggleHelp => app.toggle_help()?,
         Action::ToggleReadMode => app.toggle_read_mode()?,
         Action::ToggleReadStatus => app.toggle_read()?,
+        Action::ToggleStarred => app.toggle_starred()?,
+        Action::ToggleStarredEntries => app.toggle_starred_entries()?,
+        Action::ToggleEntrySort => app.toggle_entry_sort()?,
         Action::EnterEditingMode => app.set_mode(Mode::Editing),
         Action::CopyLinkToClipboard => app.put_current_link_in_clipboard()?,
         Action::OpenLinkInBrowser => app.open_link_in_browser()?,
+        Action::CopyFeedLinkToClipboard => app.put_current_feed_link_in_clipboard()?,
+        Action::OpenFeedLinkInBrowser => app.open_feed_link_in_browser()?,
+        Action::ResolveLink => app.resolve_current_entry_link()?,
+        Action::RediscoverFeed => app.rediscover_feed()?,
+        Action::EnqueueEntry => app.enqueue_current_entry()?,
+        Action::ToggleGroupFeedsByDomain => app.toggle_group_feeds_by_domain()?,
+        Action::CycleFeedSort => app.cycle_feed_sort()?,
+        Action::TogglePreview => app.toggle_preview(),
+        Action::ToggleEntryInfo => app.toggle_entry_info()?,
+        Action::ToggleOfflineMode => app.toggle_offline(),
+        Action::ScrollTitleLeft => app.scroll_title_left(),
+        Action::ScrollTitleRight => app.scroll_title_right(),
+        Action::ReadNextQueuedEntry => app.read_next_queued_entry()?,
+        Action::MarkFeedRead => app.mark_current_feed_read()?,
+        Action::ToggleSplitEntry => app.toggle_split_entry()?,
+        Action::ToggleSplitEntryFocus => app.toggle_split_entry_focus(),
+        Action::FetchFullContent => app.fetch_full_content()?,
+        Action::JumpBack => app.jump_back()?,
+        Action::JumpForward => app.jump_forward()?,
+        Action::ToggleTimeTravel => app.toggle_time_travel()?,
+        Action::TimeTravelEarlier => app.time_travel_earlier()?,
+        Action::TimeTravelLater => app.time_travel_later()?,
+        Action::ReadRelatedEntry(index) => app.read_related_entry(index)?,
+        Action::HintUnboundKey(key) => {
+            app.flash(format!("no action for '{key}' here — press ? for help"))
+        }
+        Action::PromptSuggestedFeedMove => app.prompt_suggested_feed_move(),
+        Action::AcceptSuggestedFeedMove => app.accept_suggested_feed_move()?,
+        Action::DismissSuggestedFeedMove => app.dismiss_suggested_feed_move()?,
+        // handled by middleware before they get here
+        Action::ToggleMacroRecording | Action::ReplayMacro | Action::Undo => (),
+        Action::ToggleEntryRead(entry_id) => app.toggle_entry_read(entry_id)?,
         Action::SubscribeToFeed => app.subscribe_to_feed()?,
+        Action::ToggleDirectorySearch => app.toggle_directory_search(),
+        Action::DirectoryNext => app.directory_next(),
+        Action::DirectoryPrevious => app.directory_previous(),
+        Action::SubscribeToDirectoryFeed => app.subscribe_to_directory_feed()?,
+        Action::EnterSearchMode => app.set_mode(Mode::Searching),
+        Action::PushSearchChar(c) => app.push_search_input(c),
+        Action::DeleteSearchChar => app.pop_search_input(),
+        Action::SearchEntries => app.search_entries()?,
+        Action::ClearSearch => app.clear_search()?,
         Action::PushInputChar(c) => app.push_feed_subscription_input(c),
         Action::DeleteInputChar => app.pop_feed_subscription_input(),
+        Action::PromptDeleteFeed => app.prompt_delete_feed(),
         Action::DeleteFeed => app.delete_feed()?,
+        Action::PendKey(key) => app.set_pending_key(key),
+        Action::EnterSwitchDatabaseMode => app.enter_switch_database_mode(),
+        Action::PushDatabasePathChar(c) => app.push_database_path_input(c),
+        Action::DeleteDatabasePathChar => app.pop_database_path_input(),
+        Action::SwitchDatabase => app.switch_database()?,
+        Action::EnterEditFeedHttpSettingsMode => app.enter_edit_feed_http_settings_mode()?,
+        Action::PushFeedHttpSettingsChar(c) => app.push_feed_http_settings_input(c),
+        Action::DeleteFeedHttpSettingsChar => app.pop_feed_http_settings_input(),
+        Action::SaveFeedHttpSettings => app.save_feed_http_settings()?,
+        Action::EnterEditFeedTitleMode => app.enter_edit_feed_title_mode(),
+        Action::PushFeedTitleChar(c) => app.push_feed_title_input(c),
+        Action::DeleteFeedTitleChar => app.pop_feed_title_input(),
+        Action::SaveFeedTitle => app.save_feed_title()?,
         Action::EnterNormalMode => app.set_mode(Mode::Normal),
         Action::ClearErrorFlash => app.clear_error_flash(),
         Action::SelectAndShowCurrentEntry => app.select_and_show_current_entry()?,
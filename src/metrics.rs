@@ -0,0 +1,175 @@
+//! A small HTTP endpoint that `russ daemon` can expose,
+//! so it can be monitored like any other service.
+//!
+//! - `GET /metrics` returns metrics in the Prometheus text format
+//! - `GET /health` returns `200 OK` if the database is reachable, `503` otherwise
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+pub(crate) struct Metrics {
+    refreshes_total: u64,
+    feed_refreshes_total: u64,
+    feed_refresh_failures_total: u64,
+    last_refresh_duration: Option<Duration>,
+    last_refresh_at: Option<DateTime<Utc>>,
+    last_refresh_failures: usize,
+}
+
+impl Metrics {
+    pub(crate) fn record_refresh(&mut self, succeeded: usize, failed: usize, duration: Duration) {
+        self.refreshes_total += 1;
+        self.feed_refreshes_total += (succeeded + failed) as u64;
+        self.feed_refresh_failures_total += failed as u64;
+        self.last_refresh_duration = Some(duration);
+        self.last_refresh_at = Some(Utc::now());
+        self.last_refresh_failures = failed;
+    }
+
+    fn render(&self, feeds_count: usize) -> String {
+        let mut out = String::new();
+
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            let _ = writeln!(out, "{name} {value}");
+        };
+
+        metric(
+            "russ_feeds",
+            "gauge",
+            "Number of subscribed feeds.",
+            feeds_count.to_string(),
+        );
+        metric(
+            "russ_refreshes_total",
+            "counter",
+            "Number of completed refreshes.",
+            self.refreshes_total.to_string(),
+        );
+        metric(
+            "russ_feed_refreshes_total",
+            "counter",
+            "Number of individual feed refreshes attempted.",
+            self.feed_refreshes_total.to_string(),
+        );
+        metric(
+            "russ_feed_refresh_failures_total",
+            "counter",
+            "Number of individual feed refreshes that failed.",
+            self.feed_refresh_failures_total.to_string(),
+        );
+        metric(
+            "russ_last_refresh_failures",
+            "gauge",
+            "Number of feeds that failed to refresh in the most recent refresh.",
+            self.last_refresh_failures.to_string(),
+        );
+        metric(
+            "russ_last_refresh_duration_seconds",
+            "gauge",
+            "Duration of the most recent refresh.",
+            self.last_refresh_duration
+                .map(|duration| duration.as_secs_f64())
+                .unwrap_or(0.0)
+                .to_string(),
+        );
+        metric(
+            "russ_last_refresh_timestamp_seconds",
+            "gauge",
+            "Unix timestamp of the most recent refresh.",
+            self.last_refresh_at
+                .map(|at| at.timestamp())
+                .unwrap_or(0)
+                .to_string(),
+        );
+
+        out
+    }
+}
+
+/// Serves `/metrics` and `/health` on `address`, forever.
+pub(crate) fn serve(
+    address: SocketAddr,
+    metrics: Arc<Mutex<Metrics>>,
+    connection_pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+) -> Result<()> {
+    let listener = TcpListener::bind(address)?;
+
+    for stream in listener.incoming() {
+        // one bad client should not take the endpoint down
+        if let Err(e) = stream
+            .map_err(anyhow::Error::from)
+            .and_then(|stream| respond(stream, &metrics, &connection_pool))
+        {
+            eprintln!("metrics request failed: {e:#}");
+        }
+    }
+
+    Ok(())
+}
+
+fn respond(
+    mut stream: TcpStream,
+    metrics: &Mutex<Metrics>,
+    connection_pool: &r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
+) -> Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let feeds_count = connection_pool
+        .get()
+        .map_err(anyhow::Error::from)
+        .and_then(|conn| crate::rss::get_feed_ids(&conn))
+        .map(|feed_ids| feed_ids.len());
+
+    let (status, body) = match (method, path) {
+        ("GET", "/metrics") => match feeds_count {
+            Ok(feeds_count) => ("200 OK", metrics.lock().unwrap().render(feeds_count)),
+            Err(e) => ("503 Service Unavailable", format!("{e:#}\n")),
+        },
+        ("GET", "/health") => match feeds_count {
+            Ok(_) => ("200 OK", "ok\n".to_string()),
+            Err(e) => ("503 Service Unavailable", format!("{e:#}\n")),
+        },
+        _ => ("404 Not Found", "not found\n".to_string()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_renders_prometheus_text() {
+        let mut metrics = Metrics::default();
+        metrics.record_refresh(9, 1, Duration::from_millis(1500));
+
+        let rendered = metrics.render(10);
+
+        assert!(rendered.contains("# TYPE russ_feeds gauge\nruss_feeds 10\n"));
+        assert!(rendered.contains("russ_feed_refreshes_total 10\n"));
+        assert!(rendered.contains("russ_feed_refresh_failures_total 1\n"));
+        assert!(rendered.contains("russ_last_refresh_duration_seconds 1.5\n"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Middleware sees every `Action` on its way to `update()`.
+//!
+//! Each middleware can pass an action along, swallow it, or turn it into several actions,
+//! which is how actions are logged, how keyboard macros are recorded and replayed,
+//! and how actions are tracked so they can be undone.
+
+use crate::app::App;
+use crate::Action;
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::Path;
+
+pub(crate) trait Middleware {
+    /// Returns the actions to pass on to the next middleware, and eventually to `update()`
+    fn handle(&mut self, app: &App, action: Action) -> Result<Vec<Action>>;
+}
+
+/// The middleware every reader runs with, in the order actions pass through them
+pub(crate) fn middlewares(action_log_path: Option<&Path>) -> Result<Vec<Box<dyn Middleware>>> {
+    let mut middlewares: Vec<Box<dyn Middleware>> = vec![];
+
+    // first, so the log records what was actually pressed, and replays go through
+    // the rest of the middleware exactly like the original session did
+    if let Some(action_log_path) = action_log_path {
+        middlewares.push(Box::new(ActionLog::create(action_log_path)?));
+    }
+
+    middlewares.push(Box::<MacroRecorder>::default());
+    middlewares.push(Box::<UndoTracker>::default());
+
+    Ok(middlewares)
+}
+
+/// Passes `action` through `middlewares` in order, then to `update()`.
+/// Every action a middleware passes on goes all the way through to `update()`
+/// before the next one starts, so later middleware always sees up-to-date app state.
+pub(crate) fn dispatch(
+    app: &mut App,
+    middlewares: &mut [Box<dyn Middleware>],
+    action: Action,
+) -> Result<()> {
+    match middlewares.split_first_mut() {
+        Some((middleware, rest)) => {
+            for action in middleware.handle(app, action)? {
+                dispatch(app, rest, action)?;
+            }
+        }
+        None => crate::update(app, action)?,
+    }
+
+    Ok(())
+}
+
+/// Writes every action to a file as a line of JSON, for `--replay`
+struct ActionLog {
+    file: std::fs::File,
+}
+
+impl ActionLog {
+    fn create(path: &Path) -> Result<Self> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("unable to create action log {}", path.display()))?;
+
+        Ok(Self { file })
+    }
+}
+
+impl Middleware for ActionLog {
+    fn handle(&mut self, _app: &App, action: Action) -> Result<Vec<Action>> {
+        // replays make their own ticks
+        if action != Action::Tick {
+            writeln!(self.file, "{}", serde_json::to_string(&action)?)?;
+        }
+
+        Ok(vec![action])
+    }
+}
+
+pub(crate) fn read_action_log(path: &Path) -> Result<Vec<Action>> {
+    let action_log = std::fs::read_to_string(path)
+        .with_context(|| format!("unable to read action log {}", path.display()))?;
+
+    parse_action_log(&action_log)
+}
+
+fn parse_action_log(action_log: &str) -> Result<Vec<Action>> {
+    action_log
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("unable to parse action on line {}", i + 1))
+        })
+        .collect()
+}
+
+/// Records keyboard macros with `m`, and replays the last one with `@`
+#[derive(Default)]
+struct MacroRecorder {
+    recording: Option<Vec<Action>>,
+    last_macro: Vec<Action>,
+}
+
+impl Middleware for MacroRecorder {
+    fn handle(&mut self, app: &App, action: Action) -> Result<Vec<Action>> {
+        match action {
+            Action::ToggleMacroRecording => {
+                match self.recording.take() {
+                    Some(recorded) => {
+                        app.flash(format!("Recorded macro of {} actions", recorded.len()));
+                        self.last_macro = recorded;
+                    }
+                    None => {
+                        app.flash("Recording macro, m to stop".to_string());
+                        self.recording = Some(vec![]);
+                    }
+                }
+
+                Ok(vec![])
+            }
+            Action::ReplayMacro => {
+                if self.last_macro.is_empty() {
+                    app.flash("No macro recorded".to_string());
+                }
+
+                Ok(self.last_macro.clone())
+            }
+            action => {
+                if let Some(recording) = &mut self.recording {
+                    if !matches!(
+                        action,
+                        Action::Tick | Action::Quit | Action::Unlock | Action::HintUnboundKey(_)
+                    ) {
+                        recording.push(action.clone());
+                    }
+                }
+
+                Ok(vec![action])
+            }
+        }
+    }
+}
+
+/// Remembers which entries had their read state toggled, so `u` can toggle them back
+#[derive(Default)]
+struct UndoTracker {
+    toggled_entry_ids: Vec<crate::rss::EntryId>,
+}
+
+impl Middleware for UndoTracker {
+    fn handle(&mut self, app: &App, action: Action) -> Result<Vec<Action>> {
+        match action {
+            Action::ToggleReadStatus => {
+                if let Some(entry_id) = app.selected_entry_id() {
+                    self.toggled_entry_ids.push(entry_id);
+                }
+
+                Ok(vec![action])
+            }
+            // the entries toggled so far are in the database being switched away from
+            Action::SwitchDatabase => {
+                self.toggled_entry_ids.clear();
+                Ok(vec![action])
+            }
+            Action::Undo => match self.toggled_entry_ids.pop() {
+                // toggling is its own undo
+                Some(entry_id) => Ok(vec![Action::ToggleEntryRead(entry_id)]),
+                None => {
+                    app.flash("Nothing to undo".to_string());
+                    Ok(vec![])
+                }
+            },
+            action => Ok(vec![action]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_action_logs() {
+        let actions = vec![
+            Action::MoveRight,
+            Action::PushInputChar('x'),
+            Action::ToggleEntryRead(1.into()),
+        ];
+
+        let action_log = actions
+            .iter()
+            .map(|action| serde_json::to_string(action).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(parse_action_log(&action_log).unwrap(), actions);
+        assert!(parse_action_log("\"NotAnAction\"").is_err());
+    }
+}
@@ -0,0 +1,306 @@
+//! Import feeds, entry history, and read/starred state from other feed readers,
+//! and a browser's bookmarks, to read later.
+//!
+//! Unlike OPML import, nothing is fetched over the network:
+//! entries come from the other reader's or browser's own storage.
+
+use crate::rss::ImportedEntry;
+use crate::ImportOptions;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// The response body of Miniflux's `GET /v1/entries` API
+#[derive(Deserialize)]
+struct MinifluxExport {
+    entries: Vec<MinifluxEntry>,
+}
+
+#[derive(Deserialize)]
+struct MinifluxEntry {
+    title: String,
+    url: String,
+    author: String,
+    content: String,
+    /// one of `unread`, `read`, or `removed`
+    status: String,
+    starred: bool,
+    published_at: String,
+    changed_at: Option<String>,
+    feed: MinifluxFeed,
+}
+
+#[derive(Deserialize)]
+struct MinifluxFeed {
+    id: i64,
+    feed_url: String,
+    site_url: String,
+    title: String,
+}
+
+pub(crate) fn import_miniflux_export(options: ImportOptions, path: &Path) -> Result<ExitCode> {
+    let export_file =
+        std::fs::File::open(path).context("must provide a valid Miniflux export file")?;
+
+    let export: MinifluxExport = serde_json::from_reader(std::io::BufReader::new(export_file))
+        .context("unable to parse provided Miniflux export")?;
+
+    let mut feeds: BTreeMap<i64, (MinifluxFeed, Vec<ImportedEntry>)> = BTreeMap::new();
+
+    for entry in export.entries {
+        if entry.status == "removed" {
+            continue;
+        }
+
+        let changed_at = entry.changed_at.as_deref().and_then(parse_rfc3339);
+
+        let imported_entry = ImportedEntry {
+            title: Some(entry.title),
+            author: Some(entry.author).filter(|author| !author.is_empty()),
+            pub_date: parse_rfc3339(&entry.published_at),
+            content: Some(entry.content),
+            link: Some(entry.url),
+            read_at: if entry.status == "read" {
+                changed_at.or_else(|| Some(Utc::now()))
+            } else {
+                None
+            },
+            starred_at: if entry.starred {
+                changed_at.or_else(|| Some(Utc::now()))
+            } else {
+                None
+            },
+        };
+
+        feeds
+            .entry(entry.feed.id)
+            .or_insert_with(|| (entry.feed, vec![]))
+            .1
+            .push(imported_entry);
+    }
+
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::tune_connection(&conn, &options.database_options)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let mut progress = crate::progress::ImportProgress::new(feeds.len(), options.output);
+
+    for (feed, entries) in feeds.into_values() {
+        progress.working_on(&feed.feed_url, None);
+
+        let result = crate::rss::import_feed(
+            &mut conn,
+            Some(&feed.title),
+            &feed.feed_url,
+            Some(&feed.site_url),
+            &entries,
+            &options.ingest_options,
+        );
+
+        progress.finished(&feed.feed_url, imported(entries.len(), result));
+    }
+
+    progress.summarize()
+}
+
+/// Newsboat has no notion of starring,
+/// so entries with any of newsboat's user-defined flags are imported as starred.
+pub(crate) fn import_newsboat_cache(options: ImportOptions, path: &Path) -> Result<ExitCode> {
+    let cache =
+        rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .context("must provide a valid newsboat cache.db")?;
+
+    let mut feeds_statement = cache.prepare("SELECT rssurl, url, title FROM rss_feed")?;
+
+    let feeds = feeds_statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()
+        .context("unable to read feeds from provided newsboat cache")?;
+
+    let mut items_statement = cache.prepare(
+        "SELECT title, author, url, pubDate, content, unread, flags
+        FROM rss_item
+        WHERE feedurl = ?1 AND deleted = 0",
+    )?;
+
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::tune_connection(&conn, &options.database_options)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let now = Utc::now();
+    let mut progress = crate::progress::ImportProgress::new(feeds.len(), options.output);
+
+    for (feed_url, site_url, title) in feeds {
+        progress.working_on(&feed_url, None);
+
+        let entries = items_statement
+            .query_map([&feed_url], |row| {
+                let unread: bool = row.get(5)?;
+                let flags: Option<String> = row.get(6)?;
+
+                Ok(ImportedEntry {
+                    title: row.get(0)?,
+                    author: row
+                        .get::<_, Option<String>>(1)?
+                        .filter(|author| !author.is_empty()),
+                    link: row.get(2)?,
+                    pub_date: DateTime::from_timestamp(row.get(3)?, 0),
+                    content: row.get(4)?,
+                    // newsboat does not record when something was read
+                    read_at: if unread { None } else { Some(now) },
+                    starred_at: flags.filter(|flags| !flags.is_empty()).map(|_| now),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("unable to read items for {feed_url} from newsboat cache"))?;
+
+        let result = crate::rss::import_feed(
+            &mut conn,
+            title.as_deref(),
+            &feed_url,
+            site_url.as_deref(),
+            &entries,
+            &options.ingest_options,
+        );
+
+        progress.finished(&feed_url, imported(entries.len(), result));
+    }
+
+    progress.summarize()
+}
+
+/// Imports the links in a browser's bookmarks export, in the Netscape bookmarks format
+/// that every browser exports, as starred, unread entries of a local bookmarks feed.
+/// That feed is never refreshed, so bookmarks only change by importing them again.
+pub(crate) fn import_bookmarks(options: ImportOptions, path: &Path) -> Result<ExitCode> {
+    let html = std::fs::read_to_string(path).context("must provide a valid bookmarks file")?;
+
+    let entries = parse_bookmarks(&html, Utc::now());
+
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::tune_connection(&conn, &options.database_options)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let result = crate::rss::import_feed(
+        &mut conn,
+        Some("Bookmarks"),
+        crate::rss::BOOKMARKS_FEED_LINK,
+        None,
+        &entries,
+        &options.ingest_options,
+    );
+
+    let mut progress = crate::progress::ImportProgress::new(1, options.output);
+    progress.finished(
+        path.to_string_lossy().as_ref(),
+        imported(entries.len(), result),
+    );
+    progress.summarize()
+}
+
+/// The web links in a Netscape bookmarks file, which look like
+/// `<DT><A HREF="https://example.com" ADD_DATE="1712345678">Example</A>`.
+/// Bookmarks are starred when they were added, or `now` if that is not known.
+fn parse_bookmarks(html: &str, now: DateTime<Utc>) -> Vec<ImportedEntry> {
+    let mut entries = vec![];
+    let mut tags = crate::util::tags(html);
+
+    while let Some(tag) = tags.next() {
+        if tag.name != "a" || tag.is_closing {
+            continue;
+        }
+
+        let title_end = tags
+            .by_ref()
+            .find(|tag| tag.name == "a" && tag.is_closing)
+            .map(|tag| tag.range.start)
+            .unwrap_or(html.len());
+
+        let attributes = tag.attributes();
+
+        // browsers also export things like `place:` queries and `javascript:` bookmarklets
+        let Some(link) = attributes
+            .get("href")
+            .filter(|href| href.starts_with("http://") || href.starts_with("https://"))
+        else {
+            continue;
+        };
+
+        let title = html_escape::decode_html_entities(html[tag.range.end..title_end].trim());
+
+        let added_at = attributes
+            .get("add_date")
+            .and_then(|add_date| add_date.parse().ok())
+            .and_then(|add_date| DateTime::from_timestamp(add_date, 0));
+
+        entries.push(ImportedEntry {
+            title: Some(title.to_string()).filter(|title| !title.is_empty()),
+            author: None,
+            pub_date: added_at,
+            content: None,
+            link: Some(link.to_owned()),
+            read_at: None,
+            starred_at: Some(added_at.unwrap_or(now)),
+        });
+    }
+
+    entries
+}
+
+fn parse_rfc3339(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// How many of a feed's `entries_len` entries were new, for the import's progress
+fn imported(entries_len: usize, result: Result<usize>) -> Result<Option<String>> {
+    result.map(|inserted| Some(format!("{inserted} new of {entries_len} entries")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_bookmarks_exports() {
+        let html = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<TITLE>Bookmarks</TITLE>
+<DL><p>
+    <DT><H3 ADD_DATE="1700000000">To read</H3>
+    <DL><p>
+        <DT><A HREF="https://example.com/a?x=1&amp;y=2" ADD_DATE="1712345678" ICON="data:image/png;base64,AAAA">Tom &amp; Jerry</A>
+        <DT><A HREF="javascript:alert(1)">Bookmarklet</A>
+        <DT><abbr>not a link</abbr>
+        <DT><a href='http://example.com/b'></a>
+    </DL><p>
+</DL><p>"#;
+
+        let now = Utc::now();
+        let entries = parse_bookmarks(html, now);
+
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0].title.as_deref(), Some("Tom & Jerry"));
+        assert_eq!(
+            entries[0].link.as_deref(),
+            Some("https://example.com/a?x=1&y=2")
+        );
+        assert_eq!(entries[0].pub_date, DateTime::from_timestamp(1712345678, 0));
+        assert_eq!(entries[0].starred_at, entries[0].pub_date);
+        assert_eq!(entries[0].read_at, None);
+
+        assert_eq!(entries[1].title, None);
+        assert_eq!(entries[1].link.as_deref(), Some("http://example.com/b"));
+        assert_eq!(entries[1].starred_at, Some(now));
+    }
+}
@@ -5,19 +5,50 @@
 pub enum Selected {
     Feeds,
     Entries,
-    Entry(crate::rss::EntryMetadata),
+    Entry(Box<crate::rss::EntryMetadata>),
     None,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Mode {
     Editing,
     Normal,
+    /// typing a query to search every feed's entries for
+    Searching,
+    /// asking whether to delete the selected feed, and every entry in it
+    DeletingFeed,
+    /// asking whether to move the selected feed to where it appears to have moved
+    MovingFeed,
+    /// typing the path of another database to switch to
+    SwitchingDatabase,
+    /// typing the selected feed's own HTTP headers, like `Authorization`
+    EditingFeedHttpSettings,
+    /// typing the title to show the selected feed with, in place of its own
+    EditingFeedTitle,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum ReadMode {
     ShowRead,
     ShowUnread,
     All,
 }
+
+impl ReadMode {
+    /// The mode `a` switches to: unread, then read, then all, then back to unread
+    pub fn next(&self) -> Self {
+        match self {
+            ReadMode::ShowUnread => ReadMode::ShowRead,
+            ReadMode::ShowRead => ReadMode::All,
+            ReadMode::All => ReadMode::ShowUnread,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ReadMode::ShowUnread => "unread",
+            ReadMode::ShowRead => "read",
+            ReadMode::All => "all",
+        }
+    }
+}
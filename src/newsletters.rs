@@ -0,0 +1,150 @@
+//! Email newsletters, fetched from an IMAP mailbox into a feed for each sender,
+//! so they can be read like any other feed.
+//!
+//! Only built with the `imap` feature.
+
+use crate::rss::ImportedEntry;
+use crate::NewslettersOptions;
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+
+mod imap;
+
+/// Where the mailbox password is read from, rather than the command line,
+/// which other users can often see
+const PASSWORD_ENV_VAR: &str = "RUSS_IMAP_PASSWORD";
+
+/// Adds the unread messages from the configured senders to their senders' feeds,
+/// and marks them read in the mailbox
+pub(crate) fn fetch(options: NewslettersOptions) -> Result<()> {
+    let password = std::env::var(PASSWORD_ENV_VAR)
+        .with_context(|| format!("set {PASSWORD_ENV_VAR} to the password for the mailbox"))?;
+
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::tune_connection(&conn, &options.database_options)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let mut session = imap::Session::connect(
+        &options.imap_host,
+        options.imap_port,
+        options.network_timeout,
+    )?;
+    session.login(&options.imap_user, &password)?;
+    session.select(&options.mailbox)?;
+
+    let mut uids = BTreeSet::new();
+    for sender in &options.senders {
+        uids.extend(session.search_unseen_from(sender)?);
+    }
+
+    let mut added = 0;
+
+    for uid in uids {
+        let message = session.fetch(uid)?;
+
+        let Some(newsletter) = Newsletter::from_message(&message) else {
+            eprintln!("skipping message {uid}, which has no sender");
+            continue;
+        };
+
+        added += crate::rss::import_feed(
+            &mut conn,
+            Some(&newsletter.title),
+            &newsletter.feed_link,
+            None,
+            &[newsletter.entry],
+            &options.ingest_options,
+        )?;
+
+        // only once it is safely stored
+        session.mark_seen(uid)?;
+    }
+
+    session.logout()?;
+
+    eprintln!("added {added} newsletters");
+
+    Ok(())
+}
+
+/// A message, as an entry in the feed of the newsletter that sent it
+struct Newsletter {
+    /// `mailto:` the sender, so each sender gets its own feed
+    feed_link: String,
+    /// the sender's name
+    title: String,
+    entry: ImportedEntry,
+}
+
+impl Newsletter {
+    fn from_message(message: &[u8]) -> Option<Self> {
+        let message = mail_parser::MessageParser::default().parse(message)?;
+
+        let sender = message.from()?.first()?;
+        let address = sender.address()?.to_lowercase();
+        let title = sender.name().unwrap_or(&address).to_owned();
+
+        let content = message
+            .body_html(0)
+            .map(|html| html.into_owned())
+            .or_else(|| {
+                message
+                    .body_text(0)
+                    .map(|text| format!("<pre>{}</pre>", html_escape::encode_text(&text)))
+            });
+
+        Some(Newsletter {
+            feed_link: format!("mailto:{address}"),
+            entry: ImportedEntry {
+                title: message.subject().map(|subject| subject.to_owned()),
+                author: Some(title.clone()),
+                pub_date: message
+                    .date()
+                    .and_then(|date| chrono::DateTime::from_timestamp(date.to_timestamp(), 0)),
+                content,
+                // entries are deduplicated by link, and a message's id is unique to it
+                link: message.message_id().map(|id| format!("mid:{id}")),
+                read_at: None,
+                starred_at: None,
+            },
+            title,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_turns_messages_into_newsletter_entries() {
+        let message = b"From: Example Weekly <Weekly@Example.substack.com>\r
+To: reader@example.com\r
+Subject: Issue 42\r
+Date: Tue, 1 Oct 2024 12:00:00 +0000\r
+Message-ID: <abc123@example.substack.com>\r
+Content-Type: text/html; charset=utf-8\r
+\r
+<p>Hello, readers</p>\r
+";
+
+        let newsletter = Newsletter::from_message(message).unwrap();
+
+        assert_eq!(newsletter.feed_link, "mailto:weekly@example.substack.com");
+        assert_eq!(newsletter.title, "Example Weekly");
+        assert_eq!(newsletter.entry.title.as_deref(), Some("Issue 42"));
+        assert_eq!(
+            newsletter.entry.link.as_deref(),
+            Some("mid:abc123@example.substack.com")
+        );
+        assert_eq!(
+            newsletter.entry.pub_date.unwrap().to_rfc3339(),
+            "2024-10-01T12:00:00+00:00"
+        );
+        assert!(newsletter
+            .entry
+            .content
+            .unwrap()
+            .contains("<p>Hello, readers</p>"));
+    }
+}
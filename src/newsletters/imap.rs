@@ -0,0 +1,187 @@
+//! Just enough of an IMAP client to find, fetch, and mark newsletters,
+//! over TLS on a blocking socket, like the rest of russ's networking
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+
+type TlsStream = rustls::StreamOwned<rustls::ClientConnection, TcpStream>;
+
+pub(super) struct Session {
+    stream: BufReader<TlsStream>,
+    next_tag: u32,
+}
+
+/// An untagged response, with the contents of its literals taken out of the line
+struct Response {
+    line: String,
+    literals: Vec<Vec<u8>>,
+}
+
+impl Session {
+    pub(super) fn connect(host: &str, port: u16, timeout: std::time::Duration) -> Result<Self> {
+        let root_store = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+
+        let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+
+        let server_name = rustls::pki_types::ServerName::try_from(host.to_owned())?;
+        let connection = rustls::ClientConnection::new(Arc::new(config), server_name)?;
+
+        let socket = TcpStream::connect((host, port))
+            .with_context(|| format!("unable to connect to {host}:{port}"))?;
+        socket.set_read_timeout(Some(timeout))?;
+        socket.set_write_timeout(Some(timeout))?;
+
+        let mut session = Session {
+            stream: BufReader::new(rustls::StreamOwned::new(connection, socket)),
+            next_tag: 1,
+        };
+
+        let greeting = session.read_response()?.line;
+        if !greeting.starts_with("* OK") {
+            bail!("unexpected IMAP greeting: {greeting}");
+        }
+
+        Ok(session)
+    }
+
+    pub(super) fn login(&mut self, user: &str, password: &str) -> Result<()> {
+        self.command(&format!("LOGIN {} {}", quote(user), quote(password)))
+            .context("IMAP login failed")?;
+        Ok(())
+    }
+
+    pub(super) fn select(&mut self, mailbox: &str) -> Result<()> {
+        self.command(&format!("SELECT {}", quote(mailbox)))
+            .with_context(|| format!("unable to select mailbox {mailbox}"))?;
+        Ok(())
+    }
+
+    /// The uids of the unread messages whose From header contains `sender`
+    pub(super) fn search_unseen_from(&mut self, sender: &str) -> Result<Vec<u32>> {
+        let responses = self.command(&format!("UID SEARCH UNSEEN FROM {}", quote(sender)))?;
+
+        let uids = responses
+            .iter()
+            .filter_map(|response| response.line.strip_prefix("* SEARCH"))
+            .flat_map(|uids| uids.split_whitespace())
+            .filter_map(|uid| uid.parse().ok())
+            .collect();
+
+        Ok(uids)
+    }
+
+    /// The whole message, without marking it read
+    pub(super) fn fetch(&mut self, uid: u32) -> Result<Vec<u8>> {
+        self.command(&format!("UID FETCH {uid} BODY.PEEK[]"))?
+            .into_iter()
+            .find_map(|response| response.literals.into_iter().next())
+            .ok_or_else(|| anyhow!("message {uid} has no body"))
+    }
+
+    pub(super) fn mark_seen(&mut self, uid: u32) -> Result<()> {
+        self.command(&format!("UID STORE {uid} +FLAGS.SILENT (\\Seen)"))?;
+        Ok(())
+    }
+
+    pub(super) fn logout(&mut self) -> Result<()> {
+        self.command("LOGOUT")?;
+        Ok(())
+    }
+
+    /// Sends `command`, returning the untagged responses to it if it succeeds
+    fn command(&mut self, command: &str) -> Result<Vec<Response>> {
+        let tag = format!("A{}", self.next_tag);
+        self.next_tag += 1;
+
+        let stream = self.stream.get_mut();
+        stream.write_all(format!("{tag} {command}\r\n").as_bytes())?;
+        stream.flush()?;
+
+        let mut responses = vec![];
+
+        loop {
+            let response = self.read_response()?;
+
+            // the command is left out of errors, as it can be a login with a password
+            if let Some(status) = response.line.strip_prefix(&format!("{tag} ")) {
+                if status.starts_with("OK") {
+                    return Ok(responses);
+                } else {
+                    bail!("IMAP server responded {status}");
+                }
+            }
+
+            responses.push(response);
+        }
+    }
+
+    fn read_response(&mut self) -> Result<Response> {
+        let mut line = vec![];
+        let mut literals = vec![];
+
+        loop {
+            let mut chunk = vec![];
+            if self.stream.read_until(b'\n', &mut chunk)? == 0 {
+                bail!("IMAP server closed the connection");
+            }
+
+            match literal_len(&chunk) {
+                Some(len) => {
+                    line.extend_from_slice(
+                        &chunk[..chunk.iter().rposition(|b| *b == b'{').unwrap_or(0)],
+                    );
+
+                    let mut literal = vec![0; len];
+                    self.stream.read_exact(&mut literal)?;
+                    literals.push(literal);
+                }
+                None => {
+                    line.extend_from_slice(&chunk);
+                    break;
+                }
+            }
+        }
+
+        Ok(Response {
+            line: String::from_utf8_lossy(&line).trim_end().to_owned(),
+            literals,
+        })
+    }
+}
+
+/// The length of the literal that follows `line`, if it ends by announcing one, like `{1234}`
+fn literal_len(line: &[u8]) -> Option<usize> {
+    let line = std::str::from_utf8(line).ok()?.trim_end();
+    let len = line.strip_suffix('}')?.rsplit_once('{')?.1;
+    len.parse().ok()
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_literals_and_quotes_strings() {
+        assert_eq!(
+            literal_len(b"* 1 FETCH (UID 7 BODY[] {1234}\r\n"),
+            Some(1234)
+        );
+        assert_eq!(literal_len(b"* SEARCH 1 2 3\r\n"), None);
+        assert_eq!(literal_len(b"* OK {not a literal}\r\n"), None);
+
+        assert_eq!(quote(r#"pass"wo\rd"#), r#""pass\"wo\\rd""#);
+    }
+}
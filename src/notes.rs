@@ -0,0 +1,190 @@
+//! Export starred entries as a notebook of Markdown files,
+//! for note-taking tools like Obsidian.
+
+use crate::rss::{EntryContent, EntryMetadata, FeedId};
+use crate::ExportNotesOptions;
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+
+/// Longer titles are cut short, as some filesystems limit file name length
+const MAX_FILE_NAME_CHARS: usize = 100;
+
+const LINE_LENGTH: usize = 80;
+
+pub(crate) fn export_notes(options: ExportNotesOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    std::fs::create_dir_all(&options.dir)
+        .with_context(|| format!("unable to create {}", options.dir.display()))?;
+
+    let mut feed_titles: HashMap<FeedId, Option<String>> = HashMap::new();
+    let mut file_names = HashSet::new();
+
+    let entry_ids = crate::rss::get_starred_entry_ids(&conn)?;
+
+    for entry_id in &entry_ids {
+        let entry_meta = crate::rss::get_entry_meta(&conn, *entry_id)?;
+        let entry_content = crate::rss::get_entry_content(&conn, *entry_id)?;
+
+        let feed_title = match feed_titles.get(&entry_meta.feed_id) {
+            Some(feed_title) => feed_title.clone(),
+            None => {
+                let feed_title = crate::rss::get_feed(&conn, entry_meta.feed_id)?.title;
+                feed_titles.insert(entry_meta.feed_id, feed_title.clone());
+                feed_title
+            }
+        };
+
+        let mut file_name = file_name(entry_meta.title.as_deref());
+        // two entries with the same title should not overwrite each other
+        if !file_names.insert(file_name.clone()) {
+            file_name = format!("{file_name} ({entry_id})");
+            file_names.insert(file_name.clone());
+        }
+
+        let path = options.dir.join(format!("{file_name}.md"));
+
+        let images = options
+            .asset_cache_dir
+            .as_ref()
+            .map(|asset_cache_dir| crate::assets::cached_asset_links(asset_cache_dir, *entry_id))
+            .unwrap_or_default();
+
+        std::fs::write(
+            &path,
+            render_note(&entry_meta, &entry_content, feed_title.as_deref(), &images),
+        )
+        .with_context(|| format!("unable to write {}", path.display()))?;
+    }
+
+    eprintln!(
+        "exported {} starred entries to {}",
+        entry_ids.len(),
+        options.dir.display()
+    );
+
+    Ok(())
+}
+
+fn file_name(title: Option<&str>) -> String {
+    let file_name = title
+        .unwrap_or("Untitled")
+        .chars()
+        .filter(|c| {
+            !matches!(
+                c,
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']'
+            )
+        })
+        .filter(|c| !c.is_control())
+        .take(MAX_FILE_NAME_CHARS)
+        .collect::<String>();
+
+    let file_name = file_name.trim().trim_start_matches('.');
+
+    if file_name.is_empty() {
+        "Untitled".to_string()
+    } else {
+        file_name.to_string()
+    }
+}
+
+fn render_note(
+    entry_meta: &EntryMetadata,
+    entry_content: &EntryContent,
+    feed_title: Option<&str>,
+    images: &[String],
+) -> String {
+    let mut note = String::from("---\n");
+
+    let mut field = |name: &str, value: &str| {
+        note.push_str(&format!("{name}: {}\n", yaml_string(value)));
+    };
+
+    if let Some(title) = &entry_meta.title {
+        field("title", title);
+    }
+
+    if let Some(link) = entry_meta
+        .resolved_link
+        .as_ref()
+        .or(entry_meta.link.as_ref())
+    {
+        field("url", link);
+    }
+
+    field(
+        "date",
+        &entry_meta
+            .pub_date
+            .unwrap_or(entry_meta.inserted_at)
+            .to_rfc3339(),
+    );
+
+    if let Some(feed_title) = feed_title {
+        field("feed", feed_title);
+    }
+
+    if let Some(starred_at) = entry_meta.starred_at {
+        field("starred", &starred_at.to_rfc3339());
+    }
+
+    note.push_str("---\n\n");
+
+    if let Some(html) = entry_content
+        .content
+        .as_ref()
+        .or(entry_content.description.as_ref())
+    {
+        note.push_str(&html2text::from_read(html.as_bytes(), LINE_LENGTH));
+    }
+
+    // rendered text has no images, so the cached copies go at the end
+    for image in images {
+        note.push_str(&format!("\n![]({image})\n"));
+    }
+
+    note
+}
+
+/// Always double-quoted, so titles like `yes` or `1.0` stay strings
+fn yaml_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_makes_safe_file_names() {
+        assert_eq!(
+            file_name(Some("Rust 2024: what's new? / [draft]")),
+            "Rust 2024 what's new  draft"
+        );
+        assert_eq!(file_name(Some("../..")), "Untitled");
+        assert_eq!(file_name(None), "Untitled");
+    }
+
+    #[test]
+    fn it_quotes_yaml_strings() {
+        assert_eq!(
+            yaml_string(r#"a "quoted" \ title"#),
+            r#""a \"quoted\" \\ title""#
+        );
+    }
+}
@@ -2,14 +2,16 @@
 
 use crate::ImportOptions;
 use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::ExitCode;
 
-pub(crate) fn import(options: ImportOptions) -> Result<()> {
+pub(crate) fn import(options: ImportOptions, opml_path: &Path) -> Result<ExitCode> {
     let mut conn = rusqlite::Connection::open(options.database_path)?;
 
+    crate::rss::tune_connection(&conn, &options.database_options)?;
     crate::rss::initialize_db(&mut conn)?;
 
-    let opml_file =
-        std::fs::File::open(options.opml_path).context("must provide a valid OPML file")?;
+    let opml_file = std::fs::File::open(opml_path).context("must provide a valid OPML file")?;
 
     let mut opml_reader = std::io::BufReader::new(opml_file);
 
@@ -22,53 +24,132 @@ pub(crate) fn import(options: ImportOptions) -> Result<()> {
 
     let feed_urls = get_feed_urls(&opml_document);
 
-    let mut successful_imports = 0;
-    let mut failed_imports = vec![];
-
-    for feed_url in feed_urls {
-        eprintln!(">>>>>>>>>>");
-        eprintln!("{}: starting import", feed_url);
-        match crate::rss::subscribe_to_feed(&http_client, &mut conn, &feed_url) {
-            Ok(_feed_id) => {
-                eprintln!("{feed_url}: OK");
-                successful_imports += 1;
-            }
-            Err(e) => {
-                eprintln!("ERROR: {:?}", e);
-                failed_imports.push(feed_url);
+    let mut progress = crate::progress::ImportProgress::new(feed_urls.len(), options.output);
+
+    for (feed_url, folder) in feed_urls {
+        progress.working_on(&feed_url, None);
+
+        let result = crate::rss::subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            &feed_url,
+            &options.ingest_options,
+            options.archive_pages,
+            |page| {
+                progress.working_on(
+                    &feed_url,
+                    Some(&format!(
+                        "fetching archive page {page}/{}",
+                        options.archive_pages
+                    )),
+                );
+                Ok(())
+            },
+        )
+        .and_then(|feed_id| {
+            if let Some(folder) = &folder {
+                crate::rss::set_feed_folder(&conn, feed_id, Some(folder))?;
             }
-        };
-        eprintln!("<<<<<<<<<<");
-    }
-
-    eprintln!();
-    eprintln!("{successful_imports} feeds imported");
-    eprintln!("{} feeds failed to import", failed_imports.len());
+            Ok(folder.map(|folder| format!("in {folder}")))
+        });
 
-    if !failed_imports.is_empty() {
-        eprintln!();
-
-        for failed_import_url in failed_imports {
-            eprintln!("{failed_import_url} failed to import");
-        }
+        progress.finished(&feed_url, result);
     }
 
-    Ok(())
+    progress.summarize()
 }
 
 // outlines can be nested within other outlines in a tree structure,
-// so we have to traverse them
-fn get_feed_urls(opml_document: &opml::OPML) -> Vec<String> {
-    let mut outlines_stack = opml_document.body.outlines.to_owned();
+// so we have to traverse them.
+// the outlines feeds are nested in are their folder, like `Tech / Rust`
+fn get_feed_urls(opml_document: &opml::OPML) -> Vec<(String, Option<String>)> {
+    let mut outlines_stack = opml_document
+        .body
+        .outlines
+        .iter()
+        .map(|outline| (outline, None))
+        .collect::<Vec<_>>();
     let mut feed_urls = vec![];
 
-    while let Some(this_outline) = outlines_stack.pop() {
-        outlines_stack.extend_from_slice(&this_outline.outlines);
-
-        if let Some(xml_url) = this_outline.xml_url {
-            feed_urls.push(xml_url);
+    while let Some((this_outline, folder)) = outlines_stack.pop() {
+        if let Some(xml_url) = &this_outline.xml_url {
+            feed_urls.push((xml_url.to_owned(), folder.clone()));
         }
+
+        let name = this_outline
+            .title
+            .as_deref()
+            .unwrap_or(&this_outline.text)
+            .trim();
+
+        let child_folder = match (&folder, name) {
+            // feeds' own outlines are not folders
+            _ if this_outline.xml_url.is_some() => folder.clone(),
+            (_, "") => folder.clone(),
+            (Some(folder), name) => Some(format!("{folder} / {name}")),
+            (None, name) => Some(name.to_owned()),
+        };
+
+        outlines_stack.extend(
+            this_outline
+                .outlines
+                .iter()
+                .map(|outline| (outline, child_folder.clone())),
+        );
     }
 
     feed_urls
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_keeps_the_folders_feeds_are_in() {
+        let feed = |url: &str| opml::Outline {
+            text: url.to_owned(),
+            xml_url: Some(url.to_owned()),
+            ..Default::default()
+        };
+        let folder = |name: &str, outlines: Vec<opml::Outline>| opml::Outline {
+            text: name.to_owned(),
+            outlines,
+            ..Default::default()
+        };
+
+        let opml_document = opml::OPML {
+            body: opml::Body {
+                outlines: vec![
+                    feed("https://example.com/top"),
+                    folder(
+                        "Tech",
+                        vec![
+                            feed("https://example.com/tech"),
+                            folder("Rust", vec![feed("https://example.com/rust")]),
+                        ],
+                    ),
+                ],
+            },
+            ..Default::default()
+        };
+
+        let mut feed_urls = get_feed_urls(&opml_document);
+        feed_urls.sort();
+
+        assert_eq!(
+            feed_urls,
+            vec![
+                (
+                    "https://example.com/rust".to_string(),
+                    Some("Tech / Rust".to_string())
+                ),
+                (
+                    "https://example.com/tech".to_string(),
+                    Some("Tech".to_string())
+                ),
+                ("https://example.com/top".to_string(), None),
+            ]
+        );
+    }
+}
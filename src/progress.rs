@@ -0,0 +1,203 @@
+//! How commands that work on many feeds, like `russ import` and `russ refresh`, report how they went:
+//! progress and a summary for people, or with `--output json`, one JSON document for scripts,
+//! and an exit code that tells every feed working apart from some or all of them failing.
+//!
+//! On a terminal, `russ import` shows a progress bar with a status line above it for each finished feed.
+//! Otherwise, it is only the status lines.
+
+use serde::Serialize;
+use std::io::{IsTerminal, Write};
+use std::process::ExitCode;
+
+const BAR_WIDTH: usize = 24;
+
+/// Exit codes past `1`, for errors that stop a command before it finishes,
+/// and `2`, for clap's usage errors
+const SOME_FEEDS_FAILED: u8 = 3;
+const EVERY_FEED_FAILED: u8 = 4;
+
+/// What a command prints when it finishes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum OutputFormat {
+    /// progress and a summary on stderr
+    Text,
+    /// nothing but a JSON summary on stdout, for scripts
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(anyhow::anyhow!("{s} is not one of text or json")),
+        }
+    }
+}
+
+/// `0` if no feed failed, otherwise whether only some or every feed failed
+pub(crate) fn exit_code(succeeded: usize, failed: usize) -> ExitCode {
+    match (succeeded, failed) {
+        (_, 0) => ExitCode::SUCCESS,
+        (0, _) => ExitCode::from(EVERY_FEED_FAILED),
+        _ => ExitCode::from(SOME_FEEDS_FAILED),
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum FeedStatus {
+    Imported,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+struct FeedReport {
+    feed_url: String,
+    status: FeedStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Summary<'a> {
+    imported: usize,
+    failed: usize,
+    feeds: &'a [FeedReport],
+}
+
+pub(crate) struct ImportProgress {
+    feeds_len: usize,
+    output: OutputFormat,
+    draws_bar: bool,
+    reports: Vec<FeedReport>,
+}
+
+impl ImportProgress {
+    pub(crate) fn new(feeds_len: usize, output: OutputFormat) -> Self {
+        Self {
+            feeds_len,
+            output,
+            draws_bar: output == OutputFormat::Text && std::io::stderr().is_terminal(),
+            reports: vec![],
+        }
+    }
+
+    /// Shows that `feed_url` is being imported, and what it is doing, like fetching archive pages
+    pub(crate) fn working_on(&self, feed_url: &str, doing: Option<&str>) {
+        if !self.draws_bar {
+            return;
+        }
+
+        let done = self.reports.len();
+        let filled = (done * BAR_WIDTH).checked_div(self.feeds_len).unwrap_or(0);
+
+        let mut line = format!(
+            "[{}{}] {done}/{} {feed_url}",
+            "=".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            self.feeds_len
+        );
+        if let Some(doing) = doing {
+            line.push_str(": ");
+            line.push_str(doing);
+        }
+
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(stderr, "\r\x1b[2K{line}");
+        let _ = stderr.flush();
+    }
+
+    /// Records how importing `feed_url` went, with a `detail` like how many entries it had
+    pub(crate) fn finished(&mut self, feed_url: &str, result: anyhow::Result<Option<String>>) {
+        let report = match result {
+            Ok(detail) => FeedReport {
+                feed_url: feed_url.to_owned(),
+                status: FeedStatus::Imported,
+                detail,
+                error: None,
+            },
+            Err(e) => FeedReport {
+                feed_url: feed_url.to_owned(),
+                status: FeedStatus::Failed,
+                detail: None,
+                error: Some(format!("{e:#}")),
+            },
+        };
+
+        if self.output == OutputFormat::Text {
+            self.clear_bar();
+
+            match &report {
+                FeedReport {
+                    error: Some(error), ..
+                } => eprintln!("failed  {feed_url}: {error}"),
+                FeedReport {
+                    detail: Some(detail),
+                    ..
+                } => eprintln!("ok      {feed_url} ({detail})"),
+                _ => eprintln!("ok      {feed_url}"),
+            }
+        }
+
+        self.reports.push(report);
+        self.working_on("", None);
+    }
+
+    /// Prints how many feeds were imported and which failed, and why
+    pub(crate) fn summarize(self) -> anyhow::Result<ExitCode> {
+        let failed = self
+            .reports
+            .iter()
+            .filter(|report| matches!(report.status, FeedStatus::Failed))
+            .collect::<Vec<_>>();
+
+        let imported = self.reports.len() - failed.len();
+        let exit_code = exit_code(imported, failed.len());
+
+        if self.output == OutputFormat::Json {
+            let summary = Summary {
+                imported,
+                failed: failed.len(),
+                feeds: &self.reports,
+            };
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+            return Ok(exit_code);
+        }
+
+        self.clear_bar();
+
+        eprintln!();
+        eprintln!("imported  {imported}");
+        eprintln!("failed    {}", failed.len());
+
+        if !failed.is_empty() {
+            let url_width = failed
+                .iter()
+                .map(|report| report.feed_url.chars().count())
+                .max()
+                .unwrap_or(0);
+
+            eprintln!();
+            for report in failed {
+                eprintln!(
+                    "{:url_width$}  {}",
+                    report.feed_url,
+                    report.error.as_deref().unwrap_or_default()
+                );
+            }
+        }
+
+        Ok(exit_code)
+    }
+
+    fn clear_bar(&self) {
+        if self.draws_bar {
+            eprint!("\r\x1b[2K");
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! Finding the article in a web page, for feeds whose entries are only summaries.
+//!
+//! This is a small take on Mozilla's Readability: each paragraph scores points,
+//! going by how long it is, for the element it is in and half as many for that element's parent.
+//! The element with the highest score, less however much of its text is links,
+//! is taken to be the article.
+//! Like `util::strip_html_elements`, this works on the HTML text rather than a parsed document.
+
+use crate::util;
+use std::ops::Range;
+
+/// Elements that are never part of an article
+const NON_CONTENT_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "nav", "header", "footer", "aside", "form",
+    "iframe", "svg", "button", "select", "textarea",
+];
+
+/// Elements that can be the article
+const CANDIDATE_ELEMENTS: &[&str] = &[
+    "article",
+    "main",
+    "section",
+    "div",
+    "td",
+    "blockquote",
+    "body",
+];
+
+/// Elements whose text scores points for the elements they are in
+const PARAGRAPH_ELEMENTS: &[&str] = &["p", "pre"];
+
+/// Paragraphs shorter than this tend to be bylines, captions, and buttons, not content
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+/// Words in an element's class or id that say it is, or is not, the article
+const POSITIVE_HINTS: &[&str] = &[
+    "article", "body", "content", "entry", "main", "page", "post", "story", "text",
+];
+const NEGATIVE_HINTS: &[&str] = &[
+    "ad-", "banner", "comment", "footer", "masthead", "meta", "promo", "related", "share",
+    "sidebar", "social", "sponsor", "widget",
+];
+
+/// How many points a class or id hint is worth
+const HINT_WEIGHT: f64 = 25.0;
+
+struct OpenElement {
+    name: String,
+    inner_start: usize,
+    score: f64,
+    /// from the hints in its attributes
+    weight: f64,
+}
+
+struct Candidate {
+    inner: Range<usize>,
+    score: f64,
+}
+
+/// The HTML of the article in the web page `html`, if there is one
+pub fn extract_article(html: &str) -> Option<String> {
+    let non_content_elements = NON_CONTENT_ELEMENTS
+        .iter()
+        .map(|element| element.to_string())
+        .collect::<Vec<_>>();
+    let html = util::strip_html_elements(html, &non_content_elements);
+
+    let mut open_elements: Vec<OpenElement> = vec![];
+    let mut candidates = vec![];
+
+    for tag in util::tags(&html) {
+        if tag.is_closing {
+            // a closing tag with no opening tag is ignored,
+            // and one closes any elements opened since its opening tag that were never closed
+            if let Some(i) = open_elements
+                .iter()
+                .rposition(|element| element.name == tag.name)
+            {
+                while open_elements.len() > i {
+                    close_element(&mut open_elements, &mut candidates, &html, tag.range.start);
+                }
+            }
+            continue;
+        }
+
+        // paragraphs are often left unclosed
+        if tag.name == "p"
+            && open_elements
+                .last()
+                .is_some_and(|element| element.name == "p")
+        {
+            close_element(&mut open_elements, &mut candidates, &html, tag.range.start);
+        }
+
+        if !tag.is_self_closing() {
+            let attributes = tag.attributes();
+            let hints = ["class", "id"]
+                .iter()
+                .filter_map(|name| attributes.get(*name))
+                .map(|hint| hint.to_ascii_lowercase())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            open_elements.push(OpenElement {
+                name: tag.name,
+                inner_start: tag.range.end,
+                score: 0.0,
+                weight: hint_weight(&hints),
+            });
+        }
+    }
+
+    while !open_elements.is_empty() {
+        close_element(&mut open_elements, &mut candidates, &html, html.len());
+    }
+
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let inner = &html[candidate.inner.clone()];
+            let score = candidate.score * (1.0 - link_density(inner));
+            (inner, score)
+        })
+        .filter(|(_, score)| *score > 0.0)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(inner, _)| inner.trim().to_owned())
+}
+
+/// Closes the innermost open element, which ends at `inner_end`,
+/// scoring its ancestors if it is a paragraph and keeping it if it could be the article
+fn close_element(
+    open_elements: &mut Vec<OpenElement>,
+    candidates: &mut Vec<Candidate>,
+    html: &str,
+    inner_end: usize,
+) {
+    let Some(element) = open_elements.pop() else {
+        return;
+    };
+
+    let inner = element.inner_start..inner_end.max(element.inner_start);
+
+    if PARAGRAPH_ELEMENTS.contains(&element.name.as_str()) {
+        let text = text(&html[inner.clone()]);
+        let chars = text.trim().chars().count();
+
+        if chars >= MIN_PARAGRAPH_CHARS {
+            let commas = text.matches(',').count();
+            let score = 1.0 + commas as f64 + (chars / 100).min(3) as f64;
+
+            if let Some(parent) = open_elements.last_mut() {
+                parent.score += score;
+            }
+            if let Some(grandparent) = open_elements.len().checked_sub(2) {
+                open_elements[grandparent].score += score / 2.0;
+            }
+        }
+    }
+
+    if CANDIDATE_ELEMENTS.contains(&element.name.as_str()) && element.score > 0.0 {
+        candidates.push(Candidate {
+            inner,
+            score: element.score + element.weight,
+        });
+    }
+}
+
+/// Points for the `hints` in an element's class and id, lowercased
+fn hint_weight(hints: &str) -> f64 {
+    let mut weight = 0.0;
+
+    if POSITIVE_HINTS.iter().any(|hint| hints.contains(hint)) {
+        weight += HINT_WEIGHT;
+    }
+    if NEGATIVE_HINTS.iter().any(|hint| hints.contains(hint)) {
+        weight -= HINT_WEIGHT;
+    }
+
+    weight
+}
+
+/// `html` without its tags
+fn text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+
+    text
+}
+
+/// How much of the text of `html` is the text of links, from 0 to 1
+fn link_density(html: &str) -> f64 {
+    let text_len = text(html).trim().chars().count();
+    if text_len == 0 {
+        return 0.0;
+    }
+
+    let mut link_text_len = 0;
+    let mut tags = util::tags(html);
+
+    while let Some(tag) = tags.next() {
+        if tag.name == "a" && !tag.is_closing {
+            let closing_tag_end = tags.skip_element("a").unwrap_or(html.len());
+            link_text_len += text(&html[tag.range.end..closing_tag_end])
+                .trim()
+                .chars()
+                .count();
+        }
+    }
+
+    (link_text_len as f64 / text_len as f64).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_article_in_a_page() {
+        let paragraph = "<p>Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.</p>";
+
+        let html = format!(
+            r#"<!DOCTYPE html>
+            <html>
+            <head><title>A post</title><script>var p = "<p>not this</p>";</script></head>
+            <body>
+              <nav><p>Home, About, Archive, and everything else on this site</p></nav>
+              <div class="sidebar">
+                <p>Some other post, that you might like, just as much as this one</p>
+              </div>
+              <div class="post-content">
+                <h1>A post</h1>
+                {paragraph}
+                <p>Unclosed, but still a paragraph, with plenty of words in it
+                {paragraph}
+                <img src="/photo.jpg">
+              </div>
+              <div class="links">
+                <p><a href="/1">A link to something else, with a long title</a></p>
+                <p><a href="/2">Another link to something else, with a long title</a></p>
+              </div>
+            </body>
+            </html>"#
+        );
+
+        let article = extract_article(&html).unwrap();
+
+        assert!(article.starts_with("<h1>A post</h1>"));
+        assert!(article.contains("Unclosed, but still a paragraph"));
+        assert!(article.ends_with(r#"<img src="/photo.jpg">"#));
+        assert!(!article.contains("you might like"));
+
+        assert_eq!(
+            extract_article("<html><body><p>Too short</p></body></html>"),
+            None
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! `russ refresh` refreshes every feed once, without a TUI,
+//! or with `--dry-run`, reports how many new entries each feed would add without adding them
+
+use crate::progress::OutputFormat;
+use crate::RefreshOptions;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+/// What `russ refresh --output json` prints
+#[derive(Debug, Default, Serialize)]
+struct Summary {
+    refreshed: usize,
+    failed: usize,
+    /// entries added, or with `--dry-run`, that would be added
+    new_entries: usize,
+    /// with `--dry-run`, each feed that would get new entries, and how many
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    feeds_with_new_entries: Vec<FeedNewEntries>,
+    errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FeedNewEntries {
+    feed: String,
+    new_entries: usize,
+}
+
+pub(crate) fn refresh(options: RefreshOptions) -> Result<ExitCode> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+    crate::rss::tune_connection(&conn, &options.database_options)?;
+    crate::rss::initialize_db(&mut conn)?;
+
+    let connection_pool = crate::io::connection_pool(
+        &options.database_path,
+        &options.database_options,
+        &options.ingest_options,
+    )?;
+
+    let http_client = ureq::AgentBuilder::new()
+        .timeout_read(options.network_timeout)
+        .user_agent("russ/0.5.0")
+        .build();
+
+    let feed_ids = crate::rss::get_refreshable_feed_ids(&conn)?;
+
+    let feed_names = crate::rss::get_feeds(&conn, crate::rss::FeedSort::default())?
+        .into_iter()
+        .map(|feed| {
+            let name = feed
+                .title
+                .or(feed.feed_link)
+                .unwrap_or_else(|| feed.id.to_string());
+            (feed.id, name)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let feed_name = |feed_id| {
+        feed_names
+            .get(&feed_id)
+            .cloned()
+            .unwrap_or_else(|| feed_id.to_string())
+    };
+
+    let text_output = options.output == OutputFormat::Text;
+    let mut summary = Summary::default();
+
+    if options.dry_run {
+        crate::io::preview_refresh_feeds(
+            &http_client,
+            &connection_pool,
+            &feed_ids,
+            &options.ingest_options,
+            |feed_id, preview_result| match preview_result {
+                Ok(0) => (),
+                Ok(count) => {
+                    if text_output {
+                        println!("{count}\t{}", feed_name(feed_id));
+                    }
+                    summary.new_entries += count;
+                    summary.feeds_with_new_entries.push(FeedNewEntries {
+                        feed: feed_name(feed_id),
+                        new_entries: count,
+                    });
+                }
+                Err(e) => {
+                    let error = format!("skipping feed {}: {e:#}", feed_name(feed_id));
+                    if text_output {
+                        eprintln!("{error}");
+                    }
+                    summary.errors.push(error);
+                }
+            },
+        )?;
+
+        summary.failed = summary.errors.len();
+        summary.refreshed = feed_ids.len() - summary.failed;
+
+        if text_output {
+            eprintln!(
+                "refreshing would add {} entries to {}/{} feeds",
+                summary.new_entries,
+                summary.feeds_with_new_entries.len(),
+                feed_ids.len()
+            );
+        }
+    } else {
+        // inserted_at only has whole seconds
+        let refreshed_since = chrono::SubsecRound::trunc_subsecs(chrono::Utc::now(), 0);
+
+        crate::io::refresh_feeds(
+            &http_client,
+            &connection_pool,
+            &feed_ids,
+            &options.ingest_options,
+            |refresh_result| {
+                if let Err(e) = refresh_result {
+                    let error = format!("{e:#}");
+                    if text_output {
+                        eprintln!("{error}");
+                    }
+                    summary.errors.push(error);
+                }
+            },
+        )?;
+
+        summary.failed = summary.errors.len();
+        summary.refreshed = feed_ids.len() - summary.failed;
+        summary.new_entries = crate::rss::count_entries_inserted_since(&conn, refreshed_since)?;
+
+        if text_output {
+            eprintln!(
+                "refreshed {}/{} feeds, adding {} entries",
+                summary.refreshed,
+                feed_ids.len(),
+                summary.new_entries
+            );
+        }
+    }
+
+    if options.output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    }
+
+    Ok(crate::progress::exit_code(
+        summary.refreshed,
+        summary.failed,
+    ))
+}
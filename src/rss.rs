@@ -1,22 +1,39 @@
 //! The functions and datatypes in this module all for the retrieval and storage
 //! of RSS/Atom feeds in Russ' SQLite database.
 
+use crate::error::FetchError;
 use crate::modes::ReadMode;
 use anyhow::{bail, Context, Result};
 use atom_syndication as atom;
 use chrono::prelude::{DateTime, Utc};
 use html_escape::decode_html_entities_to_string;
 use rss::Channel;
-use rusqlite::params;
 use rusqlite::types::{FromSql, ToSqlOutput};
-use std::collections::HashSet;
+use rusqlite::{params, OptionalExtension};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+mod bot_check;
+mod compression;
+mod mastodon;
+mod migrations;
+mod query;
+mod search;
+
+use compression::StoredText;
+pub use query::{EntriesOrder, EntriesQuery};
+pub use search::search_entries;
+
+/// How many prepared statements a connection keeps around to be reused.
+/// rusqlite's default of 16 is fewer than the distinct queries the reader makes while navigating,
+/// which would evict statements that are about to be used again.
+pub const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub(crate) struct EntryId(i64);
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub(crate) struct FeedId(i64);
 
 impl From<i64> for EntryId {
@@ -67,6 +84,214 @@ impl Display for FeedId {
     }
 }
 
+/// Where a feed's documents come from, which follows from its feed link
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FeedSource {
+    Http,
+    /// a `file://` feed link
+    File,
+    /// an `exec:` feed link, whose command prints the feed on stdout, like newsboat's
+    Exec,
+    /// a `mailto:` feed link, for an email newsletter's sender.
+    /// these are only updated by `russ newsletters`
+    Email,
+    /// the feed bookmarks are imported into, with `russ import --from-bookmarks`.
+    /// it is never refreshed
+    Bookmarks,
+}
+
+/// The feed link of the feed that imported bookmarks are entries of
+pub const BOOKMARKS_FEED_LINK: &str = "bookmarks:";
+
+impl FeedSource {
+    fn of_feed_link(feed_link: &str) -> Self {
+        if feed_link.starts_with("file://") {
+            FeedSource::File
+        } else if feed_link.starts_with(EXEC_FEED_PREFIX) {
+            FeedSource::Exec
+        } else if feed_link.starts_with("mailto:") {
+            FeedSource::Email
+        } else if feed_link.starts_with(BOOKMARKS_FEED_LINK) {
+            FeedSource::Bookmarks
+        } else {
+            FeedSource::Http
+        }
+    }
+}
+
+impl Display for FeedSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let out = match self {
+            FeedSource::Http => "http",
+            FeedSource::File => "file",
+            FeedSource::Exec => "exec",
+            FeedSource::Email => "email",
+            FeedSource::Bookmarks => "bookmarks",
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+impl FromStr for FeedSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "http" => Ok(FeedSource::Http),
+            "file" => Ok(FeedSource::File),
+            "exec" => Ok(FeedSource::Exec),
+            "email" => Ok(FeedSource::Email),
+            "bookmarks" => Ok(FeedSource::Bookmarks),
+            _ => Err(anyhow::anyhow!(format!("{s} is not a valid FeedSource"))),
+        }
+    }
+}
+
+impl rusqlite::ToSql for FeedSource {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for FeedSource {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        FeedSource::from_str(value.as_str()?)
+            .map_err(|e| rusqlite::types::FromSqlError::Other(e.into()))
+    }
+}
+
+/// How a feed's entries are listed, chosen per feed
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EntrySort {
+    /// newest published first
+    #[default]
+    Published,
+    /// most recently first seen by russ first, for feeds that backfill old posts
+    /// or whose publication dates can't be trusted
+    Discovered,
+}
+
+impl EntrySort {
+    pub fn next(self) -> Self {
+        match self {
+            EntrySort::Published => EntrySort::Discovered,
+            EntrySort::Discovered => EntrySort::Published,
+        }
+    }
+
+    fn order(self) -> EntriesOrder {
+        match self {
+            EntrySort::Published => EntriesOrder::Newest,
+            EntrySort::Discovered => EntriesOrder::Discovered,
+        }
+    }
+}
+
+impl Display for EntrySort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let out = match self {
+            EntrySort::Published => "published",
+            EntrySort::Discovered => "discovered",
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+impl FromStr for EntrySort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "published" => Ok(EntrySort::Published),
+            "discovered" => Ok(EntrySort::Discovered),
+            _ => Err(anyhow::anyhow!("{s} is not a valid EntrySort")),
+        }
+    }
+}
+
+impl rusqlite::ToSql for EntrySort {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for EntrySort {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        EntrySort::from_str(value.as_str()?)
+            .map_err(|e| rusqlite::types::FromSqlError::Other(e.into()))
+    }
+}
+
+/// How the feeds are listed
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FeedSort {
+    /// by title, from A to Z
+    #[default]
+    Title,
+    /// the feed with the newest entry first
+    Updated,
+    /// the feed with the most unread entries first
+    Unread,
+    /// the most recently subscribed to feed first
+    Added,
+}
+
+impl FeedSort {
+    pub fn next(self) -> Self {
+        match self {
+            FeedSort::Title => FeedSort::Updated,
+            FeedSort::Updated => FeedSort::Unread,
+            FeedSort::Unread => FeedSort::Added,
+            FeedSort::Added => FeedSort::Title,
+        }
+    }
+
+    /// Feeds sorted the same any other way are sorted by title
+    fn to_sql(self) -> &'static str {
+        match self {
+            FeedSort::Title => "lower(coalesce(custom_title, title)) ASC",
+            FeedSort::Updated => {
+                "(SELECT max(coalesce(pub_date, inserted_at)) FROM entries WHERE entries.feed_id = feeds.id) DESC, lower(coalesce(custom_title, title)) ASC"
+            }
+            FeedSort::Unread => {
+                "(SELECT count(*) FROM entries WHERE entries.feed_id = feeds.id AND read_at IS NULL) DESC, lower(coalesce(custom_title, title)) ASC"
+            }
+            FeedSort::Added => "id DESC",
+        }
+    }
+}
+
+impl Display for FeedSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let out = match self {
+            FeedSort::Title => "title",
+            FeedSort::Updated => "updated",
+            FeedSort::Unread => "unread",
+            FeedSort::Added => "added",
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+impl FromStr for FeedSort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "title" => Ok(FeedSort::Title),
+            "updated" => Ok(FeedSort::Updated),
+            "unread" => Ok(FeedSort::Unread),
+            "added" => Ok(FeedSort::Added),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not one of title, updated, unread, or added"
+            )),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum FeedKind {
     Atom,
@@ -128,6 +353,48 @@ pub struct Feed {
     pub inserted_at: chrono::DateTime<Utc>,
     pub updated_at: chrono::DateTime<Utc>,
     pub latest_etag: Option<String>,
+    /// The WebSub (PubSubHubbub) hub the feed advertises, if any
+    pub hub_link: Option<String>,
+    /// When the feed's entries were last opened
+    pub last_viewed_at: Option<chrono::DateTime<Utc>>,
+    /// How many refreshes in a row have failed
+    pub consecutive_failures: u32,
+    /// When the feed was found to be dead, either by failing to refresh too many times
+    /// or by being explicitly gone. Dead feeds are not refreshed with the rest of the feeds.
+    pub dead_at: Option<chrono::DateTime<Utc>>,
+    /// Where the feed appears to have moved to, waiting for the user to confirm the move
+    pub suggested_feed_link: Option<String>,
+    pub source: FeedSource,
+    /// The most new entries a refresh of this feed may add, rather than the global limit
+    pub max_new_entries: Option<usize>,
+    /// How many new entries the last refresh left out for being over the limit
+    pub skipped_entries: usize,
+    /// Refreshes mark the feed's entries published more than this many days ago as read
+    pub mark_read_after_days: Option<u32>,
+    /// What the feed says it is: the RSS `description` or Atom `subtitle`
+    pub description: Option<String>,
+    pub language: Option<String>,
+    /// When the feed document was last changed, going by the RSS `lastBuildDate` or Atom `updated`
+    pub last_build_date: Option<chrono::DateTime<Utc>>,
+    /// The folder the feed is filed in, like the OPML outline it was imported from.
+    /// Nested folders are separated by ` / `
+    pub folder: Option<String>,
+    /// The bot protection, like Cloudflare, that blocked the feed's last refresh
+    pub blocked_by: Option<String>,
+    /// What the feed's last refresh got past without failing, like dates it could not read
+    pub refresh_warnings: Vec<String>,
+    /// Which old entries refreshes of this feed delete, rather than the global retention
+    pub retention: Option<Retention>,
+    pub entry_sort: EntrySort,
+    /// The title to show instead of the feed's own, which is kept in `title`
+    pub custom_title: Option<String>,
+}
+
+impl Feed {
+    /// The title the feed was given, or else its own
+    pub fn display_title(&self) -> Option<&str> {
+        self.custom_title.as_deref().or(self.title.as_deref())
+    }
 }
 
 /// This exists:
@@ -139,6 +406,16 @@ struct IncomingFeed {
     link: Option<String>,
     feed_kind: FeedKind,
     latest_etag: Option<String>,
+    /// the `Last-Modified` header the feed was served with, to send back as `If-Modified-Since`
+    last_modified: Option<String>,
+    /// RFC 5005 link to the next (older) page or archive document of this feed, if any
+    next_page_link: Option<String>,
+    hub_link: Option<String>,
+    /// Where the request for the feed ended up, if it was redirected somewhere else
+    redirected_to: Option<String>,
+    description: Option<String>,
+    language: Option<String>,
+    last_build_date: Option<chrono::DateTime<Utc>>,
 }
 
 /// This exists:
@@ -151,21 +428,20 @@ struct IncomingEntry {
     description: Option<String>,
     content: Option<String>,
     link: Option<String>,
+    /// where `link` ends up after following redirects, if it has been resolved
+    resolved_link: Option<String>,
+    /// the Atom `id` or RSS `guid`, which identifies an entry even if its link changes
+    guid: Option<String>,
 }
 
 impl From<&atom::Entry> for IncomingEntry {
     fn from(entry: &atom::Entry) -> Self {
         Self {
-            title: {
-                let mut title = String::new();
-                decode_html_entities_to_string(entry.title(), &mut title);
-                Some(title)
-            },
-            author: entry.authors().first().map(|entry_author| {
-                let mut author = String::new();
-                decode_html_entities_to_string(&entry_author.name, &mut author);
-                author
-            }),
+            title: Some(crate::util::normalize_text(entry.title())),
+            author: entry
+                .authors()
+                .first()
+                .map(|entry_author| crate::util::normalize_text(&entry_author.name)),
             pub_date: entry.published().map(|date| date.with_timezone(&Utc)),
             description: None,
             content: entry.content().and_then(|entry_content| {
@@ -176,6 +452,8 @@ impl From<&atom::Entry> for IncomingEntry {
                 })
             }),
             link: entry.links().first().map(|link| link.href().to_string()),
+            resolved_link: None,
+            guid: Some(entry.id().to_owned()).filter(|id| !id.is_empty()),
         }
     }
 }
@@ -183,16 +461,8 @@ impl From<&atom::Entry> for IncomingEntry {
 impl From<&rss::Item> for IncomingEntry {
     fn from(entry: &rss::Item) -> Self {
         Self {
-            title: entry.title().map(|entry_title| {
-                let mut title = String::new();
-                decode_html_entities_to_string(entry_title, &mut title);
-                title
-            }),
-            author: entry.author().map(|entry_author| {
-                let mut author = String::new();
-                decode_html_entities_to_string(entry_author, &mut author);
-                author
-            }),
+            title: entry.title().map(crate::util::normalize_text),
+            author: entry.author().map(crate::util::normalize_text),
             pub_date: entry.pub_date().and_then(parse_datetime),
             description: entry.description().map(|entry_description| {
                 let mut description = String::new();
@@ -205,10 +475,24 @@ impl From<&rss::Item> for IncomingEntry {
                 content
             }),
             link: entry.link().map(|link| link.to_owned()),
+            resolved_link: None,
+            guid: entry.guid().map(|guid| guid.value().to_owned()),
         }
     }
 }
 
+/// An entry imported from another feed reader,
+/// carrying over the read and starred state it had there.
+pub(crate) struct ImportedEntry {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub pub_date: Option<chrono::DateTime<Utc>>,
+    pub content: Option<String>,
+    pub link: Option<String>,
+    pub read_at: Option<chrono::DateTime<Utc>>,
+    pub starred_at: Option<chrono::DateTime<Utc>>,
+}
+
 /// Metadata for an entry.
 ///
 /// This type exists so we can load entry metadata for lots of
@@ -222,13 +506,41 @@ pub struct EntryMetadata {
     pub title: Option<String>,
     pub author: Option<String>,
     pub pub_date: Option<chrono::DateTime<Utc>>,
+    /// The date the entry said it was published when it arrived dated in the future,
+    /// if its `pub_date` was clamped to when it arrived instead
+    pub claimed_pub_date: Option<chrono::DateTime<Utc>>,
     pub link: Option<String>,
+    pub resolved_link: Option<String>,
     pub read_at: Option<chrono::DateTime<Utc>>,
+    /// Starred entries are kept for good: nothing that deletes entries,
+    /// other than deleting their feed, may delete one
+    pub starred_at: Option<chrono::DateTime<Utc>>,
     pub inserted_at: chrono::DateTime<Utc>,
     pub updated_at: chrono::DateTime<Utc>,
+    pub guid: Option<String>,
 }
 
 impl EntryMetadata {
+    /// The entry's title, or for entries without one, a placeholder with its date,
+    /// so they can still be told apart
+    pub fn display_title(&self) -> String {
+        match self.title.as_deref().map(str::trim) {
+            Some(title) if !title.is_empty() => title.to_owned(),
+            _ => format!(
+                "(untitled — {})",
+                self.pub_date.unwrap_or(self.inserted_at).format("%Y-%m-%d")
+            ),
+        }
+    }
+
+    /// Whether the entry says it is published in the future, or did before its date was clamped
+    pub fn is_future_dated(&self) -> bool {
+        self.claimed_pub_date.is_some()
+            || self
+                .pub_date
+                .is_some_and(|pub_date| is_future_date(pub_date, Utc::now()))
+    }
+
     pub fn toggle_read(&self, conn: &rusqlite::Connection) -> Result<()> {
         if self.read_at.is_none() {
             self.mark_as_read(conn)
@@ -238,39 +550,265 @@ impl EntryMetadata {
     }
 
     fn mark_as_read(&self, conn: &rusqlite::Connection) -> Result<()> {
-        let mut statement = conn.prepare("UPDATE entries SET read_at = ?2 WHERE id = ?1")?;
+        let mut statement = conn.prepare_cached("UPDATE entries SET read_at = ?2 WHERE id = ?1")?;
         statement.execute(params![self.id, Utc::now()])?;
         Ok(())
     }
 
     fn mark_as_unread(&self, conn: &rusqlite::Connection) -> Result<()> {
-        let mut statement = conn.prepare("UPDATE entries SET read_at = NULL WHERE id = ?1")?;
+        let mut statement =
+            conn.prepare_cached("UPDATE entries SET read_at = NULL WHERE id = ?1")?;
         statement.execute([self.id])?;
         Ok(())
     }
+
+    pub fn toggle_starred(&self, conn: &rusqlite::Connection) -> Result<()> {
+        let mut statement = conn.prepare_cached(
+            "UPDATE entries SET starred_at = CASE WHEN starred_at IS NULL THEN ?2 END WHERE id = ?1",
+        )?;
+        statement.execute(params![self.id, Utc::now()])?;
+        Ok(())
+    }
 }
 
 pub struct EntryContent {
     pub content: Option<String>,
     pub description: Option<String>,
+    /// The article fetched from the entry's link, for entries that are only summaries
+    pub full_content: Option<String>,
+}
+
+/// Everything stored about an entry, for inspecting it
+#[derive(Clone, Debug)]
+pub struct EntryInfo {
+    pub meta: EntryMetadata,
+    pub feed_title: Option<String>,
+    pub feed_link: Option<String>,
+    /// in bytes, uncompressed
+    pub content_len: Option<usize>,
+    /// in bytes, uncompressed
+    pub description_len: Option<usize>,
+}
+
+/// Whether `date` is in the future as of `now`,
+/// by more than a clock that is a little off would explain
+fn is_future_date(date: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    date > now + chrono::Duration::minutes(10)
 }
 
 fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
     diligent_date_parser::parse_date(s).map(|dt| dt.with_timezone(&Utc))
 }
 
+/// Options that control how incoming entries are cleaned up before they are stored.
+#[derive(Clone, Debug, Default)]
+pub struct IngestOptions {
+    /// query parameters to strip from entry links.
+    /// a trailing `*` matches any parameter with that prefix, like `utm_*`
+    pub tracking_parameters: Vec<String>,
+    /// HTML elements to remove from entry content and descriptions, along with everything inside them
+    pub strip_elements: Vec<String>,
+    /// follow redirects on new entry links when they are stored,
+    /// for feeds that wrap their links in a redirector like FeedBurner
+    pub resolve_redirects: bool,
+    /// compress entry content and descriptions with zstd before they are stored
+    pub compress_content: bool,
+    /// download the images new entries reference, for reading offline
+    pub asset_cache: Option<crate::assets::AssetCacheOptions>,
+    /// the most new entries a refresh may add to a feed, keeping the newest,
+    /// for feeds without a limit of their own
+    pub max_new_entries: Option<usize>,
+    /// cookies to send with feed requests, which also keeps the cookies feeds set
+    pub cookie_jar: Option<std::sync::Arc<crate::cookies::CookieJar>>,
+    /// how many feeds to refresh at once, rather than twice the number of CPUs
+    pub refresh_concurrency: Option<std::num::NonZeroUsize>,
+    /// which old entries refreshes delete, for feeds without a retention of their own
+    pub retention: Option<Retention>,
+    /// store entries dated in the future as published when they arrive,
+    /// so they do not stay at the top of their feed until then
+    pub clamp_future_dates: bool,
+}
+
+impl IngestOptions {
+    /// How many threads refresh feeds at once
+    pub fn refresh_threads(&self) -> usize {
+        self.refresh_concurrency
+            .map_or_else(|| num_cpus::get() * 2, std::num::NonZeroUsize::get)
+    }
+}
+
+/// Which of a feed's old entries refreshes delete, so the database does not grow forever.
+/// Unread and starred entries are always kept, as are entries still in the feed,
+/// which the next refresh would otherwise add back as new.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Retention {
+    /// keep the newest this many entries, written like `200`
+    Entries(usize),
+    /// keep the entries published in the last this many days, written like `30d`
+    Days(u32),
+}
+
+impl FromStr for Retention {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let retention = match s.strip_suffix('d') {
+            Some(days) => days.parse().map(Retention::Days),
+            None => s.parse().map(Retention::Entries),
+        };
+
+        retention.map_err(|_| {
+            anyhow::anyhow!("{s} is not a number of entries, like 200, or of days, like 30d")
+        })
+    }
+}
+
+impl std::fmt::Display for Retention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Retention::Entries(entries) => write!(f, "{entries}"),
+            Retention::Days(days) => write!(f, "{days}d"),
+        }
+    }
+}
+
+/// SQLite tunables, applied to every connection.
+/// The defaults suit a few hundred feeds; very large archives may want a bigger cache or mmap.
+#[derive(Clone, Debug)]
+pub struct DatabaseOptions {
+    /// the size of each connection's page cache, in KiB
+    pub cache_size_kib: u32,
+    /// how many bytes of the database file to memory-map. 0 disables memory-mapping
+    pub mmap_size: u64,
+    pub synchronous: Synchronous,
+    /// only takes effect when a database is created, or on the next `VACUUM`
+    pub page_size: Option<u32>,
+}
+
+impl Default for DatabaseOptions {
+    fn default() -> Self {
+        Self {
+            cache_size_kib: 8192,
+            mmap_size: 0,
+            synchronous: Synchronous::Full,
+            page_size: None,
+        }
+    }
+}
+
+/// How hard SQLite works to make sure writes have reached the disk before moving on.
+/// See <https://www.sqlite.org/pragma.html#pragma_synchronous>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Synchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl Synchronous {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Synchronous::Off => "OFF",
+            Synchronous::Normal => "NORMAL",
+            Synchronous::Full => "FULL",
+            Synchronous::Extra => "EXTRA",
+        }
+    }
+}
+
+impl FromStr for Synchronous {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" => Ok(Synchronous::Off),
+            "normal" => Ok(Synchronous::Normal),
+            "full" => Ok(Synchronous::Full),
+            "extra" => Ok(Synchronous::Extra),
+            _ => Err(anyhow::anyhow!(
+                "{s} is not one of off, normal, full, or extra"
+            )),
+        }
+    }
+}
+
 struct FeedAndEntries {
     pub feed: IncomingFeed,
     pub entries: Vec<IncomingEntry>,
+    /// what was wrong with the feed that its entries were read anyway
+    pub warnings: Vec<String>,
+    /// the Atom feed's `xml:base`, which its relative links are relative to,
+    /// and which may itself be relative to where the feed was fetched from
+    pub base: Option<String>,
 }
 
 impl FeedAndEntries {
+    fn clean_entries(&mut self, ingest_options: &IngestOptions) {
+        for entry in &mut self.entries {
+            if let Some(link) = &entry.link {
+                entry.link = Some(crate::util::strip_tracking_parameters(
+                    link,
+                    &ingest_options.tracking_parameters,
+                ));
+            }
+
+            for html in [&mut entry.content, &mut entry.description]
+                .into_iter()
+                .flatten()
+            {
+                *html = crate::util::strip_html_elements(html, &ingest_options.strip_elements);
+            }
+        }
+    }
+
     fn set_feed_link(&mut self, url: &str) {
         self.feed.feed_link = Some(url.to_owned());
     }
 
-    fn set_latest_etag(&mut self, etag: Option<String>) {
-        self.feed.latest_etag = etag;
+    /// Makes the feed's and its entries' relative links absolute, against the feed's `xml:base`
+    /// and `document_url`, where the feed was fetched from, so they can be opened.
+    /// Links that are already absolute are left exactly as they are, as entries are matched by link
+    fn resolve_links(&mut self, document_url: &str) {
+        let Ok(document_url) = url::Url::parse(document_url) else {
+            return;
+        };
+
+        let base_url = self
+            .base
+            .as_deref()
+            .and_then(|base| document_url.join(base).ok())
+            .unwrap_or(document_url);
+
+        let resolve = |link: &mut Option<String>| {
+            let Some(relative_link) = link.as_deref() else {
+                return;
+            };
+
+            if let Err(url::ParseError::RelativeUrlWithoutBase) = url::Url::parse(relative_link) {
+                if let Ok(resolved_link) = base_url.join(relative_link) {
+                    *link = Some(resolved_link.to_string());
+                }
+            }
+        };
+
+        resolve(&mut self.feed.link);
+        resolve(&mut self.feed.next_page_link);
+        resolve(&mut self.feed.hub_link);
+
+        for entry in &mut self.entries {
+            resolve(&mut entry.link);
+        }
+    }
+
+    fn set_cache_validators(&mut self, cache_validators: CacheValidators) {
+        self.feed.latest_etag = cache_validators.etag;
+        self.feed.last_modified = cache_validators.last_modified;
+    }
+
+    fn set_redirected_to(&mut self, redirected_to: Option<String>) {
+        self.feed.redirected_to = redirected_to;
     }
 }
 
@@ -281,11 +819,26 @@ impl FromStr for FeedAndEntries {
         match atom::Feed::from_str(s) {
             Ok(atom_feed) => {
                 let feed = IncomingFeed {
-                    title: Some(atom_feed.title.to_string()),
+                    title: Some(crate::util::normalize_text(&atom_feed.title)),
                     feed_link: None,
                     link: atom_feed.links.first().map(|link| link.href().to_string()),
                     feed_kind: FeedKind::Atom,
                     latest_etag: None,
+                    last_modified: None,
+                    next_page_link: atom_feed
+                        .links
+                        .iter()
+                        .find(|link| link.rel() == "next" || link.rel() == "prev-archive")
+                        .map(|link| link.href().to_string()),
+                    hub_link: find_hub_link(&atom_feed.links),
+                    redirected_to: None,
+                    description: atom_feed
+                        .subtitle
+                        .as_ref()
+                        .map(|subtitle| subtitle.value.trim().to_string())
+                        .filter(|subtitle| !subtitle.is_empty()),
+                    language: atom_feed.lang.clone(),
+                    last_build_date: Some(atom_feed.updated.with_timezone(&Utc)),
                 };
 
                 let entries = atom_feed
@@ -294,26 +847,73 @@ impl FromStr for FeedAndEntries {
                     .map(|entry| entry.into())
                     .collect::<Vec<_>>();
 
-                Ok(FeedAndEntries { feed, entries })
+                Ok(FeedAndEntries {
+                    feed,
+                    entries,
+                    warnings: vec![],
+                    base: atom_feed.base.clone(),
+                })
             }
 
             Err(_e) => match Channel::from_str(s) {
                 Ok(channel) => {
                     let feed = IncomingFeed {
-                        title: Some(channel.title().to_string()),
+                        title: Some(crate::util::normalize_text(channel.title())),
                         feed_link: None,
                         link: Some(channel.link().to_string()),
                         feed_kind: FeedKind::Rss,
                         latest_etag: None,
+                        last_modified: None,
+                        next_page_link: None,
+                        hub_link: channel
+                            .atom_ext()
+                            .and_then(|atom_ext| find_hub_link(atom_ext.links())),
+                        redirected_to: None,
+                        description: Some(channel.description().trim().to_string())
+                            .filter(|description| !description.is_empty()),
+                        language: channel.language().map(|language| language.to_string()),
+                        last_build_date: channel.last_build_date().and_then(parse_datetime),
                     };
 
+                    let is_mastodon_feed = mastodon::is_mastodon_feed(&channel);
+
                     let entries = channel
                         .items()
                         .iter()
-                        .map(|item| item.into())
+                        .map(|item| {
+                            let mut entry = IncomingEntry::from(item);
+                            if is_mastodon_feed {
+                                mastodon::adapt_entry(&mut entry, item);
+                            }
+                            entry
+                        })
+                        .collect::<Vec<_>>();
+
+                    // atom dates that cannot be read fail the whole feed, but these are only left out
+                    let unreadable_dates = channel
+                        .items()
+                        .iter()
+                        .filter_map(|item| item.pub_date())
+                        .filter(|pub_date| parse_datetime(pub_date).is_none())
                         .collect::<Vec<_>>();
 
-                    Ok(FeedAndEntries { feed, entries })
+                    let warnings = match unreadable_dates[..] {
+                        [] => vec![],
+                        [pub_date] => vec![format!(
+                            "1 entry has a date that could not be read, {pub_date:?}, and is sorted as undated"
+                        )],
+                        [pub_date, ..] => vec![format!(
+                            "{} entries have dates that could not be read, like {pub_date:?}, and are sorted as undated",
+                            unreadable_dates.len()
+                        )],
+                    };
+
+                    Ok(FeedAndEntries {
+                        feed,
+                        entries,
+                        warnings,
+                        base: None,
+                    })
                 }
                 Err(e) => Err(e.into()),
             },
@@ -321,32 +921,137 @@ impl FromStr for FeedAndEntries {
     }
 }
 
-pub fn subscribe_to_feed(
+/// WebSub hubs are advertised with `<link rel="hub">`,
+/// either directly in Atom feeds or as `<atom:link>` elements in RSS channels
+fn find_hub_link(links: &[atom::Link]) -> Option<String> {
+    links
+        .iter()
+        .find(|link| link.rel() == "hub")
+        .map(|link| link.href().to_string())
+}
+
+/// Subscribes to the feed at `url`.
+/// If `url` is a web page rather than a feed, like a site's homepage,
+/// this subscribes to the first working feed the page advertises instead.
+///
+/// If `max_archive_pages` is greater than 0 and the feed advertises
+/// RFC 5005 `next` or `prev-archive` links, up to `max_archive_pages`
+/// additional pages are fetched so the feed's history is not limited
+/// to the latest page. `on_archive_page` is called with the number of
+/// each archive page as it is fetched.
+pub fn subscribe_to_feed<F>(
     http_client: &ureq::Agent,
     conn: &mut rusqlite::Connection,
     url: &str,
-) -> Result<FeedId> {
-    let feed_and_entries = fetch_feed(http_client, url, None)?;
+    ingest_options: &IngestOptions,
+    max_archive_pages: usize,
+    mut on_archive_page: F,
+) -> Result<FeedId>
+where
+    F: FnMut(usize) -> Result<()>,
+{
+    let url = &mastodon::account_feed_link(url)
+        .or_else(|| local_feed_link(url))
+        .unwrap_or_else(|| url.to_owned());
+
+    let (url, feed_and_entries) = match fetch_feed(
+        http_client,
+        url,
+        &CacheValidators::default(),
+        &FeedHttpSettings::default(),
+        ingest_options,
+    ) {
+        Err(e) if is_not_a_feed(&e) && FeedSource::of_feed_link(url) == FeedSource::Http => {
+            let Some(feed_link) =
+                find_working_feed_on_site(http_client, url, None, ingest_options)?
+            else {
+                return Err(e.context("The page advertises no working feed"));
+            };
+
+            let feed_and_entries = fetch_feed(
+                http_client,
+                &feed_link,
+                &CacheValidators::default(),
+                &FeedHttpSettings::default(),
+                ingest_options,
+            )?;
+
+            (feed_link, feed_and_entries)
+        }
+        result => (url.to_owned(), result?),
+    };
 
     match feed_and_entries {
-        FeedResponse::CacheMiss(feed_and_entries) => {
-            let feed_id = in_transaction(conn, |tx| {
+        FeedResponse::CacheMiss(mut feed_and_entries) => {
+            let mut seen_page_links = HashSet::from([url.to_owned()]);
+            let mut seen_entry_links = feed_and_entries
+                .entries
+                .iter()
+                .flat_map(|entry| entry.link.clone())
+                .collect::<HashSet<_>>();
+            let mut next_page_link = feed_and_entries.feed.next_page_link.clone();
+            let mut pages_fetched = 0;
+
+            while let Some(page_link) = next_page_link.take() {
+                if pages_fetched >= max_archive_pages || !seen_page_links.insert(page_link.clone())
+                {
+                    break;
+                }
+
+                pages_fetched += 1;
+                on_archive_page(pages_fetched)?;
+
+                if let FeedResponse::CacheMiss(page) = fetch_feed(
+                    http_client,
+                    &page_link,
+                    &CacheValidators::default(),
+                    &FeedHttpSettings::default(),
+                    ingest_options,
+                )
+                .with_context(|| format!("Failed to fetch archive page {page_link}"))?
+                {
+                    next_page_link = page.feed.next_page_link;
+
+                    feed_and_entries
+                        .entries
+                        .extend(page.entries.into_iter().filter(|entry| match &entry.link {
+                            Some(link) => seen_entry_links.insert(link.clone()),
+                            None => true,
+                        }));
+                }
+            }
+
+            if ingest_options.resolve_redirects {
+                resolve_entry_links(http_client, &mut feed_and_entries.entries, ingest_options);
+            }
+
+            let (feed_id, entry_ids) = in_transaction(conn, |tx| {
                 let feed_id = create_feed(tx, &feed_and_entries.feed).with_context(|| {
                     format!(
                         "creating feed {:?} failed",
                         &feed_and_entries.feed.feed_link
                     )
                 })?;
-                add_entries_to_feed(tx, feed_id, &feed_and_entries.entries).with_context(|| {
-                    format!(
-                        "inserting {} entries for feed {:?} failed",
-                        &feed_and_entries.entries.len(),
-                        &feed_and_entries.feed.feed_link
-                    )
-                })?;
-                Ok(feed_id)
+                let entry_ids =
+                    add_entries_to_feed(tx, feed_id, &feed_and_entries.entries, ingest_options)
+                        .with_context(|| {
+                            format!(
+                                "inserting {} entries for feed {:?} failed",
+                                &feed_and_entries.entries.len(),
+                                &feed_and_entries.feed.feed_link
+                            )
+                        })?;
+                Ok((feed_id, entry_ids))
             })?;
 
+            cache_entries_assets(
+                http_client,
+                conn,
+                &entry_ids,
+                &feed_and_entries.entries,
+                ingest_options,
+            );
+
             Ok(feed_id)
         }
         FeedResponse::CacheHit => {
@@ -355,541 +1060,4167 @@ pub fn subscribe_to_feed(
     }
 }
 
-enum FeedResponse {
-    /// The remote host returned a new feed.
-    /// The data may not actually be new, as hosts
-    /// seem to change etags for all kinds of reasons
-    CacheMiss(FeedAndEntries),
-    /// the remote host indicated a cache hit,
-    /// and did not return any new data
-    CacheHit,
+/// What the last fetch of a feed was told about the feed's version, sent back with the next fetch,
+/// so a feed that has not changed since comes back `304 Not Modified`, without a body to parse
+#[derive(Debug, Default)]
+struct CacheValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
 }
 
-fn fetch_feed(
-    http_client: &ureq::Agent,
-    url: &str,
-    current_etag: Option<String>,
-) -> Result<FeedResponse> {
-    let request = http_client.get(url);
-
-    let request = if let Some(etag) = current_etag {
-        request.set("If-None-Match", &etag)
-    } else {
-        request
-    };
+/// What is sent with every request for a feed, on top of what is sent for every feed,
+/// for feeds that need authentication, or that turn away russ's own User-Agent
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeedHttpSettings {
+    /// sent instead of russ's own User-Agent
+    pub user_agent: Option<String>,
+    /// each header's name and value
+    pub headers: Vec<(String, String)>,
+}
 
-    let response = request.call()?;
+/// Headers typed on one line, separated by `|`, like `User-Agent: curl/8.0 | Authorization: Bearer abc`.
+/// A `User-Agent` header sets the user agent
+impl FromStr for FeedHttpSettings {
+    type Err = anyhow::Error;
 
-    match response.status() {
-        // the etags did not match, it is a new feed file
-        200 => {
-            let header_names = response.headers_names();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut settings = FeedHttpSettings::default();
 
-            let etag_header_name = header_names
-                .iter()
-                .find(|header_name| header_name.to_lowercase() == "etag");
+        for header in s
+            .split('|')
+            .map(str::trim)
+            .filter(|header| !header.is_empty())
+        {
+            let Some((name, value)) = header.split_once(':') else {
+                bail!("{header} is not a header, like Name: value");
+            };
 
-            let etag = etag_header_name
-                .and_then(|etag_header| response.header(etag_header))
-                .map(|etag| etag.to_owned());
+            let (name, value) = (name.trim(), value.trim());
 
-            let content = response.into_string()?;
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_graphic()) {
+                bail!("{name:?} is not a header name");
+            }
 
-            let mut feed_and_entries = FeedAndEntries::from_str(&content)?;
+            if name.eq_ignore_ascii_case("user-agent") {
+                settings.user_agent = Some(value.to_owned());
+            } else {
+                settings.headers.push((name.to_owned(), value.to_owned()));
+            }
+        }
 
-            feed_and_entries.set_latest_etag(etag);
+        Ok(settings)
+    }
+}
 
-            feed_and_entries.set_feed_link(url);
+/// The way `from_str` reads them
+impl Display for FeedHttpSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers = self
+            .user_agent
+            .iter()
+            .map(|user_agent| ("User-Agent", user_agent.as_str()))
+            .chain(
+                self.headers
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str())),
+            );
 
-            Ok(FeedResponse::CacheMiss(feed_and_entries))
+        for (i, (name, value)) in headers.enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            write!(f, "{name}: {value}")?;
         }
-        // the etags match, it is the same feed we already have
-        304 => Ok(FeedResponse::CacheHit),
-        _ => Err(anyhow::anyhow!(
-            "received unexpected status code fetching feed {response:?}"
-        )),
+
+        Ok(())
     }
 }
 
-/// fetches the feed and stores the new entries
-/// uses the link as the uniqueness key.
-/// TODO hash the content to see if anything changed, and update that way.
-pub fn refresh_feed(
-    client: &ureq::Agent,
-    conn: &mut rusqlite::Connection,
-    feed_id: FeedId,
-) -> Result<()> {
-    let feed_url = get_feed_url(conn, feed_id)
-        .with_context(|| format!("Unable to get url for feed id {feed_id} from the database",))?;
+enum FeedResponse {
+    /// The remote host returned a new feed.
+    /// The data may not actually be new, as hosts
+    /// seem to change etags and modification times for all kinds of reasons
+    CacheMiss(Box<FeedAndEntries>),
+    /// the remote host indicated a cache hit,
+    /// and did not return any new data
+    CacheHit,
+}
 
-    let current_etag = get_feed_latest_etag(conn, feed_id).with_context(|| {
-        format!("Unable to get latest_etag for feed_id {feed_id} from the database")
-    })?;
+/// A `file://` URL for `path`, if it is a local file rather than a URL,
+/// so the feed still works when russ is run from a different directory
+fn local_feed_link(path: &str) -> Option<String> {
+    if path.contains("://") || path.starts_with(EXEC_FEED_PREFIX) {
+        return None;
+    }
 
-    let remote_feed = fetch_feed(client, &feed_url, current_etag)
-        .with_context(|| format!("Failed to fetch feed {feed_url}"))?;
+    let path = std::fs::canonicalize(path).ok()?;
 
-    if let FeedResponse::CacheMiss(remote_feed) = remote_feed {
-        let remote_items = remote_feed.entries;
-        let remote_items_links = remote_items
-            .iter()
-            .flat_map(|item| &item.link)
-            .cloned()
-            .collect::<HashSet<String>>();
+    if !path.is_file() {
+        return None;
+    }
 
-        let local_entries_links = get_entries_links(conn, &ReadMode::All, feed_id)?
-            .into_iter()
-            .flatten()
-            .collect::<HashSet<_>>();
+    url::Url::from_file_path(path)
+        .ok()
+        .map(|url| url.to_string())
+}
 
-        let difference = remote_items_links
-            .difference(&local_entries_links)
-            .cloned()
-            .collect::<HashSet<_>>();
+/// Reads a `file://` feed, which is never cached, so it is read again on every refresh
+fn read_local_feed(url: &str, ingest_options: &IngestOptions) -> Result<FeedResponse> {
+    let path = url::Url::parse(url)?
+        .to_file_path()
+        .map_err(|_| anyhow::anyhow!("{url} is not a local file path"))?;
 
-        let items_to_add = remote_items
-            .into_iter()
-            .filter(|item| match &item.link {
-                Some(link) => difference.contains(link.as_str()),
-                None => false,
-            })
-            .collect::<Vec<_>>();
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
 
-        in_transaction(conn, |tx| {
-            add_entries_to_feed(tx, feed_id, &items_to_add)?;
-            update_feed_refreshed_at(tx, feed_id)?;
-            update_feed_etag(tx, feed_id, remote_feed.feed.latest_etag.clone())?;
-            Ok(())
-        })?;
-    } else {
-        in_transaction(conn, |tx| update_feed_refreshed_at(tx, feed_id))?;
+    parse_feed_document(url, &content, ingest_options)
+}
+
+/// Feed links starting with this are a command to run, which prints the feed on stdout
+const EXEC_FEED_PREFIX: &str = "exec:";
+
+/// Runs an `exec:` feed's command, which is run again on every refresh
+fn run_exec_feed(url: &str, ingest_options: &IngestOptions) -> Result<FeedResponse> {
+    let command = url.trim_start_matches(EXEC_FEED_PREFIX);
+
+    let output = crate::util::shell_command(command)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to run `{command}`"))?;
+
+    if !output.status.success() {
+        bail!(
+            "`{command}` failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
 
-    Ok(())
+    parse_feed_document(
+        url,
+        &String::from_utf8_lossy(&output.stdout),
+        ingest_options,
+    )
 }
 
-pub fn initialize_db(conn: &mut rusqlite::Connection) -> Result<()> {
-    in_transaction(conn, |tx| {
-        let schema_version: u64 = tx.pragma_query_value(None, "user_version", |row| row.get(0))?;
-
-        if schema_version == 0 {
-            tx.pragma_update(None, "user_version", 1)?;
-
-            tx.execute(
-                "CREATE TABLE IF NOT EXISTS feeds (
-        id INTEGER PRIMARY KEY AUTOINCREMENT,
-        title TEXT,
-        feed_link TEXT,
-        link TEXT,
-        feed_kind TEXT,
-        refreshed_at TIMESTAMP,
-        inserted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )",
-                [],
-            )?;
+/// A feed document that did not come from an HTTP request, so has no etag or redirect
+fn parse_feed_document(
+    url: &str,
+    content: &str,
+    ingest_options: &IngestOptions,
+) -> Result<FeedResponse> {
+    let mut feed_and_entries = FeedAndEntries::from_str(content)?;
 
-            tx.execute(
-                "CREATE TABLE IF NOT EXISTS entries (
-        id INTEGER PRIMARY KEY AUTOINCREMENT,
-        feed_id INTEGER,
-        title TEXT,
-        author TEXT,
-        pub_date TIMESTAMP,
-        description TEXT,
-        content TEXT,
-        link TEXT,
-        read_at TIMESTAMP,
-        inserted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )",
-                [],
-            )?;
+    feed_and_entries.set_feed_link(url);
 
-            tx.execute(
-                "CREATE INDEX IF NOT EXISTS entries_feed_id_and_pub_date_and_inserted_at_index
-        ON entries (feed_id, pub_date, inserted_at)",
-                [],
-            )?;
+    feed_and_entries.resolve_links(url);
+
+    feed_and_entries.clean_entries(ingest_options);
+
+    Ok(FeedResponse::CacheMiss(Box::new(feed_and_entries)))
+}
+
+fn fetch_feed(
+    http_client: &ureq::Agent,
+    url: &str,
+    cache_validators: &CacheValidators,
+    http_settings: &FeedHttpSettings,
+    ingest_options: &IngestOptions,
+) -> Result<FeedResponse> {
+    match FeedSource::of_feed_link(url) {
+        FeedSource::File => return read_local_feed(url, ingest_options),
+        FeedSource::Exec => return run_exec_feed(url, ingest_options),
+        FeedSource::Email => bail!("Newsletters are fetched with `russ newsletters`"),
+        FeedSource::Bookmarks => bail!("Bookmarks are added with `russ import --from-bookmarks`"),
+        FeedSource::Http => (),
+    }
+
+    let request = http_client.get(url);
+
+    let request = if let Some(etag) = &cache_validators.etag {
+        request.set("If-None-Match", etag)
+    } else {
+        request
+    };
+
+    let request = if let Some(last_modified) = &cache_validators.last_modified {
+        request.set("If-Modified-Since", last_modified)
+    } else {
+        request
+    };
+
+    let cookie_header = ingest_options
+        .cookie_jar
+        .as_ref()
+        .and_then(|cookie_jar| cookie_jar.cookie_header(url));
+
+    let request = if let Some(cookie_header) = &cookie_header {
+        request.set("Cookie", cookie_header)
+    } else {
+        request
+    };
+
+    // last, so the feed's own headers win over russ's
+    let request = if let Some(user_agent) = &http_settings.user_agent {
+        request.set("User-Agent", user_agent)
+    } else {
+        request
+    };
+
+    let request = http_settings
+        .headers
+        .iter()
+        .fold(request, |request, (name, value)| request.set(name, value));
+
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(status @ (403 | 429 | 503), response)) => {
+            return Err(blocked_or_status_error(url, status, response).into())
+        }
+        Err(ureq::Error::Status(status, _)) => {
+            return Err(FetchError::Status {
+                url: url.to_owned(),
+                status,
+            }
+            .into())
+        }
+        Err(ureq::Error::Transport(transport)) => {
+            return Err(FetchError::Unreachable {
+                url: url.to_owned(),
+                source: Box::new(transport),
+            }
+            .into())
         }
+    };
+
+    if let Some(cookie_jar) = &ingest_options.cookie_jar {
+        cookie_jar.store(response.get_url(), &response.all("set-cookie"))?;
+    }
+
+    match response.status() {
+        // the feed has changed, or the server does not say it has not
+        200 => {
+            let header_names = response.headers_names();
+
+            let etag_header_name = header_names
+                .iter()
+                .find(|header_name| header_name.to_lowercase() == "etag");
+
+            let etag = etag_header_name
+                .and_then(|etag_header| response.header(etag_header))
+                .map(|etag| etag.to_owned());
+
+            let last_modified = response
+                .header("Last-Modified")
+                .map(|last_modified| last_modified.to_owned());
 
-        if schema_version <= 1 {
-            tx.pragma_update(None, "user_version", 2)?;
+            let final_url = response.get_url().to_owned();
+            let redirected_to = Some(final_url.clone()).filter(|final_url| final_url != url);
 
-            tx.execute("ALTER TABLE feeds ADD COLUMN latest_etag TEXT", [])?;
+            let content = response
+                .into_string()
+                .map_err(|e| FetchError::Unreachable {
+                    url: url.to_owned(),
+                    source: Box::new(e),
+                })?;
+
+            let mut feed_and_entries =
+                FeedAndEntries::from_str(&content).map_err(
+                    |e| match bot_check::challenge_provider(&content) {
+                        Some(provider) => FetchError::BlockedByBotProtection {
+                            provider: provider.to_owned(),
+                        },
+                        None => FetchError::NotAFeed {
+                            url: url.to_owned(),
+                            source: e.into(),
+                        },
+                    },
+                )?;
+
+            feed_and_entries.set_cache_validators(CacheValidators {
+                etag,
+                last_modified,
+            });
+
+            feed_and_entries.set_feed_link(url);
+
+            // relative links are relative to where the feed ended up, after any redirects
+            feed_and_entries.resolve_links(&final_url);
+
+            feed_and_entries.set_redirected_to(redirected_to);
+
+            feed_and_entries.clean_entries(ingest_options);
+
+            Ok(FeedResponse::CacheMiss(Box::new(feed_and_entries)))
         }
+        // the feed has not changed since the etag or modification time we sent
+        304 => Ok(FeedResponse::CacheHit),
+        status => Err(FetchError::Status {
+            url: url.to_owned(),
+            status,
+        }
+        .into()),
+    }
+}
 
-        if schema_version <= 2 {
-            tx.pragma_update(None, "user_version", 3)?;
+/// Fetches the web page at `link` and finds the article in it,
+/// for entries whose content is only a summary
+pub fn fetch_full_content(
+    http_client: &ureq::Agent,
+    link: &str,
+    ingest_options: &IngestOptions,
+) -> Result<String> {
+    let html = http_client
+        .get(link)
+        .call()
+        .with_context(|| format!("Failed to fetch {link}"))?
+        .into_string()?;
+
+    let article = crate::readability::extract_article(&html)
+        .ok_or_else(|| anyhow::anyhow!("Could not find an article at {link}"))?;
+
+    Ok(crate::util::strip_html_elements(
+        &article,
+        &ingest_options.strip_elements,
+    ))
+}
 
-            tx.execute(
-                "CREATE UNIQUE INDEX IF NOT EXISTS feeds_feed_link ON feeds (feed_link)",
-                [],
-            )?;
+/// The error for a feed that answered with an error `status`,
+/// saying which bot protection blocked it if the answer was a bot protection page
+fn blocked_or_status_error(url: &str, status: u16, response: ureq::Response) -> FetchError {
+    let body = response.into_string().unwrap_or_default();
+
+    match bot_check::challenge_provider(&body) {
+        Some(provider) => FetchError::BlockedByBotProtection {
+            provider: provider.to_owned(),
+        },
+        None => FetchError::Status {
+            url: url.to_owned(),
+            status,
+        },
+    }
+}
+
+/// Resolves entry links in place, returning how many failed to resolve.
+/// Links that fail to resolve are left unresolved rather than failing the whole feed,
+/// as they can always be resolved again later, on demand.
+fn resolve_entry_links(
+    http_client: &ureq::Agent,
+    entries: &mut [IncomingEntry],
+    ingest_options: &IngestOptions,
+) -> usize {
+    let mut unresolved = 0;
+
+    for entry in entries {
+        if let Some(link) = &entry.link {
+            match resolve_link(http_client, link, ingest_options) {
+                Ok(resolved_link) => {
+                    entry.resolved_link =
+                        Some(resolved_link).filter(|resolved_link| resolved_link != link)
+                }
+                Err(_) => {
+                    entry.resolved_link = None;
+                    unresolved += 1;
+                }
+            }
         }
+    }
 
-        Ok(())
-    })
+    unresolved
 }
 
-fn create_feed(tx: &rusqlite::Transaction, feed: &IncomingFeed) -> Result<FeedId> {
-    let feed_id = tx.query_row::<FeedId, _, _>(
-        "INSERT INTO feeds (title, link, feed_link, feed_kind)
-        VALUES (?1, ?2, ?3, ?4)
-        RETURNING id",
-        params![feed.title, feed.link, feed.feed_link, feed.feed_kind],
-        |r| r.get(0),
-    )?;
+/// Follows redirects from `link` and returns where they end up.
+///
+/// This tries a `HEAD` request first, falling back to `GET`
+/// for servers that do not handle `HEAD` properly.
+pub fn resolve_link(
+    http_client: &ureq::Agent,
+    link: &str,
+    ingest_options: &IngestOptions,
+) -> Result<String> {
+    let response = match http_client.head(link).call() {
+        Ok(response) => response,
+        Err(_) => http_client
+            .get(link)
+            .call()
+            .with_context(|| format!("Failed to resolve {link}"))?,
+    };
 
-    Ok(feed_id)
+    Ok(crate::util::strip_tracking_parameters(
+        response.get_url(),
+        &ingest_options.tracking_parameters,
+    ))
 }
 
-pub fn delete_feed(conn: &mut rusqlite::Connection, feed_id: FeedId) -> Result<()> {
-    in_transaction(conn, |tx| {
-        tx.execute("DELETE FROM feeds WHERE id = ?1", [feed_id])?;
-        tx.execute("DELETE FROM entries WHERE feed_id = ?1", [feed_id])?;
-        Ok(())
-    })
+pub fn update_entry_full_content(
+    conn: &rusqlite::Connection,
+    entry_id: EntryId,
+    full_content: String,
+    ingest_options: &IngestOptions,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE entry_contents SET full_content = ?2 WHERE entry_id = ?1",
+        params![
+            entry_id,
+            StoredText::new(full_content, ingest_options.compress_content)
+        ],
+    )?;
+
+    Ok(())
 }
 
-fn add_entries_to_feed(
-    tx: &rusqlite::Transaction,
-    feed_id: FeedId,
-    entries: &[IncomingEntry],
+pub fn update_entry_resolved_link(
+    conn: &rusqlite::Connection,
+    entry_id: EntryId,
+    resolved_link: &str,
 ) -> Result<()> {
-    if !entries.is_empty() {
-        let now = Utc::now();
+    conn.execute(
+        "UPDATE entries SET resolved_link = ?2 WHERE id = ?1",
+        params![entry_id, resolved_link],
+    )?;
 
-        let mut insert_statement = tx.prepare(
-            "INSERT INTO entries (feed_id, title, author, pub_date, description, content, link, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-        )?;
+    Ok(())
+}
 
-        // in most databases, doing this kind of "multiple inserts in a loop" thing would be bad and slow, but it's ok here because:
-        // 1. it is within single a transaction. in SQLite, doing many writes in the same transaction is actually fast
-        // 2. it is with single prepared statement, which further improves its write throughput
-        // see further: https://stackoverflow.com/questions/1711631/improve-insert-per-second-performance-of-sqlite
-        for entry in entries {
-            insert_statement.execute(params![
-                feed_id,
-                entry.title,
-                entry.author,
-                entry.pub_date,
-                entry.description,
-                entry.content,
-                entry.link,
-                now
-            ])?;
+/// A feed that fails to refresh this many times in a row is considered dead
+const DEAD_AFTER_CONSECUTIVE_FAILURES: u32 = 10;
+
+/// Refreshes the feed, keeping track of whether it has died.
+///
+/// A feed dies when it fails to refresh `DEAD_AFTER_CONSECUTIVE_FAILURES` times in a row,
+/// or immediately if its server says it is `410 Gone`.
+/// A dead feed that refreshes successfully comes back to life.
+/// A feed blocked by bot protection is marked with who blocked it until it refreshes again.
+/// How long every refresh takes goes in the fetch log, for `get_slowest_feeds`.
+pub fn refresh_feed(
+    client: &ureq::Agent,
+    conn: &mut rusqlite::Connection,
+    feed_id: FeedId,
+    ingest_options: &IngestOptions,
+) -> Result<()> {
+    let started_at = std::time::Instant::now();
+    let result = refresh_feed_entries(client, conn, feed_id, ingest_options);
+    record_feed_fetch(conn, feed_id, started_at.elapsed(), result.is_ok())?;
+
+    match &result {
+        Ok(()) => record_feed_refresh_success(conn, feed_id)?,
+        Err(e) => {
+            let is_gone = matches!(
+                e.downcast_ref::<FetchError>(),
+                Some(FetchError::Status { status: 410, .. })
+            );
+            let blocked_by = match e.downcast_ref::<FetchError>() {
+                Some(FetchError::BlockedByBotProtection { provider }) => Some(provider.as_str()),
+                _ => None,
+            };
+            record_feed_refresh_failure(conn, feed_id, is_gone, blocked_by)?;
+
+            // finding a suggestion is best-effort; the refresh error is what gets reported
+            let _ = suggest_feed_move_from_site(client, conn, feed_id, ingest_options);
         }
     }
 
-    Ok(())
+    result
 }
 
-pub fn get_feed(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<Feed> {
-    let s = conn.query_row(
-        "SELECT id, title, feed_link, link, feed_kind, refreshed_at, inserted_at, updated_at, latest_etag FROM feeds WHERE id=?1",
-        [feed_id],
-        |row| {
-            let feed_kind_str: String = row.get(4)?;
-            let feed_kind: FeedKind = FeedKind::from_str(&feed_kind_str)
-                .unwrap_or_else(|_| panic!("FeedKind must be Atom or RSS, got {feed_kind_str}"));
+/// Fetches the feed and counts the entries refreshing it would add,
+/// without storing anything, not even that the feed was fetched.
+pub fn preview_refresh_feed(
+    client: &ureq::Agent,
+    conn: &mut rusqlite::Connection,
+    feed_id: FeedId,
+    ingest_options: &IngestOptions,
+) -> Result<usize> {
+    let feed_url = get_feed_url(conn, feed_id)
+        .with_context(|| format!("Unable to get url for feed id {feed_id} from the database",))?;
 
-            Ok(Feed {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                feed_link: row.get(2)?,
-                link: row.get(3)?,
-                feed_kind,
-                refreshed_at: row.get(5)?,
-                inserted_at: row.get(6)?,
-                updated_at: row.get(7)?,
-                latest_etag: row.get(8)?,
-            })
-        },
-    )?;
+    let cache_validators = get_feed_cache_validators(conn, feed_id).with_context(|| {
+        format!(
+            "Unable to get latest_etag and last_modified for feed_id {feed_id} from the database"
+        )
+    })?;
 
-    Ok(s)
+    let http_settings = get_feed_http_settings(conn, feed_id)?;
+
+    let remote_feed = fetch_feed(
+        client,
+        &feed_url,
+        &cache_validators,
+        &http_settings,
+        ingest_options,
+    )
+    .with_context(|| format!("Failed to fetch feed {feed_url}"))?;
+
+    match remote_feed {
+        FeedResponse::CacheMiss(remote_feed) => {
+            let items_to_add = new_entries(conn, feed_id, remote_feed.entries, ingest_options)?;
+            let max_new_entries =
+                get_feed_max_new_entries(conn, feed_id)?.or(ingest_options.max_new_entries);
+
+            Ok(newest_entries(items_to_add, max_new_entries).0.len())
+        }
+        FeedResponse::CacheHit => Ok(0),
+    }
 }
 
-fn update_feed_refreshed_at(tx: &rusqlite::Transaction, feed_id: FeedId) -> Result<()> {
-    tx.execute(
-        "UPDATE feeds SET refreshed_at = ?2 WHERE id = ?1",
-        params![feed_id, Utc::now()],
-    )?;
+/// How many of each feed's most recent fetches the fetch log keeps
+const FETCH_LOG_LEN: usize = 20;
 
-    Ok(())
+/// How long a feed's recent fetches took, from the fetch log
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedFetchTimes {
+    pub feed_id: FeedId,
+    /// the feed's title, or its feed link if it has none
+    pub name: String,
+    pub fetches: usize,
+    pub failures: usize,
+    pub average: std::time::Duration,
+    pub slowest: std::time::Duration,
 }
 
-fn update_feed_etag(
-    tx: &rusqlite::Transaction,
+fn record_feed_fetch(
+    conn: &rusqlite::Connection,
     feed_id: FeedId,
-    latest_etag: Option<String>,
+    duration: std::time::Duration,
+    succeeded: bool,
 ) -> Result<()> {
-    tx.execute(
-        "UPDATE feeds SET latest_etag = ?2 WHERE id = ?1",
-        params![feed_id, latest_etag],
+    conn.execute(
+        "INSERT INTO feed_fetches (feed_id, fetched_at, duration_ms, succeeded) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            feed_id,
+            Utc::now(),
+            i64::try_from(duration.as_millis()).unwrap_or(i64::MAX),
+            succeeded
+        ],
+    )?;
+
+    conn.execute(
+        "DELETE FROM feed_fetches
+        WHERE feed_id = ?1
+        AND id NOT IN (
+          SELECT id FROM feed_fetches WHERE feed_id = ?1 ORDER BY id DESC LIMIT ?2
+        )",
+        params![feed_id, FETCH_LOG_LEN],
     )?;
 
     Ok(())
 }
 
-pub fn get_feed_url(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<String> {
-    let s: String = conn.query_row(
-        "SELECT feed_link FROM feeds WHERE id=?1",
-        [feed_id],
-        |row| row.get(0),
+/// The `limit` feeds whose recent fetches took the longest on average, slowest first
+pub fn get_slowest_feeds(conn: &rusqlite::Connection, limit: usize) -> Result<Vec<FeedFetchTimes>> {
+    let mut statement = conn.prepare(
+        "SELECT feeds.id, COALESCE(feeds.title, feeds.feed_link, ''), COUNT(*),
+          SUM(NOT feed_fetches.succeeded), AVG(feed_fetches.duration_ms), MAX(feed_fetches.duration_ms)
+        FROM feed_fetches
+        JOIN feeds ON feeds.id = feed_fetches.feed_id
+        GROUP BY feeds.id
+        ORDER BY AVG(feed_fetches.duration_ms) DESC
+        LIMIT ?1",
     )?;
 
-    Ok(s)
+    let slowest_feeds = statement
+        .query_map([limit], |row| {
+            let average_ms: f64 = row.get(4)?;
+
+            Ok(FeedFetchTimes {
+                feed_id: row.get(0)?,
+                name: row.get(1)?,
+                fetches: row.get(2)?,
+                failures: row.get(3)?,
+                average: std::time::Duration::from_millis(average_ms.round() as u64),
+                slowest: std::time::Duration::from_millis(row.get(5)?),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(slowest_feeds)
 }
 
-fn get_feed_latest_etag(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<Option<String>> {
-    let s: Option<String> = conn.query_row(
-        "SELECT latest_etag FROM feeds WHERE id=?1",
+fn record_feed_refresh_success(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds SET consecutive_failures = 0, dead_at = NULL, blocked_by = NULL WHERE id = ?1",
         [feed_id],
-        |row| {
-            let etag: Option<String> = row.get(0)?;
-            Ok(etag)
-        },
     )?;
 
-    Ok(s)
+    Ok(())
 }
 
-pub fn get_feeds(conn: &rusqlite::Connection) -> Result<Vec<Feed>> {
-    let mut statement = conn.prepare(
-        "SELECT 
-          id, 
-          title, 
-          feed_link, 
-          link, 
-          feed_kind, 
-          refreshed_at, 
-          inserted_at, 
-          updated_at,
-          latest_etag
-        FROM feeds ORDER BY lower(title) ASC",
+fn record_feed_refresh_failure(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    is_gone: bool,
+    blocked_by: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds
+        SET
+          consecutive_failures = consecutive_failures + 1,
+          dead_at = CASE
+            WHEN dead_at IS NULL AND (?2 OR consecutive_failures + 1 >= ?3) THEN ?4
+            ELSE dead_at
+          END,
+          blocked_by = ?5
+        WHERE id = ?1",
+        params![
+            feed_id,
+            is_gone,
+            DEAD_AFTER_CONSECUTIVE_FAILURES,
+            Utc::now(),
+            blocked_by
+        ],
     )?;
-    let mut feeds = vec![];
-    for feed in statement.query_map([], |row| {
-        Ok(Feed {
-            id: row.get(0)?,
-            title: row.get(1)?,
-            feed_link: row.get(2)?,
-            link: row.get(3)?,
-            feed_kind: row.get(4)?,
-            refreshed_at: row.get(5)?,
-            inserted_at: row.get(6)?,
-            updated_at: row.get(7)?,
-            latest_etag: row.get(8)?,
+
+    Ok(())
+}
+
+/// Tries to find a working feed for a (probably dead) feed
+/// from the feeds advertised on its site's homepage,
+/// and moves the feed there, keeping its existing entries.
+///
+/// Returns the feed's new feed link.
+pub fn rediscover_feed(
+    http_client: &ureq::Agent,
+    conn: &mut rusqlite::Connection,
+    feed_id: FeedId,
+    ingest_options: &IngestOptions,
+) -> Result<String> {
+    let feed = get_feed(conn, feed_id)?;
+
+    let Some(homepage) = feed.link else {
+        bail!("Feed has no site link to look for a new feed on");
+    };
+
+    let Some(feed_link) = find_working_feed_on_site(http_client, &homepage, None, ingest_options)?
+    else {
+        bail!("Found no working feed on {homepage}")
+    };
+
+    move_feed(conn, feed_id, &feed_link)?;
+
+    refresh_feed(http_client, conn, feed_id, ingest_options)?;
+
+    Ok(feed_link)
+}
+
+/// Where sites commonly put their feeds, tried when a feed link is not found
+const FALLBACK_FEED_PATHS: [&str; 4] = ["/feed", "/rss.xml", "/atom.xml", "/index.xml"];
+
+/// Whether `e` is the feed's server saying there is no feed there, with a 404 or 410
+pub fn is_feed_not_found(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<FetchError>(),
+        Some(FetchError::Status {
+            status: 404 | 410,
+            ..
         })
-    })? {
-        feeds.push(feed?)
+    )
+}
+
+/// Whether `e` is a fetch that got something other than a feed, like a web page
+fn is_not_a_feed(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<FetchError>(),
+        Some(FetchError::NotAFeed { .. })
+    )
+}
+
+/// The common feed locations on the same host as `feed_link`
+fn fallback_feed_links(feed_link: &str) -> Vec<String> {
+    let Ok(feed_url) = url::Url::parse(feed_link) else {
+        return vec![];
+    };
+
+    FALLBACK_FEED_PATHS
+        .iter()
+        .filter_map(|path| feed_url.join(path).ok())
+        .map(|url| url.to_string())
+        .filter(|candidate| candidate != feed_link)
+        .collect()
+}
+
+/// Returns the first of the common feed locations on `feed_link`'s host that can be fetched,
+/// for offering instead of a feed link that was not found
+pub fn find_fallback_feed_link(
+    http_client: &ureq::Agent,
+    feed_link: &str,
+    ingest_options: &IngestOptions,
+) -> Option<String> {
+    fallback_feed_links(feed_link)
+        .into_iter()
+        .find(|candidate| {
+            matches!(
+                fetch_feed(
+                    http_client,
+                    candidate,
+                    &CacheValidators::default(),
+                    &FeedHttpSettings::default(),
+                    ingest_options
+                ),
+                Ok(FeedResponse::CacheMiss(_))
+            )
+        })
+}
+
+/// Returns the first feed advertised on `homepage` that can actually be fetched,
+/// other than `current_feed_link`
+fn find_working_feed_on_site(
+    http_client: &ureq::Agent,
+    homepage: &str,
+    current_feed_link: Option<&str>,
+    ingest_options: &IngestOptions,
+) -> Result<Option<String>> {
+    let response = http_client
+        .get(homepage)
+        .call()
+        .with_context(|| format!("Failed to fetch {homepage}"))?;
+
+    let base_url = response.get_url().to_owned();
+    let html = response.into_string()?;
+
+    let working_feed_link = crate::discovery::discover_feed_links(&html, &base_url)
+        .into_iter()
+        .filter(|candidate| Some(candidate.as_str()) != current_feed_link)
+        .find(|candidate| {
+            matches!(
+                fetch_feed(
+                    http_client,
+                    candidate,
+                    &CacheValidators::default(),
+                    &FeedHttpSettings::default(),
+                    ingest_options
+                ),
+                Ok(FeedResponse::CacheMiss(_))
+            )
+        });
+
+    Ok(working_feed_link)
+}
+
+/// A feed that is redirected to the same place this many refreshes in a row
+/// is suggested to move there
+const SUGGEST_MOVE_AFTER_REDIRECTS: u32 = 3;
+
+/// A feed that fails to refresh this many times in a row has its site checked
+/// for a different feed to suggest moving to
+const SUGGEST_MOVE_AFTER_FAILURES: u32 = 3;
+
+/// Keeps track of where refreshing the feed was redirected to,
+/// suggesting moving the feed once it has consistently been redirected to the same place
+fn record_feed_redirect(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    redirected_to: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds
+        SET
+          redirect_count = CASE
+            WHEN ?2 IS NULL THEN 0
+            WHEN redirected_to = ?2 THEN redirect_count + 1
+            ELSE 1
+          END,
+          redirected_to = ?2
+        WHERE id = ?1",
+        params![feed_id, redirected_to],
+    )?;
+
+    if let Some(redirected_to) = redirected_to {
+        let redirect_count: u32 = conn.query_row(
+            "SELECT redirect_count FROM feeds WHERE id = ?1",
+            [feed_id],
+            |row| row.get(0),
+        )?;
+
+        if redirect_count >= SUGGEST_MOVE_AFTER_REDIRECTS {
+            suggest_feed_move(conn, feed_id, redirected_to)?;
+        }
     }
 
-    Ok(feeds)
+    Ok(())
 }
 
-pub fn get_feed_ids(conn: &rusqlite::Connection) -> Result<Vec<FeedId>> {
-    let mut statement = conn.prepare("SELECT id FROM feeds ORDER BY lower(title) ASC")?;
-    let mut ids = vec![];
-    for id in statement.query_map([], |row| row.get(0))? {
-        ids.push(id?)
+/// Looks on the site of a feed that has started failing to refresh
+/// for a different feed that works, and suggests moving the feed there
+fn suggest_feed_move_from_site(
+    http_client: &ureq::Agent,
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    ingest_options: &IngestOptions,
+) -> Result<()> {
+    let feed = get_feed(conn, feed_id)?;
+
+    // only look once per run of failures
+    if feed.consecutive_failures != SUGGEST_MOVE_AFTER_FAILURES
+        || feed.suggested_feed_link.is_some()
+    {
+        return Ok(());
     }
 
-    Ok(ids)
+    let Some(homepage) = feed.link else {
+        return Ok(());
+    };
+
+    if let Some(feed_link) = find_working_feed_on_site(
+        http_client,
+        &homepage,
+        feed.feed_link.as_deref(),
+        ingest_options,
+    )? {
+        suggest_feed_move(conn, feed_id, &feed_link)?;
+    }
+
+    Ok(())
 }
 
-pub fn get_entry_meta(conn: &rusqlite::Connection, entry_id: EntryId) -> Result<EntryMetadata> {
-    let result = conn.query_row(
-        "SELECT 
-          id, 
-          feed_id, 
-          title, 
-          author, 
-          pub_date, 
-          link, 
-          read_at, 
-          inserted_at, 
-          updated_at 
-        FROM entries WHERE id=?1",
-        [entry_id],
-        |row| {
-            Ok(EntryMetadata {
-                id: row.get(0)?,
-                feed_id: row.get(1)?,
-                title: row.get(2)?,
-                author: row.get(3)?,
-                pub_date: row.get(4)?,
-                link: row.get(5)?,
-                read_at: row.get(6)?,
-                inserted_at: row.get(7)?,
-                updated_at: row.get(8)?,
-            })
-        },
+/// Suggests moving the feed to `feed_link`, unless the user has already turned that move down
+fn suggest_feed_move(conn: &rusqlite::Connection, feed_id: FeedId, feed_link: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds
+        SET suggested_feed_link = ?2
+        WHERE id = ?1
+        AND ?2 IS NOT feed_link
+        AND ?2 IS NOT dismissed_feed_link",
+        params![feed_id, feed_link],
     )?;
 
-    Ok(result)
+    Ok(())
 }
 
-pub fn get_entry_content(conn: &rusqlite::Connection, entry_id: EntryId) -> Result<EntryContent> {
-    let result = conn.query_row(
-        "SELECT content, description FROM entries WHERE id=?1",
-        [entry_id],
-        |row| {
-            Ok(EntryContent {
-                content: row.get(0)?,
-                description: row.get(1)?,
-            })
-        },
+/// Points the feed at a new feed link.
+/// The feed keeps its id, so its existing entries and their read state stay with it.
+pub fn move_feed(conn: &rusqlite::Connection, feed_id: FeedId, feed_link: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds
+        SET
+          feed_link = ?2,
+          latest_etag = NULL,
+          last_modified = NULL,
+          consecutive_failures = 0,
+          dead_at = NULL,
+          redirected_to = NULL,
+          redirect_count = 0,
+          suggested_feed_link = NULL,
+          source = ?3
+        WHERE id = ?1",
+        params![feed_id, feed_link, FeedSource::of_feed_link(feed_link)],
     )?;
 
-    Ok(result)
+    Ok(())
 }
 
-pub fn get_entries_metas(
-    conn: &rusqlite::Connection,
-    read_mode: &ReadMode,
+/// Turns down the feed's suggested move, so the same move is not suggested again
+pub fn dismiss_feed_move(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds
+        SET dismissed_feed_link = suggested_feed_link, suggested_feed_link = NULL
+        WHERE id = ?1",
+        [feed_id],
+    )?;
+
+    Ok(())
+}
+
+/// fetches the feed and stores the new entries
+/// uses the link as the uniqueness key.
+/// TODO hash the content to see if anything changed, and update that way.
+fn refresh_feed_entries(
+    client: &ureq::Agent,
+    conn: &mut rusqlite::Connection,
     feed_id: FeedId,
-) -> Result<Vec<EntryMetadata>> {
-    let read_at_predicate = match read_mode {
-        ReadMode::ShowUnread => "\nAND read_at IS NULL",
-        ReadMode::ShowRead => "\nAND read_at IS NOT NULL",
-        ReadMode::All => "\n",
+    ingest_options: &IngestOptions,
+) -> Result<()> {
+    let feed_url = get_feed_url(conn, feed_id)
+        .with_context(|| format!("Unable to get url for feed id {feed_id} from the database",))?;
+
+    let cache_validators = get_feed_cache_validators(conn, feed_id).with_context(|| {
+        format!(
+            "Unable to get latest_etag and last_modified for feed_id {feed_id} from the database"
+        )
+    })?;
+
+    let http_settings = get_feed_http_settings(conn, feed_id)?;
+
+    let remote_feed = fetch_feed(
+        client,
+        &feed_url,
+        &cache_validators,
+        &http_settings,
+        ingest_options,
+    )
+    .with_context(|| format!("Failed to fetch feed {feed_url}"))?;
+
+    if let FeedResponse::CacheMiss(remote_feed) = remote_feed {
+        let mut warnings = remote_feed.warnings;
+        let remote_links = remote_feed
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.link)
+            .cloned()
+            .collect::<HashSet<_>>();
+        let items_to_add = new_entries(conn, feed_id, remote_feed.entries, ingest_options)?;
+        let max_new_entries =
+            get_feed_max_new_entries(conn, feed_id)?.or(ingest_options.max_new_entries);
+        let (mut items_to_add, skipped_entries) = newest_entries(items_to_add, max_new_entries);
+
+        if ingest_options.resolve_redirects {
+            let unresolved = resolve_entry_links(client, &mut items_to_add, ingest_options);
+            if unresolved > 0 {
+                warnings.push(format!(
+                    "{unresolved} entry links could not be followed to where they redirect, and are kept as they are"
+                ));
+            }
+        }
+
+        let entry_ids = in_transaction(conn, |tx| {
+            let entry_ids = add_entries_to_feed(tx, feed_id, &items_to_add, ingest_options)?;
+            update_feed_refreshed_at(tx, feed_id)?;
+            update_feed_skipped_entries(tx, feed_id, skipped_entries)?;
+            update_feed_refresh_warnings(tx, feed_id, &warnings)?;
+            mark_old_entries_read(tx, feed_id)?;
+            prune_entries(tx, feed_id, &remote_links, ingest_options)?;
+            update_feed_cache_validators(tx, feed_id, &remote_feed.feed)?;
+            update_feed_hub_link(tx, feed_id, remote_feed.feed.hub_link.clone())?;
+            update_feed_metadata(tx, feed_id, &remote_feed.feed)?;
+            record_feed_redirect(tx, feed_id, remote_feed.feed.redirected_to.as_deref())?;
+            Ok(entry_ids)
+        })?;
+
+        cache_entries_assets(client, conn, &entry_ids, &items_to_add, ingest_options);
+    } else {
+        in_transaction(conn, |tx| {
+            update_feed_refreshed_at(tx, feed_id)?;
+            update_feed_skipped_entries(tx, feed_id, 0)?;
+            update_feed_refresh_warnings(tx, feed_id, &[])?;
+            mark_old_entries_read(tx, feed_id)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// The newest `max_new_entries` of `entries`, if there is a limit,
+/// and how many were left out.
+/// Entries without a publication date keep their place in the feed, after the dated ones
+fn newest_entries(
+    mut entries: Vec<IncomingEntry>,
+    max_new_entries: Option<usize>,
+) -> (Vec<IncomingEntry>, usize) {
+    let Some(max_new_entries) = max_new_entries else {
+        return (entries, 0);
     };
 
-    // we get weird pubDate formats from feeds,
-    // so sort by inserted at as this as a stable order at least
-    let mut query = "SELECT 
-        id, 
-        feed_id, 
-        title, 
-        author, 
-        pub_date, 
-        link, 
-        read_at, 
-        inserted_at, 
-        updated_at 
-        FROM entries 
-        WHERE feed_id=?1"
-        .to_string();
-
-    query.push_str(read_at_predicate);
-    query.push_str("\nORDER BY pub_date DESC, inserted_at DESC");
-
-    let mut statement = conn.prepare(&query)?;
-    let mut entries = vec![];
-    for entry in statement.query_map([feed_id], |row| {
-        Ok(EntryMetadata {
-            id: row.get(0)?,
-            feed_id: row.get(1)?,
-            title: row.get(2)?,
-            author: row.get(3)?,
-            pub_date: row.get(4)?,
-            link: row.get(5)?,
-            read_at: row.get(6)?,
-            inserted_at: row.get(7)?,
-            updated_at: row.get(8)?,
-        })
-    })? {
-        entries.push(entry?)
+    if entries.len() <= max_new_entries {
+        return (entries, 0);
     }
 
-    Ok(entries)
+    // a stable sort, and `None` sorts before any date, so reversed, undated entries go last
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.pub_date));
+    let skipped_entries = entries.len() - max_new_entries;
+    entries.truncate(max_new_entries);
+
+    (entries, skipped_entries)
 }
 
-pub fn get_entries_links(
+/// The entries of `remote_items` that the feed does not have yet, by link
+fn new_entries(
     conn: &rusqlite::Connection,
-    read_mode: &ReadMode,
     feed_id: FeedId,
-) -> Result<Vec<Option<String>>> {
-    let read_at_predicate = match read_mode {
-        ReadMode::ShowUnread => "\nAND read_at IS NULL",
-        ReadMode::ShowRead => "\nAND read_at IS NOT NULL",
-        ReadMode::All => "\n",
+    remote_items: Vec<IncomingEntry>,
+    ingest_options: &IngestOptions,
+) -> Result<Vec<IncomingEntry>> {
+    let remote_items_links = remote_items
+        .iter()
+        .flat_map(|item| &item.link)
+        .cloned()
+        .collect::<HashSet<String>>();
+
+    // entries stored before a tracking parameter was configured
+    // should still match their cleaned-up remote counterparts
+    let local_entries_links = get_entries_links(conn, &ReadMode::All, feed_id)?
+        .into_iter()
+        .flatten()
+        .map(|link| {
+            crate::util::strip_tracking_parameters(&link, &ingest_options.tracking_parameters)
+        })
+        .collect::<HashSet<_>>();
+
+    let difference = remote_items_links
+        .difference(&local_entries_links)
+        .cloned()
+        .collect::<HashSet<_>>();
+
+    let items_to_add = remote_items
+        .into_iter()
+        .filter(|item| match &item.link {
+            Some(link) => difference.contains(link.as_str()),
+            None => false,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(items_to_add)
+}
+
+/// Stores the entries of a feed document fetched by something other than russ,
+/// like a script, adding only the entries the feed does not have yet, as a refresh does.
+/// The feed is created if there is no feed with `feed_link` yet.
+///
+/// Returns how many entries were added.
+pub fn ingest_feed(
+    http_client: &ureq::Agent,
+    conn: &mut rusqlite::Connection,
+    feed_link: &str,
+    content: &str,
+    ingest_options: &IngestOptions,
+) -> Result<usize> {
+    let mut feed_and_entries = FeedAndEntries::from_str(content)?;
+    feed_and_entries.set_feed_link(feed_link);
+    feed_and_entries.resolve_links(feed_link);
+    feed_and_entries.clean_entries(ingest_options);
+
+    let existing_feed_id = conn
+        .query_row(
+            "SELECT id FROM feeds WHERE feed_link = ?1",
+            [feed_link],
+            |row| row.get::<_, FeedId>(0),
+        )
+        .optional()?;
+
+    let mut items_to_add = match existing_feed_id {
+        Some(feed_id) => new_entries(conn, feed_id, feed_and_entries.entries, ingest_options)?,
+        None => feed_and_entries.entries,
     };
 
-    // we get weird pubDate formats from feeds,
-    // so sort by inserted at as this as a stable order at least
-    let mut query = "SELECT link FROM entries WHERE feed_id=?1".to_string();
+    if ingest_options.resolve_redirects {
+        resolve_entry_links(http_client, &mut items_to_add, ingest_options);
+    }
+
+    let entry_ids = in_transaction(conn, |tx| {
+        let feed_id = match existing_feed_id {
+            Some(feed_id) => feed_id,
+            None => create_feed(tx, &feed_and_entries.feed)?,
+        };
+        let entry_ids = add_entries_to_feed(tx, feed_id, &items_to_add, ingest_options)?;
+        update_feed_refreshed_at(tx, feed_id)?;
+        Ok(entry_ids)
+    })?;
 
-    query.push_str(read_at_predicate);
-    query.push_str("\nORDER BY pub_date DESC, inserted_at DESC");
+    cache_entries_assets(http_client, conn, &entry_ids, &items_to_add, ingest_options);
 
-    let mut links = vec![];
-    let mut statement = conn.prepare(&query)?;
+    Ok(items_to_add.len())
+}
 
-    for link in statement.query_map([feed_id], |row| row.get(0))? {
-        links.push(link?);
-    }
+/// Creates or migrates the database schema,
+/// and sets up `conn` the way every connection to the database should be
+pub fn initialize_db(conn: &mut rusqlite::Connection) -> Result<()> {
+    // immediate, so that when two connections set up a new database at once,
+    // like the reader and its refresh thread's pool, or `russ refresh` from cron,
+    // the second waits for the first's migrations and then finds nothing left to do,
+    // instead of both reading the old version and one failing to write
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+    migrations::migrate(&tx)?;
+    tx.commit()?;
 
-    Ok(links)
+    // this has to be outside of a transaction to take effect,
+    // and after the migrations, which rebuild tables
+    enable_foreign_keys(conn)?;
+
+    Ok(())
 }
 
-/// run `f` in a transaction, committing if `f` returns an `Ok` value,
-/// otherwise rolling back.
-fn in_transaction<F, R>(conn: &mut rusqlite::Connection, f: F) -> Result<R>
-where
-    F: Fn(&rusqlite::Transaction) -> Result<R>,
-{
-    let tx = conn.transaction()?;
+/// SQLite only enforces foreign keys, like deleting a feed's entries along with it,
+/// on connections that ask for it
+fn enable_foreign_keys(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.pragma_update(None, "foreign_keys", true)
+}
 
-    let result = f(&tx)?;
+/// Applies `database_options` to `conn`.
+/// Call this before `initialize_db`, so `page_size` applies to new databases.
+pub fn tune_connection(
+    conn: &rusqlite::Connection,
+    database_options: &DatabaseOptions,
+) -> rusqlite::Result<()> {
+    if let Some(page_size) = database_options.page_size {
+        conn.pragma_update(None, "page_size", page_size)?;
+    }
 
-    tx.commit()?;
+    // negative sizes are in KiB rather than pages, so they mean the same thing whatever the page size
+    conn.pragma_update(
+        None,
+        "cache_size",
+        -i64::from(database_options.cache_size_kib),
+    )?;
+    // this one reports the size it settled on, if any, which may be capped by how sqlite was compiled
+    conn.pragma(None, "mmap_size", database_options.mmap_size, |_| Ok(()))?;
+    conn.pragma_update(None, "synchronous", database_options.synchronous.as_str())?;
 
-    Ok(result)
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const ZCT: &str = "https://zeroclarkthirty.com/feed";
+/// Switches the database to write-ahead logging,
+/// so reading does not wait on writes being committed, nor writing on reads.
+/// This is stored in the database file, so it lasts for every later connection too.
+pub fn enable_wal(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    // in-memory databases cannot use a WAL, and say "memory" instead
+    conn.pragma(None, "journal_mode", "WAL", |_| Ok(()))
+}
 
-    #[test]
-    fn it_fetches() {
-        let http_client = ureq::AgentBuilder::new()
-            .timeout_read(std::time::Duration::from_secs(5))
-            .build();
-        let feed_and_entries = fetch_feed(&http_client, ZCT, None).unwrap();
-        if let FeedResponse::CacheMiss(feed_and_entries) = feed_and_entries {
-            assert!(!feed_and_entries.entries.is_empty())
-        } else {
-            panic!("somehow got a cached response when passing no etag")
-        }
+/// The `--database-path` for a database that lasts only as long as russ is running
+pub const IN_MEMORY_DATABASE_PATH: &str = ":memory:";
+
+pub fn is_in_memory(database_path: &std::path::Path) -> bool {
+    database_path == std::path::Path::new(IN_MEMORY_DATABASE_PATH)
+}
+
+/// Opens the database at `database_path`.
+/// Every connection to `:memory:` gets an empty database of its own,
+/// so for the reader, its io thread, and its connection pool to share one,
+/// it is opened as an in-memory database in a shared cache, named for this process.
+/// That database lasts as long as any connection to it is open.
+pub fn open(
+    database_path: &std::path::Path,
+    flags: rusqlite::OpenFlags,
+) -> rusqlite::Result<rusqlite::Connection> {
+    if is_in_memory(database_path) {
+        rusqlite::Connection::open_with_flags(
+            shared_in_memory_database_uri(),
+            flags | rusqlite::OpenFlags::SQLITE_OPEN_URI,
+        )
+    } else {
+        rusqlite::Connection::open_with_flags(database_path, flags)
     }
+}
 
-    #[test]
-    fn it_subscribes_to_a_feed() {
-        let http_client = ureq::AgentBuilder::new()
-            .timeout_read(std::time::Duration::from_secs(5))
-            .build();
-        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
-        initialize_db(&mut conn).unwrap();
-        subscribe_to_feed(&http_client, &mut conn, ZCT).unwrap();
-        let count: i64 = conn
-            .query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))
-            .unwrap();
+fn shared_in_memory_database_uri() -> String {
+    format!("file:russ-{}?mode=memory&cache=shared", std::process::id())
+}
 
-        assert!(count > 50)
+/// A connection for the reader's queries alone, which cannot write.
+/// With `enable_wal`, its reads see what was last committed, without waiting on writers,
+/// so the UI does not stall while a big refresh commits.
+pub fn open_read_only(
+    database_path: &std::path::Path,
+    database_options: &DatabaseOptions,
+) -> Result<rusqlite::Connection> {
+    let conn = open(
+        database_path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY
+            | rusqlite::OpenFlags::SQLITE_OPEN_URI
+            | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("Unable to open {} to read", database_path.display()))?;
+
+    // in a shared cache, reads wait on tables being written rather than on commits,
+    // so this reads what is there without waiting, like a WAL reader would
+    if is_in_memory(database_path) {
+        conn.pragma_update(None, "read_uncommitted", true)?;
     }
 
-    #[test]
-    fn refresh_feed_does_not_add_any_items_if_there_are_no_new_items() {
-        let http_client = ureq::AgentBuilder::new()
-            .timeout_read(std::time::Duration::from_secs(5))
-            .build();
-        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
-        initialize_db(&mut conn).unwrap();
-        subscribe_to_feed(&http_client, &mut conn, ZCT).unwrap();
-        let feed_id = 1.into();
-        let old_entries = get_entries_metas(&conn, &ReadMode::ShowUnread, feed_id).unwrap();
-        refresh_feed(&http_client, &mut conn, feed_id).unwrap();
-        let e = get_entry_meta(&conn, 1.into()).unwrap();
-        e.mark_as_read(&conn).unwrap();
-        let new_entries = get_entries_metas(&conn, &ReadMode::ShowUnread, feed_id).unwrap();
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+    // `page_size` is only for creating databases, which this connection cannot do
+    tune_connection(
+        &conn,
+        &DatabaseOptions {
+            page_size: None,
+            ..database_options.clone()
+        },
+    )?;
 
-        assert_eq!(new_entries.len(), old_entries.len() - 1);
+    Ok(conn)
+}
+
+/// For connection pools, whose connections need the same setup as `initialize_db`
+/// and `tune_connection` give
+pub fn connection_manager(
+    database_path: &std::path::Path,
+    database_options: &DatabaseOptions,
+) -> r2d2_sqlite::SqliteConnectionManager {
+    let database_options = database_options.clone();
+
+    // rusqlite's default flags, which the manager opens with, allow URIs
+    let manager = if is_in_memory(database_path) {
+        r2d2_sqlite::SqliteConnectionManager::file(shared_in_memory_database_uri())
+    } else {
+        r2d2_sqlite::SqliteConnectionManager::file(database_path)
+    };
+
+    manager.with_init(move |conn| {
+        tune_connection(conn, &database_options)?;
+        enable_foreign_keys(conn)
+    })
+}
+
+fn create_feed(tx: &rusqlite::Transaction, feed: &IncomingFeed) -> Result<FeedId> {
+    let feed_id = tx.query_row::<FeedId, _, _>(
+        "INSERT INTO feeds (title, link, feed_link, feed_kind, hub_link, source, description, language, last_build_date)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        RETURNING id",
+        params![
+            feed.title,
+            feed.link,
+            feed.feed_link,
+            feed.feed_kind,
+            feed.hub_link,
+            feed.feed_link
+                .as_deref()
+                .map(FeedSource::of_feed_link)
+                .unwrap_or(FeedSource::Http),
+            feed.description,
+            feed.language,
+            feed.last_build_date
+        ],
+        |r| r.get(0),
+    )?;
+
+    Ok(feed_id)
+}
+
+pub fn delete_feed(conn: &mut rusqlite::Connection, feed_id: FeedId) -> Result<()> {
+    in_transaction(conn, |tx| {
+        // remember what was read, for if the feed is subscribed to again
+        tx.execute(
+            "INSERT INTO entry_tombstones (feed_link, link, guid, read_at)
+            SELECT feeds.feed_link, entries.link, entries.guid, entries.read_at
+            FROM entries
+            JOIN feeds ON feeds.id = entries.feed_id
+            WHERE feeds.id = ?1
+            AND feeds.feed_link IS NOT NULL
+            AND entries.read_at IS NOT NULL",
+            [feed_id],
+        )?;
+
+        // its entries are deleted along with it by the foreign key
+        tx.execute("DELETE FROM feeds WHERE id = ?1", [feed_id])?;
+        Ok(())
+    })
+}
+
+/// Marks the feed's entries that were read before it was deleted as read again,
+/// matching them by guid or link, so resubscribing does not bring them all back unread
+fn restore_read_entries(tx: &rusqlite::Transaction, feed_id: FeedId) -> Result<()> {
+    let mut statement = tx.prepare_cached(
+        "SELECT entry_tombstones.id, entry_tombstones.link, entry_tombstones.guid, entry_tombstones.read_at
+        FROM entry_tombstones
+        JOIN feeds ON feeds.feed_link = entry_tombstones.feed_link
+        WHERE feeds.id = ?1",
+    )?;
+
+    let tombstones = statement
+        .query_map([feed_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, DateTime<Utc>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (tombstone_id, link, guid, read_at) in tombstones {
+        let restored = tx.execute(
+            "UPDATE entries SET read_at = ?2
+            WHERE feed_id = ?1
+            AND read_at IS NULL
+            AND (guid = ?3 OR link = ?4)",
+            params![feed_id, read_at, guid, link],
+        )?;
+
+        if restored > 0 {
+            tx.execute("DELETE FROM entry_tombstones WHERE id = ?1", [tombstone_id])?;
+        }
+    }
+
+    Ok(())
+}
+
+fn add_entries_to_feed(
+    tx: &rusqlite::Transaction,
+    feed_id: FeedId,
+    entries: &[IncomingEntry],
+    ingest_options: &IngestOptions,
+) -> Result<Vec<EntryId>> {
+    let mut entry_ids = Vec::with_capacity(entries.len());
+
+    if !entries.is_empty() {
+        let now = Utc::now();
+
+        let mut insert_statement = tx.prepare(
+            "INSERT INTO entries (feed_id, title, author, pub_date, claimed_pub_date, link, resolved_link, guid, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )?;
+
+        let mut insert_content_statement = tx.prepare(
+            "INSERT INTO entry_contents (entry_id, content, description) VALUES (?, ?, ?)",
+        )?;
+
+        // in most databases, doing this kind of "multiple inserts in a loop" thing would be bad and slow, but it's ok here because:
+        // 1. it is within single a transaction. in SQLite, doing many writes in the same transaction is actually fast
+        // 2. it is with single prepared statement, which further improves its write throughput
+        // see further: https://stackoverflow.com/questions/1711631/improve-insert-per-second-performance-of-sqlite
+        for entry in entries {
+            let (pub_date, claimed_pub_date) = match entry.pub_date {
+                Some(pub_date)
+                    if ingest_options.clamp_future_dates && is_future_date(pub_date, now) =>
+                {
+                    (Some(now), Some(pub_date))
+                }
+                pub_date => (pub_date, None),
+            };
+
+            insert_statement.execute(params![
+                feed_id,
+                entry.title,
+                entry.author,
+                pub_date,
+                claimed_pub_date,
+                entry.link,
+                entry.resolved_link,
+                entry.guid,
+                now
+            ])?;
+
+            let entry_id = EntryId::from(tx.last_insert_rowid());
+
+            insert_content_statement.execute(params![
+                entry_id,
+                stored_text(entry.content.clone(), ingest_options),
+                stored_text(entry.description.clone(), ingest_options),
+            ])?;
+
+            search::index_entry(
+                tx,
+                entry_id,
+                entry.title.as_deref(),
+                entry.content.as_deref().or(entry.description.as_deref()),
+            )?;
+
+            entry_ids.push(entry_id);
+        }
+
+        restore_read_entries(tx, feed_id)?;
+    }
+
+    Ok(entry_ids)
+}
+
+/// Downloads the images of newly stored entries, if asset caching is on,
+/// then prunes the cache back down to its size.
+/// `entry_ids` are the ids `entries` were stored with.
+fn cache_entries_assets(
+    http_client: &ureq::Agent,
+    conn: &rusqlite::Connection,
+    entry_ids: &[EntryId],
+    entries: &[IncomingEntry],
+    ingest_options: &IngestOptions,
+) {
+    let Some(asset_cache) = &ingest_options.asset_cache else {
+        return;
+    };
+
+    if entry_ids.is_empty() {
+        return;
+    }
+
+    for (entry_id, entry) in entry_ids.iter().zip(entries) {
+        for html in [&entry.content, &entry.description].into_iter().flatten() {
+            crate::assets::cache_entry_assets(
+                http_client,
+                asset_cache,
+                *entry_id,
+                html,
+                entry.link.as_deref(),
+            );
+        }
+    }
+
+    // the entries are stored either way, and the cache is pruned again next time
+    let _ = crate::assets::prune(conn, asset_cache);
+}
+
+fn stored_text(text: Option<String>, ingest_options: &IngestOptions) -> Option<StoredText> {
+    text.map(|text| StoredText::new(text, ingest_options.compress_content))
+}
+
+/// Compresses, or with `compress` unset decompresses,
+/// the content, description, and fetched full content of every entry that is not already that way.
+/// Returns the number of entries changed.
+pub fn recompress_entries(conn: &mut rusqlite::Connection, compress: bool) -> Result<usize> {
+    in_transaction(conn, |tx| {
+        let mut select_statement =
+            tx.prepare("SELECT entry_id, content, description, full_content FROM entry_contents")?;
+
+        let mut update_statement = tx.prepare(
+            "UPDATE entry_contents
+            SET content = ?2, description = ?3, full_content = ?4
+            WHERE entry_id = ?1",
+        )?;
+
+        let mut rows = select_statement.query([])?;
+        let mut changed = 0;
+
+        while let Some(row) = rows.next()? {
+            let entry_id: EntryId = row.get(0)?;
+            let content: Option<StoredText> = row.get(1)?;
+            let description: Option<StoredText> = row.get(2)?;
+            let full_content: Option<StoredText> = row.get(3)?;
+
+            let mut changed_this_entry = false;
+
+            let mut restore = |stored: Option<StoredText>| -> Result<Option<StoredText>> {
+                let Some(stored) = stored else {
+                    return Ok(None);
+                };
+
+                if stored.is_compressed() == compress {
+                    return Ok(Some(stored));
+                }
+
+                // text too short to get smaller stays as it is
+                let restored = StoredText::new(stored.into_string()?, compress);
+                changed_this_entry |= restored.is_compressed() == compress;
+                Ok(Some(restored))
+            };
+
+            let content = restore(content)?;
+            let description = restore(description)?;
+            let full_content = restore(full_content)?;
+
+            if !changed_this_entry {
+                continue;
+            }
+
+            update_statement.execute(params![entry_id, content, description, full_content])?;
+
+            changed += 1;
+        }
+
+        Ok(changed)
+    })
+}
+
+/// Imports a feed and its entries from another feed reader, without fetching anything.
+///
+/// If a feed with `feed_link` already exists, entries are merged into it.
+/// Entries are deduplicated by link, and entries that already exist
+/// pick up the imported read and starred state if they do not have their own.
+///
+/// Returns the number of entries that were inserted.
+pub(crate) fn import_feed(
+    conn: &mut rusqlite::Connection,
+    title: Option<&str>,
+    feed_link: &str,
+    link: Option<&str>,
+    entries: &[ImportedEntry],
+    ingest_options: &IngestOptions,
+) -> Result<usize> {
+    in_transaction(conn, |tx| {
+        let existing_feed_id = tx
+            .query_row(
+                "SELECT id FROM feeds WHERE feed_link = ?1",
+                [feed_link],
+                |row| row.get::<_, FeedId>(0),
+            )
+            .optional()?;
+
+        let feed_id = match existing_feed_id {
+            Some(feed_id) => feed_id,
+            None => create_feed(
+                tx,
+                &IncomingFeed {
+                    title: title.map(|title| title.to_owned()),
+                    feed_link: Some(feed_link.to_owned()),
+                    link: link.map(|link| link.to_owned()),
+                    // other readers do not tell us what kind of feed this is.
+                    // it does not matter much, as the kind is only displayed
+                    feed_kind: FeedKind::Rss,
+                    latest_etag: None,
+                    last_modified: None,
+                    next_page_link: None,
+                    hub_link: None,
+                    redirected_to: None,
+                    description: None,
+                    language: None,
+                    last_build_date: None,
+                },
+            )?,
+        };
+
+        // entries without links are told apart by their titles and dates instead
+        let mut insert_statement = tx.prepare(
+            "INSERT INTO entries (feed_id, title, author, pub_date, link, read_at, starred_at, updated_at)
+            SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8
+            WHERE NOT EXISTS (
+                SELECT 1 FROM entries
+                WHERE feed_id = ?1
+                AND link IS ?5
+                AND (?5 IS NOT NULL OR (title IS ?2 AND pub_date IS ?4))
+            )",
+        )?;
+
+        let mut insert_content_statement =
+            tx.prepare("INSERT INTO entry_contents (entry_id, content) VALUES (?1, ?2)")?;
+
+        let mut update_statement = tx.prepare(
+            "UPDATE entries
+            SET read_at = COALESCE(read_at, ?3), starred_at = COALESCE(starred_at, ?4)
+            WHERE feed_id = ?1
+            AND link IS ?2
+            AND (?2 IS NOT NULL OR (title IS ?5 AND pub_date IS ?6))",
+        )?;
+
+        let now = Utc::now();
+        let mut inserted = 0;
+
+        for entry in entries {
+            let link = entry.link.as_deref().map(|link| {
+                crate::util::strip_tracking_parameters(link, &ingest_options.tracking_parameters)
+            });
+
+            let content = entry.content.as_deref().map(|content| {
+                crate::util::strip_html_elements(content, &ingest_options.strip_elements)
+            });
+
+            let inserted_this_entry = insert_statement.execute(params![
+                feed_id,
+                entry.title,
+                entry.author,
+                entry.pub_date,
+                link,
+                entry.read_at,
+                entry.starred_at,
+                now
+            ])?;
+
+            if inserted_this_entry > 0 {
+                let entry_id = EntryId::from(tx.last_insert_rowid());
+                search::index_entry(tx, entry_id, entry.title.as_deref(), content.as_deref())?;
+                insert_content_statement
+                    .execute(params![entry_id, stored_text(content, ingest_options)])?;
+                inserted += inserted_this_entry;
+            } else {
+                update_statement.execute(params![
+                    feed_id,
+                    link,
+                    entry.read_at,
+                    entry.starred_at,
+                    entry.title,
+                    entry.pub_date
+                ])?;
+            }
+        }
+
+        restore_read_entries(tx, feed_id)?;
+
+        Ok(inserted)
+    })
+}
+
+pub fn get_feed(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<Feed> {
+    let s = conn.prepare_cached(
+        "SELECT id, title, feed_link, link, feed_kind, refreshed_at, inserted_at, updated_at, latest_etag, hub_link, last_viewed_at, consecutive_failures, dead_at, suggested_feed_link, source, max_new_entries, skipped_entries, mark_read_after_days, description, language, last_build_date, folder, blocked_by, refresh_warnings, retention, entry_sort, custom_title FROM feeds WHERE id=?1",
+    )?.query_row([feed_id], |row| {
+            let feed_kind_str: String = row.get(4)?;
+            let feed_kind: FeedKind = FeedKind::from_str(&feed_kind_str)
+                .unwrap_or_else(|_| panic!("FeedKind must be Atom or RSS, got {feed_kind_str}"));
+
+            Ok(Feed {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                feed_link: row.get(2)?,
+                link: row.get(3)?,
+                feed_kind,
+                refreshed_at: row.get(5)?,
+                inserted_at: row.get(6)?,
+                updated_at: row.get(7)?,
+                latest_etag: row.get(8)?,
+                hub_link: row.get(9)?,
+                last_viewed_at: row.get(10)?,
+                consecutive_failures: row.get(11)?,
+                dead_at: row.get(12)?,
+                suggested_feed_link: row.get(13)?,
+                source: row.get(14)?,
+                max_new_entries: row.get(15)?,
+                skipped_entries: row.get(16)?,
+                mark_read_after_days: row.get(17)?,
+                description: row.get(18)?,
+                language: row.get(19)?,
+                last_build_date: row.get(20)?,
+                folder: row.get(21)?,
+                blocked_by: row.get(22)?,
+                refresh_warnings: refresh_warnings_from_sql(row.get(23)?),
+                retention: retention_from_sql(row.get(24)?),
+                entry_sort: row.get(25)?,
+                custom_title: row.get(26)?,
+            })
+        })?;
+
+    Ok(s)
+}
+
+fn update_feed_refreshed_at(tx: &rusqlite::Transaction, feed_id: FeedId) -> Result<()> {
+    tx.execute(
+        "UPDATE feeds SET refreshed_at = ?2 WHERE id = ?1",
+        params![feed_id, Utc::now()],
+    )?;
+
+    Ok(())
+}
+
+fn update_feed_refresh_warnings(
+    tx: &rusqlite::Transaction,
+    feed_id: FeedId,
+    warnings: &[String],
+) -> Result<()> {
+    // one warning per line
+    tx.execute(
+        "UPDATE feeds SET refresh_warnings = ?2 WHERE id = ?1",
+        params![
+            feed_id,
+            Some(warnings.join("\n")).filter(|warnings| !warnings.is_empty())
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Retentions that can no longer be read, like ones written by a later version, are ignored
+fn retention_from_sql(retention: Option<String>) -> Option<Retention> {
+    retention.and_then(|retention| retention.parse().ok())
+}
+
+/// The warnings stored one per line by `update_feed_refresh_warnings`
+fn refresh_warnings_from_sql(warnings: Option<String>) -> Vec<String> {
+    warnings
+        .map(|warnings| warnings.lines().map(str::to_owned).collect())
+        .unwrap_or_default()
+}
+
+fn update_feed_skipped_entries(
+    tx: &rusqlite::Transaction,
+    feed_id: FeedId,
+    skipped_entries: usize,
+) -> Result<()> {
+    tx.execute(
+        "UPDATE feeds SET skipped_entries = ?2 WHERE id = ?1",
+        params![feed_id, skipped_entries],
+    )?;
+
+    Ok(())
+}
+
+fn get_feed_max_new_entries(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<Option<usize>> {
+    let max_new_entries = conn
+        .prepare_cached("SELECT max_new_entries FROM feeds WHERE id = ?1")?
+        .query_row([feed_id], |row| row.get(0))?;
+
+    Ok(max_new_entries)
+}
+
+/// Limits how many new entries a refresh of the feed with `feed_link` may add,
+/// or with `None`, goes back to the global limit
+pub fn set_feed_max_new_entries(
+    conn: &rusqlite::Connection,
+    feed_link: &str,
+    max_new_entries: Option<usize>,
+) -> Result<()> {
+    let updated = conn.execute(
+        "UPDATE feeds SET max_new_entries = ?2 WHERE feed_link = ?1",
+        params![feed_link, max_new_entries],
+    )?;
+
+    if updated == 0 {
+        bail!("There is no feed with the feed link {feed_link}");
+    }
+
+    Ok(())
+}
+
+/// Marks the feed's unread entries published more than its `mark_read_after_days` ago as read,
+/// so firehose feeds don't pile up a backlog nobody will read
+fn mark_old_entries_read(tx: &rusqlite::Transaction, feed_id: FeedId) -> Result<usize> {
+    let mark_read_after_days: Option<u32> = tx
+        .prepare_cached("SELECT mark_read_after_days FROM feeds WHERE id = ?1")?
+        .query_row([feed_id], |row| row.get(0))?;
+
+    let Some(mark_read_after_days) = mark_read_after_days else {
+        return Ok(0);
+    };
+
+    let now = Utc::now();
+    let cutoff = now - chrono::Duration::days(mark_read_after_days.into());
+
+    let marked = tx.execute(
+        "UPDATE entries SET read_at = ?2
+        WHERE feed_id = ?1
+        AND read_at IS NULL
+        AND COALESCE(pub_date, inserted_at) < ?3",
+        params![feed_id, now, cutoff],
+    )?;
+
+    Ok(marked)
+}
+
+/// Marks every unread entry of the feed as read, returning how many there were
+pub fn mark_feed_read(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<usize> {
+    let marked = conn.execute(
+        "UPDATE entries SET read_at = ?2 WHERE feed_id = ?1 AND read_at IS NULL",
+        params![feed_id, Utc::now()],
+    )?;
+
+    Ok(marked)
+}
+
+/// What the feed sends with every request for it
+pub fn get_feed_http_settings(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+) -> Result<FeedHttpSettings> {
+    let (user_agent, headers) = conn
+        .prepare_cached("SELECT user_agent, http_headers FROM feeds WHERE id = ?1")?
+        .query_row([feed_id], |row| {
+            Ok((row.get(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+
+    // one `Name: value` per line
+    let headers = headers
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+        .collect();
+
+    Ok(FeedHttpSettings {
+        user_agent,
+        headers,
+    })
+}
+
+fn get_feed_entry_sort(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<EntrySort> {
+    let entry_sort = conn
+        .prepare_cached("SELECT entry_sort FROM feeds WHERE id = ?1")?
+        .query_row([feed_id], |row| row.get(0))?;
+
+    Ok(entry_sort)
+}
+
+pub fn set_feed_entry_sort(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    entry_sort: EntrySort,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds SET entry_sort = ?2 WHERE id = ?1",
+        params![feed_id, entry_sort],
+    )?;
+
+    Ok(())
+}
+
+/// Shows the feed as `custom_title` instead of its own title,
+/// or with `None`, goes back to its own title
+pub fn set_feed_custom_title(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    custom_title: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds SET custom_title = ?2 WHERE id = ?1",
+        params![feed_id, custom_title],
+    )?;
+
+    Ok(())
+}
+
+pub fn set_feed_http_settings(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    http_settings: &FeedHttpSettings,
+) -> Result<()> {
+    let headers = http_settings
+        .headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    conn.execute(
+        "UPDATE feeds SET user_agent = ?2, http_headers = ?3 WHERE id = ?1",
+        params![
+            feed_id,
+            http_settings.user_agent,
+            Some(headers).filter(|headers| !headers.is_empty())
+        ],
+    )?;
+
+    Ok(())
+}
+
+fn get_feed_retention(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<Option<Retention>> {
+    let retention = conn
+        .prepare_cached("SELECT retention FROM feeds WHERE id = ?1")?
+        .query_row([feed_id], |row| row.get(0))?;
+
+    Ok(retention_from_sql(retention))
+}
+
+/// Sets which old entries refreshes of the feed with `feed_link` delete,
+/// or with `None`, goes back to the global retention
+pub fn set_feed_retention(
+    conn: &rusqlite::Connection,
+    feed_link: &str,
+    retention: Option<Retention>,
+) -> Result<()> {
+    let updated = conn.execute(
+        "UPDATE feeds SET retention = ?2 WHERE feed_link = ?1",
+        params![feed_link, retention.map(|retention| retention.to_string())],
+    )?;
+
+    if updated == 0 {
+        bail!("There is no feed with the feed link {feed_link}");
+    }
+
+    Ok(())
+}
+
+/// Deletes the feed's entries past its retention, except unread and starred entries,
+/// and entries in `remote_links`, the links of the feed as it was just fetched,
+/// which the next refresh would otherwise add back as new.
+/// Returns how many entries were deleted
+fn prune_entries(
+    tx: &rusqlite::Transaction,
+    feed_id: FeedId,
+    remote_links: &HashSet<String>,
+    ingest_options: &IngestOptions,
+) -> Result<usize> {
+    let Some(retention) = get_feed_retention(tx, feed_id)?.or(ingest_options.retention) else {
+        return Ok(0);
+    };
+
+    let (predicate, bound): (&str, Box<dyn rusqlite::ToSql>) = match retention {
+        Retention::Entries(entries) => (
+            "id NOT IN (
+              SELECT id FROM entries WHERE feed_id = ?1
+              ORDER BY COALESCE(pub_date, inserted_at) DESC
+              LIMIT ?2
+            )",
+            Box::new(entries),
+        ),
+        Retention::Days(days) => (
+            "COALESCE(pub_date, inserted_at) < ?2",
+            Box::new(Utc::now() - chrono::Duration::days(days.into())),
+        ),
+    };
+
+    let mut statement = tx.prepare(&format!(
+        "SELECT id, link FROM entries
+        WHERE feed_id = ?1
+        AND read_at IS NOT NULL
+        AND starred_at IS NULL
+        AND {predicate}"
+    ))?;
+
+    let expired = statement
+        .query_map(params![feed_id, bound], |row| {
+            Ok((row.get::<_, EntryId>(0)?, row.get::<_, Option<String>>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut delete = tx.prepare_cached("DELETE FROM entries WHERE id = ?1")?;
+    let mut pruned = 0;
+
+    for (entry_id, link) in expired {
+        let still_in_feed = link.is_some_and(|link| {
+            remote_links.contains(&crate::util::strip_tracking_parameters(
+                &link,
+                &ingest_options.tracking_parameters,
+            ))
+        });
+
+        if !still_in_feed {
+            pruned += delete.execute([entry_id])?;
+        }
+    }
+
+    Ok(pruned)
+}
+
+/// Has refreshes of the feed with `feed_link` mark its entries older than `days` as read,
+/// or with `None`, stops them
+pub fn set_feed_mark_read_after_days(
+    conn: &rusqlite::Connection,
+    feed_link: &str,
+    days: Option<u32>,
+) -> Result<()> {
+    let updated = conn.execute(
+        "UPDATE feeds SET mark_read_after_days = ?2 WHERE feed_link = ?1",
+        params![feed_link, days],
+    )?;
+
+    if updated == 0 {
+        bail!("There is no feed with the feed link {feed_link}");
+    }
+
+    Ok(())
+}
+
+fn update_feed_cache_validators(
+    tx: &rusqlite::Transaction,
+    feed_id: FeedId,
+    feed: &IncomingFeed,
+) -> Result<()> {
+    tx.execute(
+        "UPDATE feeds SET latest_etag = ?2, last_modified = ?3 WHERE id = ?1",
+        params![feed_id, feed.latest_etag, feed.last_modified],
+    )?;
+
+    Ok(())
+}
+
+/// How many of a feed's entries without a guid `backfill_guids` found one for
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GuidBackfill {
+    pub matched: usize,
+    pub missing: usize,
+}
+
+/// Fetches a feed again to find the guids of its entries that were stored without one.
+/// Entries are matched to what the feed has now by link,
+/// so entries that have fallen out of the feed stay without a guid.
+pub fn backfill_guids(
+    http_client: &ureq::Agent,
+    conn: &mut rusqlite::Connection,
+    feed_id: FeedId,
+    ingest_options: &IngestOptions,
+) -> Result<GuidBackfill> {
+    let feed_url = get_feed_url(conn, feed_id)?;
+
+    let remote_entries = match fetch_feed(
+        http_client,
+        &feed_url,
+        &CacheValidators::default(),
+        &get_feed_http_settings(conn, feed_id)?,
+        ingest_options,
+    )
+    .with_context(|| format!("Failed to fetch feed {feed_url}"))?
+    {
+        FeedResponse::CacheMiss(feed_and_entries) => feed_and_entries.entries,
+        FeedResponse::CacheHit => vec![],
+    };
+
+    in_transaction(conn, |tx| fill_in_guids(tx, feed_id, &remote_entries))
+}
+
+fn fill_in_guids(
+    tx: &rusqlite::Transaction,
+    feed_id: FeedId,
+    remote_entries: &[IncomingEntry],
+) -> Result<GuidBackfill> {
+    let mut update_statement = tx.prepare(
+        "UPDATE entries SET guid = ?3 WHERE feed_id = ?1 AND link = ?2 AND guid IS NULL",
+    )?;
+
+    let mut matched = 0;
+
+    for entry in remote_entries {
+        if let (Some(link), Some(guid)) = (&entry.link, &entry.guid) {
+            matched += update_statement.execute(params![feed_id, link, guid])?;
+        }
+    }
+
+    let missing: usize = tx.query_row(
+        "SELECT COUNT(*) FROM entries WHERE feed_id = ?1 AND guid IS NULL",
+        [feed_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(GuidBackfill { matched, missing })
+}
+
+/// Files the feed in `folder`, or with `None`, takes it out of any folder
+pub fn set_feed_folder(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    folder: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds SET folder = ?2 WHERE id = ?1",
+        params![feed_id, folder],
+    )?;
+
+    Ok(())
+}
+
+/// Keeps what the feed says about itself up to date, as it changes
+fn update_feed_metadata(
+    tx: &rusqlite::Transaction,
+    feed_id: FeedId,
+    feed: &IncomingFeed,
+) -> Result<()> {
+    tx.execute(
+        "UPDATE feeds SET description = ?2, language = ?3, last_build_date = ?4 WHERE id = ?1",
+        params![
+            feed_id,
+            feed.description,
+            feed.language,
+            feed.last_build_date
+        ],
+    )?;
+
+    Ok(())
+}
+
+fn update_feed_hub_link(
+    tx: &rusqlite::Transaction,
+    feed_id: FeedId,
+    hub_link: Option<String>,
+) -> Result<()> {
+    tx.execute(
+        "UPDATE feeds SET hub_link = ?2 WHERE id = ?1",
+        params![feed_id, hub_link],
+    )?;
+
+    Ok(())
+}
+
+pub fn get_feed_url(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<String> {
+    let s: String = conn
+        .prepare_cached("SELECT feed_link FROM feeds WHERE id=?1")?
+        .query_row([feed_id], |row| row.get(0))?;
+
+    Ok(s)
+}
+
+fn get_feed_cache_validators(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+) -> Result<CacheValidators> {
+    let cache_validators = conn
+        .prepare_cached("SELECT latest_etag, last_modified FROM feeds WHERE id=?1")?
+        .query_row([feed_id], |row| {
+            Ok(CacheValidators {
+                etag: row.get(0)?,
+                last_modified: row.get(1)?,
+            })
+        })?;
+
+    Ok(cache_validators)
+}
+
+pub fn get_feeds(conn: &rusqlite::Connection, sort: FeedSort) -> Result<Vec<Feed>> {
+    let mut statement = conn.prepare_cached(&format!(
+        "SELECT 
+          id, 
+          title, 
+          feed_link, 
+          link, 
+          feed_kind, 
+          refreshed_at, 
+          inserted_at, 
+          updated_at,
+          latest_etag,
+          hub_link,
+          last_viewed_at,
+          consecutive_failures,
+          dead_at,
+          suggested_feed_link,
+          source,
+          max_new_entries,
+          skipped_entries,
+          mark_read_after_days,
+          description,
+          language,
+          last_build_date,
+          folder,
+          blocked_by,
+          refresh_warnings,
+          retention,
+          entry_sort,
+          custom_title
+        FROM feeds ORDER BY {}",
+        sort.to_sql()
+    ))?;
+    let mut feeds = vec![];
+    for feed in statement.query_map([], |row| {
+        Ok(Feed {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            feed_link: row.get(2)?,
+            link: row.get(3)?,
+            feed_kind: row.get(4)?,
+            refreshed_at: row.get(5)?,
+            inserted_at: row.get(6)?,
+            updated_at: row.get(7)?,
+            latest_etag: row.get(8)?,
+            hub_link: row.get(9)?,
+            last_viewed_at: row.get(10)?,
+            consecutive_failures: row.get(11)?,
+            dead_at: row.get(12)?,
+            suggested_feed_link: row.get(13)?,
+            source: row.get(14)?,
+            max_new_entries: row.get(15)?,
+            skipped_entries: row.get(16)?,
+            mark_read_after_days: row.get(17)?,
+            description: row.get(18)?,
+            language: row.get(19)?,
+            last_build_date: row.get(20)?,
+            folder: row.get(21)?,
+            blocked_by: row.get(22)?,
+            refresh_warnings: refresh_warnings_from_sql(row.get(23)?),
+            retention: retention_from_sql(row.get(24)?),
+            entry_sort: row.get(25)?,
+            custom_title: row.get(26)?,
+        })
+    })? {
+        feeds.push(feed?)
+    }
+
+    Ok(feeds)
+}
+
+pub fn update_feed_last_viewed_at(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds SET last_viewed_at = ?2 WHERE id = ?1",
+        params![feed_id, Utc::now()],
+    )?;
+
+    Ok(())
+}
+
+/// The number of entries each feed has gained since its entries were last viewed.
+/// This is unrelated to whether those entries have been read.
+/// Feeds with no new entries are not included.
+pub fn get_new_entries_counts(conn: &rusqlite::Connection) -> Result<HashMap<FeedId, usize>> {
+    let mut statement = conn.prepare_cached(
+        "SELECT feeds.id, COUNT(entries.id)
+        FROM feeds
+        JOIN entries ON entries.feed_id = feeds.id
+        WHERE feeds.last_viewed_at IS NULL OR entries.inserted_at > feeds.last_viewed_at
+        GROUP BY feeds.id",
+    )?;
+
+    let counts = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    Ok(counts)
+}
+
+/// The number of unread entries in each feed.
+/// Feeds with no unread entries are not included.
+pub fn get_unread_entries_counts(conn: &rusqlite::Connection) -> Result<HashMap<FeedId, usize>> {
+    let mut statement = conn.prepare_cached(
+        "SELECT feed_id, COUNT(*)
+        FROM entries
+        WHERE read_at IS NULL
+        GROUP BY feed_id",
+    )?;
+
+    let counts = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    Ok(counts)
+}
+
+/// How many new entries the feeds refreshed since `since` left out for being over their limits
+pub fn count_entries_skipped_since(
+    conn: &rusqlite::Connection,
+    since: chrono::DateTime<Utc>,
+) -> Result<usize> {
+    let count = conn.query_row(
+        "SELECT COALESCE(SUM(skipped_entries), 0) FROM feeds WHERE refreshed_at >= ?1",
+        [since],
+        |row| row.get(0),
+    )?;
+
+    Ok(count)
+}
+
+/// How many entries, across all feeds, were inserted at or after `since`
+pub fn count_entries_inserted_since(
+    conn: &rusqlite::Connection,
+    since: chrono::DateTime<Utc>,
+) -> Result<usize> {
+    let count = conn.query_row(
+        "SELECT COUNT(*) FROM entries WHERE inserted_at >= ?1",
+        [since],
+        |row| row.get(0),
+    )?;
+
+    Ok(count)
+}
+
+/// The feeds that should be refreshed when refreshing all feeds,
+/// which is all but the dead ones, email newsletters, and imported bookmarks
+pub fn get_refreshable_feed_ids(conn: &rusqlite::Connection) -> Result<Vec<FeedId>> {
+    let mut statement = conn.prepare_cached(
+        "SELECT id FROM feeds
+        WHERE dead_at IS NULL AND source NOT IN ('email', 'bookmarks')
+        ORDER BY lower(title) ASC",
+    )?;
+    let mut ids = vec![];
+    for id in statement.query_map([], |row| row.get(0))? {
+        ids.push(id?)
+    }
+
+    Ok(ids)
+}
+
+pub fn get_feed_ids(conn: &rusqlite::Connection) -> Result<Vec<FeedId>> {
+    let mut statement = conn.prepare_cached("SELECT id FROM feeds ORDER BY lower(title) ASC")?;
+    let mut ids = vec![];
+    for id in statement.query_map([], |row| row.get(0))? {
+        ids.push(id?)
+    }
+
+    Ok(ids)
+}
+
+pub fn get_entry_meta(conn: &rusqlite::Connection, entry_id: EntryId) -> Result<EntryMetadata> {
+    let result = conn
+        .prepare_cached(&format!(
+            "SELECT {} FROM entries WHERE id=?1",
+            query::ENTRY_META_COLUMNS
+        ))?
+        .query_row([entry_id], query::entry_meta_from_row)?;
+
+    Ok(result)
+}
+
+pub fn get_entry_content(conn: &rusqlite::Connection, entry_id: EntryId) -> Result<EntryContent> {
+    let result = conn
+        .prepare_cached(
+            "SELECT content, description, full_content FROM entry_contents WHERE entry_id=?1",
+        )?
+        .query_row([entry_id], |row| {
+            Ok(EntryContent {
+                content: compression::get_text(row, 0)?,
+                description: compression::get_text(row, 1)?,
+                full_content: compression::get_text(row, 2)?,
+            })
+        })?;
+
+    Ok(result)
+}
+
+pub fn get_entry_info(conn: &rusqlite::Connection, entry_id: EntryId) -> Result<EntryInfo> {
+    let meta = get_entry_meta(conn, entry_id)?;
+    let content = get_entry_content(conn, entry_id)?;
+
+    let (feed_title, feed_link) = conn
+        .prepare_cached("SELECT title, feed_link FROM feeds WHERE id=?1")?
+        .query_row([meta.feed_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    Ok(EntryInfo {
+        meta,
+        feed_title,
+        feed_link,
+        content_len: content.content.map(|content| content.len()),
+        description_len: content.description.map(|description| description.len()),
+    })
+}
+
+/// Words too common in titles to say what an entry is about
+const TITLE_STOPWORDS: &[&str] = &[
+    "about", "after", "again", "also", "been", "before", "being", "could", "does", "from", "have",
+    "here", "into", "just", "like", "made", "make", "more", "most", "much", "need", "news", "only",
+    "other", "over", "some", "than", "that", "their", "them", "then", "there", "these", "they",
+    "this", "what", "when", "where", "which", "while", "will", "with", "would", "your",
+];
+
+/// The words of a title that say what it is about, lowercased and without duplicates
+fn title_keywords(title: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = vec![];
+
+    for word in title
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|word| word.to_lowercase())
+    {
+        if word.chars().count() >= 4
+            && !TITLE_STOPWORDS.contains(&word.as_str())
+            && !keywords.contains(&word)
+        {
+            keywords.push(word);
+        }
+    }
+
+    keywords
+}
+
+/// Other entries, from any feed, whose titles share keywords with `entry_meta`'s,
+/// the ones sharing the most first, then the newest.
+/// With more than one keyword, an entry has to share two of them,
+/// as one common word like "release" is not much of a topic.
+pub fn get_related_entries(
+    conn: &rusqlite::Connection,
+    entry_meta: &EntryMetadata,
+    limit: usize,
+) -> Result<Vec<EntryMetadata>> {
+    // keywords past the first few rarely help, and each one is another LIKE for every entry
+    const MAX_KEYWORDS: usize = 8;
+
+    let Some(title) = &entry_meta.title else {
+        return Ok(vec![]);
+    };
+
+    let mut keywords = title_keywords(title);
+    keywords.truncate(MAX_KEYWORDS);
+
+    if keywords.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let min_shared_keywords = keywords.len().min(2);
+
+    let title_predicates = (0..keywords.len())
+        .map(|i| format!("title LIKE ?{}", i + 2))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+
+    let mut statement = conn.prepare(&format!(
+        "SELECT {} FROM entries
+        WHERE id != ?1
+        AND ({title_predicates})
+        ORDER BY pub_date DESC, inserted_at DESC",
+        query::ENTRY_META_COLUMNS
+    ))?;
+
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(entry_meta.id)];
+    params.extend(
+        keywords
+            .iter()
+            .map(|keyword| Box::new(format!("%{keyword}%")) as Box<dyn rusqlite::ToSql>),
+    );
+
+    let candidates = statement
+        .query_map(
+            rusqlite::params_from_iter(params),
+            query::entry_meta_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // LIKE matches within words too, like "rust" in "trust", so count whole words here
+    let mut related = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let candidate_keywords = title_keywords(candidate.title.as_deref().unwrap_or_default());
+            let shared_keywords = keywords
+                .iter()
+                .filter(|keyword| candidate_keywords.contains(keyword))
+                .count();
+
+            (shared_keywords >= min_shared_keywords).then_some((shared_keywords, candidate))
+        })
+        .collect::<Vec<_>>();
+
+    // a stable sort, so entries sharing as many keywords stay newest first
+    related.sort_by_key(|(shared_keywords, _)| std::cmp::Reverse(*shared_keywords));
+
+    Ok(related
+        .into_iter()
+        .take(limit)
+        .map(|(_, entry_meta)| entry_meta)
+        .collect())
+}
+
+pub fn get_entry_ids(conn: &rusqlite::Connection) -> Result<Vec<EntryId>> {
+    EntriesQuery::new().ids(conn)
+}
+
+/// Starred entries, most recently starred first
+pub fn get_starred_entry_ids(conn: &rusqlite::Connection) -> Result<Vec<EntryId>> {
+    EntriesQuery::new()
+        .starred(true)
+        .order(EntriesOrder::RecentlyStarred)
+        .ids(conn)
+}
+
+/// Starred entries in every feed, most recently starred first
+pub fn get_starred_entries(conn: &rusqlite::Connection) -> Result<Vec<EntryMetadata>> {
+    EntriesQuery::new()
+        .starred(true)
+        .order(EntriesOrder::RecentlyStarred)
+        .metas(conn)
+}
+
+/// With `as_of`, only the entries published before it,
+/// to see a feed as it was then, without anything newer.
+/// They are sorted the way the feed's `entry_sort` says
+pub fn get_entries_metas(
+    conn: &rusqlite::Connection,
+    read_mode: &ReadMode,
+    feed_id: FeedId,
+    as_of: Option<DateTime<Utc>>,
+) -> Result<Vec<EntryMetadata>> {
+    EntriesQuery::new()
+        .feed(feed_id)
+        .read_mode(read_mode)
+        .published_between(None, as_of)
+        .order(get_feed_entry_sort(conn, feed_id)?.order())
+        .metas(conn)
+}
+
+pub fn get_entries_links(
+    conn: &rusqlite::Connection,
+    read_mode: &ReadMode,
+    feed_id: FeedId,
+) -> Result<Vec<Option<String>>> {
+    EntriesQuery::new()
+        .feed(feed_id)
+        .read_mode(read_mode)
+        .links(conn)
+}
+
+/// run `f` in a transaction, committing if `f` returns an `Ok` value,
+/// otherwise rolling back.
+fn in_transaction<F, R>(conn: &mut rusqlite::Connection, f: F) -> Result<R>
+where
+    F: Fn(&rusqlite::Transaction) -> Result<R>,
+{
+    let tx = conn.transaction()?;
+
+    let result = f(&tx)?;
+
+    tx.commit()?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const ZCT: &str = "https://zeroclarkthirty.com/feed";
+
+    /// An unread entry at `link`, titled with its link, and with nothing else
+    fn imported_entry(link: &str) -> ImportedEntry {
+        ImportedEntry {
+            title: Some(link.to_owned()),
+            author: None,
+            pub_date: None,
+            content: None,
+            link: Some(link.to_owned()),
+            read_at: None,
+            starred_at: None,
+        }
+    }
+
+    /// Imports `entries` into the feed at `feed_link`, called "Example" if it is new
+    fn feed_with_entries(
+        conn: &mut rusqlite::Connection,
+        feed_link: &str,
+        entries: &[ImportedEntry],
+    ) -> FeedId {
+        import_feed(
+            conn,
+            Some("Example"),
+            feed_link,
+            None,
+            entries,
+            &IngestOptions::default(),
+        )
+        .unwrap();
+
+        conn.query_row(
+            "SELECT id FROM feeds WHERE feed_link = ?1",
+            [feed_link],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn it_fetches() {
+        let http_client = ureq::AgentBuilder::new()
+            .timeout_read(std::time::Duration::from_secs(5))
+            .build();
+        let feed_and_entries = fetch_feed(
+            &http_client,
+            ZCT,
+            &CacheValidators::default(),
+            &FeedHttpSettings::default(),
+            &IngestOptions::default(),
+        )
+        .unwrap();
+        if let FeedResponse::CacheMiss(feed_and_entries) = feed_and_entries {
+            assert!(!feed_and_entries.entries.is_empty())
+        } else {
+            panic!("somehow got a cached response when passing no etag")
+        }
+    }
+
+    #[test]
+    fn it_subscribes_to_a_feed() {
+        let http_client = ureq::AgentBuilder::new()
+            .timeout_read(std::time::Duration::from_secs(5))
+            .build();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+        subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            ZCT,
+            &IngestOptions::default(),
+            0,
+            |_| Ok(()),
+        )
+        .unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))
+            .unwrap();
+
+        assert!(count > 50)
+    }
+
+    #[test]
+    fn refresh_feed_does_not_add_any_items_if_there_are_no_new_items() {
+        let http_client = ureq::AgentBuilder::new()
+            .timeout_read(std::time::Duration::from_secs(5))
+            .build();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+        subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            ZCT,
+            &IngestOptions::default(),
+            0,
+            |_| Ok(()),
+        )
+        .unwrap();
+        let feed_id = 1.into();
+        let old_entries = get_entries_metas(&conn, &ReadMode::ShowUnread, feed_id, None).unwrap();
+        refresh_feed(&http_client, &mut conn, feed_id, &IngestOptions::default()).unwrap();
+        let e = get_entry_meta(&conn, 1.into()).unwrap();
+        e.mark_as_read(&conn).unwrap();
+        let new_entries = get_entries_metas(&conn, &ReadMode::ShowUnread, feed_id, None).unwrap();
+
+        assert_eq!(new_entries.len(), old_entries.len() - 1);
+    }
+
+    #[test]
+    fn it_subscribes_to_and_refreshes_local_feeds() {
+        let rss = |items: &str| {
+            format!(
+                r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Local</title><link>https://example.com</link>{items}</channel></rss>"#
+            )
+        };
+        let item = |n: u32| {
+            format!("<item><title>Entry {n}</title><link>https://example.com/{n}</link></item>")
+        };
+
+        let dir = crate::util::TestDir::new("local-feed");
+        let path = dir.join("feed.xml");
+        std::fs::write(&path, rss(&item(1))).unwrap();
+
+        let http_client = ureq::AgentBuilder::new().build();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_id = subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            path.to_str().unwrap(),
+            &IngestOptions::default(),
+            0,
+            |_| Ok(()),
+        )
+        .unwrap();
+
+        let feed = get_feed(&conn, feed_id).unwrap();
+        assert_eq!(
+            feed.feed_link.unwrap(),
+            url::Url::from_file_path(std::fs::canonicalize(&path).unwrap())
+                .unwrap()
+                .to_string()
+        );
+
+        std::fs::write(&path, rss(&format!("{}{}", item(1), item(2)))).unwrap();
+
+        assert_eq!(
+            preview_refresh_feed(&http_client, &mut conn, feed_id, &IngestOptions::default())
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            get_entries_metas(&conn, &ReadMode::All, feed_id, None)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        refresh_feed(&http_client, &mut conn, feed_id, &IngestOptions::default()).unwrap();
+
+        let entries = get_entries_metas(&conn, &ReadMode::All, feed_id, None).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        // only the newest new entry fits under the feed's limit, which beats the global one
+        let dated_item = |n: u32, pub_date: &str| {
+            format!(
+                "<item><title>Entry {n}</title><link>https://example.com/{n}</link><pubDate>{pub_date}</pubDate></item>"
+            )
+        };
+        std::fs::write(
+            &path,
+            rss(&format!(
+                "{}{}{}{}",
+                item(1),
+                dated_item(3, "Mon, 01 Jan 2024 00:00:00 GMT"),
+                item(5),
+                dated_item(4, "Wed, 03 Jan 2024 00:00:00 GMT"),
+            )),
+        )
+        .unwrap();
+
+        let feed_link = get_feed(&conn, feed_id).unwrap().feed_link.unwrap();
+        set_feed_max_new_entries(&conn, &feed_link, Some(1)).unwrap();
+        let ingest_options = IngestOptions {
+            max_new_entries: Some(2),
+            ..Default::default()
+        };
+        let refreshed_since = Utc::now() - chrono::Duration::seconds(1);
+
+        refresh_feed(&http_client, &mut conn, feed_id, &ingest_options).unwrap();
+
+        let entries = get_entries_metas(&conn, &ReadMode::All, feed_id, None).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries
+            .iter()
+            .any(|entry| entry.title.as_deref() == Some("Entry 4")));
+        assert_eq!(get_feed(&conn, feed_id).unwrap().skipped_entries, 2);
+        assert_eq!(
+            count_entries_skipped_since(&conn, refreshed_since).unwrap(),
+            2
+        );
+
+        // the entries from 2024 are old enough to be marked read, the undated ones are not
+        set_feed_mark_read_after_days(&conn, &feed_link, Some(30)).unwrap();
+        refresh_feed(&http_client, &mut conn, feed_id, &ingest_options).unwrap();
+
+        let unread = get_entries_metas(&conn, &ReadMode::ShowUnread, feed_id, None).unwrap();
+        assert_eq!(
+            unread
+                .iter()
+                .map(|entry| entry.title.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["Entry 2", "Entry 1"]
+        );
+        assert_eq!(
+            get_entries_metas(&conn, &ReadMode::ShowRead, feed_id, None)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refreshes_keep_what_they_got_past_as_warnings() {
+        let rss = |pub_date: &str| {
+            format!(
+                r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Local</title><link>https://example.com</link><item><title>Entry</title><link>https://example.com/1</link><pubDate>{pub_date}</pubDate></item></channel></rss>"#
+            )
+        };
+
+        let dir = crate::util::TestDir::new("refresh-warnings");
+        let path = dir.join("feed.xml");
+        std::fs::write(&path, rss("sometime last week")).unwrap();
+
+        let http_client = ureq::AgentBuilder::new().build();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_id = subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            path.to_str().unwrap(),
+            &IngestOptions::default(),
+            0,
+            |_| Ok(()),
+        )
+        .unwrap();
+
+        refresh_feed(&http_client, &mut conn, feed_id, &IngestOptions::default()).unwrap();
+        assert_eq!(
+            get_feed(&conn, feed_id).unwrap().refresh_warnings,
+            vec![
+                "1 entry has a date that could not be read, \"sometime last week\", and is sorted as undated"
+            ]
+        );
+
+        std::fs::write(&path, rss("Mon, 01 Jan 2024 12:00:00 GMT")).unwrap();
+        refresh_feed(&http_client, &mut conn, feed_id, &IngestOptions::default()).unwrap();
+        assert!(get_feeds(&conn, FeedSort::default()).unwrap()[0]
+            .refresh_warnings
+            .is_empty());
+    }
+
+    #[test]
+    fn refreshes_delete_old_read_entries_past_the_retention() {
+        let rss = |days: &[u32]| {
+            let items = days
+                .iter()
+                .map(|day| {
+                    format!(
+                        "<item><title>{day}</title><link>https://example.com/{day}</link><pubDate>{day:02} Jan 2024 12:00:00 GMT</pubDate></item>"
+                    )
+                })
+                .collect::<String>();
+            format!(
+                r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Local</title><link>https://example.com</link>{items}</channel></rss>"#
+            )
+        };
+
+        let dir = crate::util::TestDir::new("retention");
+        let path = dir.join("feed.xml");
+        std::fs::write(&path, rss(&[1, 2, 3, 4])).unwrap();
+
+        let http_client = ureq::AgentBuilder::new().build();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_id = subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            path.to_str().unwrap(),
+            &IngestOptions::default(),
+            0,
+            |_| Ok(()),
+        )
+        .unwrap();
+
+        for entry_meta in get_entries_metas(&conn, &ReadMode::All, feed_id, None).unwrap() {
+            match entry_meta.title.as_deref() {
+                Some("1") => {
+                    entry_meta.toggle_read(&conn).unwrap();
+                    entry_meta.toggle_starred(&conn).unwrap();
+                }
+                Some("2") => (),
+                _ => entry_meta.toggle_read(&conn).unwrap(),
+            }
+        }
+
+        // 3 is the only entry that is read, not starred, past the newest one, and gone from the feed
+        std::fs::write(&path, rss(&[4, 5])).unwrap();
+        let ingest_options = IngestOptions {
+            retention: Some("1".parse().unwrap()),
+            ..Default::default()
+        };
+        refresh_feed(&http_client, &mut conn, feed_id, &ingest_options).unwrap();
+
+        let mut titles = get_entries_metas(&conn, &ReadMode::All, feed_id, None)
+            .unwrap()
+            .into_iter()
+            .map(|entry_meta| entry_meta.title.unwrap())
+            .collect::<Vec<_>>();
+        titles.sort();
+        assert_eq!(titles, vec!["1", "2", "4", "5"]);
+
+        // the feed's own retention takes the place of the global one
+        let feed_link = get_feed(&conn, feed_id).unwrap().feed_link.unwrap();
+        set_feed_retention(&conn, &feed_link, Some(Retention::Days(1))).unwrap();
+        assert_eq!(
+            get_feed(&conn, feed_id).unwrap().retention,
+            Some(Retention::Days(1))
+        );
+
+        std::fs::write(&path, rss(&[5])).unwrap();
+        refresh_feed(&http_client, &mut conn, feed_id, &IngestOptions::default()).unwrap();
+        assert_eq!(
+            get_entries_metas(&conn, &ReadMode::All, feed_id, None)
+                .unwrap()
+                .len(),
+            3
+        );
+
+        assert_eq!("30d".parse::<Retention>().unwrap(), Retention::Days(30));
+        assert!("a month".parse::<Retention>().is_err());
+    }
+
+    #[test]
+    fn it_clamps_or_marks_entries_dated_in_the_future() {
+        let content = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Local</title><link>https://example.com</link>
+<item><title>Later</title><link>https://example.com/later</link><pubDate>Fri, 01 Jan 2100 12:00:00 GMT</pubDate></item>
+<item><title>Earlier</title><link>https://example.com/earlier</link><pubDate>Mon, 01 Jan 2024 12:00:00 GMT</pubDate></item>
+</channel></rss>"#;
+
+        let http_client = ureq::AgentBuilder::new().build();
+
+        for clamp_future_dates in [false, true] {
+            let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+            initialize_db(&mut conn).unwrap();
+
+            let ingest_options = IngestOptions {
+                clamp_future_dates,
+                ..Default::default()
+            };
+            ingest_feed(
+                &http_client,
+                &mut conn,
+                "https://example.com/feed.xml",
+                content,
+                &ingest_options,
+            )
+            .unwrap();
+
+            let entries = get_entries_metas(&conn, &ReadMode::All, 1.into(), None).unwrap();
+            let later = entries
+                .iter()
+                .find(|entry| entry.title.as_deref() == Some("Later"))
+                .unwrap();
+            let earlier = entries
+                .iter()
+                .find(|entry| entry.title.as_deref() == Some("Earlier"))
+                .unwrap();
+
+            assert!(later.is_future_dated());
+            assert!(!earlier.is_future_dated());
+
+            let claimed = "2100-01-01T12:00:00Z".parse().unwrap();
+            if clamp_future_dates {
+                assert!(later.pub_date.unwrap() <= Utc::now());
+                assert_eq!(later.claimed_pub_date, Some(claimed));
+            } else {
+                assert_eq!(later.pub_date, Some(claimed));
+                assert_eq!(later.claimed_pub_date, None);
+            }
+        }
+    }
+
+    #[test]
+    fn entries_without_titles_are_shown_with_their_date() {
+        let content = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Local</title><link>https://example.com</link>
+<item><title>Titled</title><link>https://example.com/titled</link><pubDate>Wed, 01 May 2024 12:00:00 GMT</pubDate></item>
+<item><link>https://example.com/untitled</link><pubDate>Wed, 01 May 2024 12:00:00 GMT</pubDate></item>
+<item><title>   </title><link>https://example.com/blank</link><pubDate>Thu, 02 May 2024 12:00:00 GMT</pubDate></item>
+</channel></rss>"#;
+
+        let http_client = ureq::AgentBuilder::new().build();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        ingest_feed(
+            &http_client,
+            &mut conn,
+            "https://example.com/feed.xml",
+            content,
+            &IngestOptions::default(),
+        )
+        .unwrap();
+
+        let mut titles = get_entries_metas(&conn, &ReadMode::All, 1.into(), None)
+            .unwrap()
+            .iter()
+            .map(EntryMetadata::display_title)
+            .collect::<Vec<_>>();
+        titles.sort();
+
+        assert_eq!(
+            titles,
+            vec![
+                "(untitled — 2024-05-01)",
+                "(untitled — 2024-05-02)",
+                "Titled"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_subscribes_to_exec_feeds() {
+        let http_client = ureq::AgentBuilder::new().build();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_id = subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            r#"exec:echo '<rss version="2.0"><channel><title>Generated</title><item><link>https://example.com/1</link></item></channel></rss>'"#,
+            &IngestOptions::default(),
+            0,
+            |_| Ok(()),
+        )
+        .unwrap();
+
+        let feed = get_feed(&conn, feed_id).unwrap();
+        assert_eq!(feed.source, FeedSource::Exec);
+        assert_eq!(feed.title.as_deref(), Some("Generated"));
+        assert_eq!(
+            get_entries_metas(&conn, &ReadMode::All, feed_id, None)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        assert!(subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            "exec:exit 1",
+            &IngestOptions::default(),
+            0,
+            |_| Ok(()),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn it_ingests_feeds_from_elsewhere() {
+        let rss = |items: &str| {
+            format!(
+                r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Scraped</title><link>https://example.com</link>{items}</channel></rss>"#
+            )
+        };
+        let item = |n: u32| {
+            format!("<item><title>Entry {n}</title><link>https://example.com/{n}</link></item>")
+        };
+
+        let http_client = ureq::AgentBuilder::new().build();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_link = "https://example.com/scraped";
+        let ingest = |conn: &mut rusqlite::Connection, content: &str| {
+            ingest_feed(
+                &http_client,
+                conn,
+                feed_link,
+                content,
+                &IngestOptions::default(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(ingest(&mut conn, &rss(&item(1))), 1);
+        assert_eq!(
+            ingest(&mut conn, &rss(&format!("{}{}", item(1), item(2)))),
+            1
+        );
+
+        let feed_ids = get_feed_ids(&conn).unwrap();
+        assert_eq!(feed_ids.len(), 1);
+        let feed = get_feed(&conn, feed_ids[0]).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Scraped"));
+        assert_eq!(
+            get_entries_metas(&conn, &ReadMode::All, feed.id, None)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn resubscribing_to_a_feed_restores_what_was_read() {
+        let rss = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Example</title><link>https://example.com</link>
+<item><title>Entry 1</title><link>https://example.com/1</link></item>
+<item><title>Entry 2</title><link>https://example.com/2</link><guid>urn:2</guid></item>
+<item><title>Entry 3</title><link>https://example.com/3</link></item>
+</channel></rss>"#;
+
+        let http_client = ureq::AgentBuilder::new().build();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_link = "https://example.com/feed";
+        let subscribe = |conn: &mut rusqlite::Connection| {
+            ingest_feed(
+                &http_client,
+                conn,
+                feed_link,
+                rss,
+                &IngestOptions::default(),
+            )
+            .unwrap();
+            get_feed_ids(conn).unwrap()[0]
+        };
+        let unread_titles = |conn: &rusqlite::Connection, feed_id| {
+            get_entries_metas(conn, &ReadMode::ShowUnread, feed_id, None)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.title.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let feed_id = subscribe(&mut conn);
+        for entry in get_entries_metas(&conn, &ReadMode::All, feed_id, None).unwrap() {
+            if entry.title.as_deref() != Some("Entry 3") {
+                entry.toggle_read(&conn).unwrap();
+            }
+        }
+
+        delete_feed(&mut conn, feed_id).unwrap();
+        let feed_id = subscribe(&mut conn);
+
+        assert_eq!(unread_titles(&conn, feed_id), vec!["Entry 3"]);
+
+        let tombstones_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM entry_tombstones", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(tombstones_count, 0);
+    }
+
+    #[test]
+    fn it_finds_rfc_5005_archive_links() {
+        let atom = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Example</title>
+  <id>urn:example</id>
+  <updated>2024-01-01T00:00:00Z</updated>
+  <link rel="self" href="https://example.com/feed"/>
+  <link rel="prev-archive" href="https://example.com/feed/archive/1"/>
+</feed>"#;
+
+        let feed_and_entries = FeedAndEntries::from_str(atom).unwrap();
+
+        assert_eq!(
+            feed_and_entries.feed.next_page_link.as_deref(),
+            Some("https://example.com/feed/archive/1")
+        );
+    }
+
+    #[test]
+    fn it_falls_back_to_common_feed_paths_on_the_same_host() {
+        assert_eq!(
+            fallback_feed_links("https://example.com/blog/feed.xml?x=1"),
+            vec![
+                "https://example.com/feed",
+                "https://example.com/rss.xml",
+                "https://example.com/atom.xml",
+                "https://example.com/index.xml",
+            ]
+        );
+
+        assert_eq!(
+            fallback_feed_links("https://example.com/rss.xml").len(),
+            FALLBACK_FEED_PATHS.len() - 1
+        );
+
+        assert!(fallback_feed_links("not a url").is_empty());
+    }
+
+    #[test]
+    fn it_finds_websub_hubs() {
+        let rss = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+  <channel>
+    <title>Example</title>
+    <link>https://example.com</link>
+    <description>Example</description>
+    <atom:link rel="hub" href="https://pubsubhubbub.appspot.com/"/>
+  </channel>
+</rss>"#;
+
+        let feed_and_entries = FeedAndEntries::from_str(rss).unwrap();
+
+        assert_eq!(
+            feed_and_entries.feed.hub_link.as_deref(),
+            Some("https://pubsubhubbub.appspot.com/")
+        );
+    }
+
+    #[test]
+    fn it_reads_feed_metadata() {
+        let rss = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Example</title>
+    <link>https://example.com</link>
+    <description> Notes on examples </description>
+    <language>en-us</language>
+    <lastBuildDate>Mon, 01 Jan 2024 12:00:00 GMT</lastBuildDate>
+  </channel>
+</rss>"#;
+
+        let feed = FeedAndEntries::from_str(rss).unwrap().feed;
+        assert_eq!(feed.description.as_deref(), Some("Notes on examples"));
+        assert_eq!(feed.language.as_deref(), Some("en-us"));
+        assert_eq!(feed.last_build_date, parse_datetime("2024-01-01T12:00:00Z"));
+
+        let atom = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="fr">
+  <title>Example</title>
+  <subtitle>Des exemples</subtitle>
+  <id>urn:example</id>
+  <updated>2024-01-01T00:00:00Z</updated>
+</feed>"#;
+
+        let feed = FeedAndEntries::from_str(atom).unwrap().feed;
+        assert_eq!(feed.description.as_deref(), Some("Des exemples"));
+        assert_eq!(feed.language.as_deref(), Some("fr"));
+        assert_eq!(feed.last_build_date, parse_datetime("2024-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn it_resolves_relative_links_against_xml_base_and_the_feed_link() {
+        let atom = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:base="/blog/">
+  <title>Example</title>
+  <link href="../"/>
+  <id>urn:example</id>
+  <updated>2024-01-01T00:00:00Z</updated>
+  <entry>
+    <title>Relative</title>
+    <link href="posts/1"/>
+    <id>urn:example:1</id>
+    <updated>2024-01-01T00:00:00Z</updated>
+  </entry>
+  <entry>
+    <title>Absolute</title>
+    <link href="https://elsewhere.example"/>
+    <id>urn:example:2</id>
+    <updated>2024-01-01T00:00:00Z</updated>
+  </entry>
+</feed>"#;
+
+        let mut feed_and_entries = FeedAndEntries::from_str(atom).unwrap();
+        feed_and_entries.resolve_links("https://example.com/feeds/atom.xml");
+
+        assert_eq!(
+            feed_and_entries.feed.link.as_deref(),
+            Some("https://example.com/")
+        );
+        assert_eq!(
+            feed_and_entries.entries[0].link.as_deref(),
+            Some("https://example.com/blog/posts/1")
+        );
+        // left exactly as it was, without the trailing slash parsing it would add
+        assert_eq!(
+            feed_and_entries.entries[1].link.as_deref(),
+            Some("https://elsewhere.example")
+        );
+
+        let rss = r#"<rss version="2.0"><channel><title>Example</title><link>/</link><description></description><item><link>/posts/1</link></item></channel></rss>"#;
+
+        let mut feed_and_entries = FeedAndEntries::from_str(rss).unwrap();
+        feed_and_entries.resolve_links("https://example.com/feed.xml");
+
+        assert_eq!(
+            feed_and_entries.entries[0].link.as_deref(),
+            Some("https://example.com/posts/1")
+        );
+    }
+
+    #[test]
+    fn it_fills_in_missing_guids_by_link() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_id = feed_with_entries(
+            &mut conn,
+            "https://example.com/feed",
+            &[
+                imported_entry("https://example.com/1"),
+                imported_entry("https://example.com/gone"),
+            ],
+        );
+
+        let rss = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Example</title>
+    <link>https://example.com</link>
+    <description>Example</description>
+    <item>
+      <link>https://example.com/1</link>
+      <guid isPermaLink="false">example-1</guid>
+    </item>
+    <item>
+      <link>https://example.com/2</link>
+      <guid isPermaLink="false">example-2</guid>
+    </item>
+  </channel>
+</rss>"#;
+
+        let feed_and_entries = FeedAndEntries::from_str(rss).unwrap();
+
+        let backfill = in_transaction(&mut conn, |tx| {
+            fill_in_guids(tx, feed_id, &feed_and_entries.entries)
+        })
+        .unwrap();
+
+        assert_eq!(
+            backfill,
+            GuidBackfill {
+                matched: 1,
+                missing: 1
+            }
+        );
+
+        let guid: Option<String> = conn
+            .query_row(
+                "SELECT guid FROM entries WHERE link = 'https://example.com/1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(guid.as_deref(), Some("example-1"));
+    }
+
+    #[test]
+    fn it_counts_new_entries_since_the_last_visit() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let read_entry = |link: &str| ImportedEntry {
+            read_at: Some(Utc::now()),
+            ..imported_entry(link)
+        };
+
+        let feed_id = feed_with_entries(
+            &mut conn,
+            "https://example.com/feed",
+            &[
+                read_entry("https://example.com/1"),
+                read_entry("https://example.com/2"),
+            ],
+        );
+
+        // read entries are still new if the feed has not been visited
+        assert_eq!(
+            get_new_entries_counts(&conn).unwrap(),
+            HashMap::from([(feed_id, 2)])
+        );
+
+        update_feed_last_viewed_at(&conn, feed_id).unwrap();
+
+        assert!(get_new_entries_counts(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_marks_every_entry_of_a_feed_read() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        for (feed_link, link) in [
+            ("https://example.com/feed", "https://example.com/1"),
+            ("https://example.org/feed", "https://example.org/1"),
+        ] {
+            feed_with_entries(
+                &mut conn,
+                feed_link,
+                &[
+                    imported_entry(link),
+                    imported_entry(&format!("{link}/more")),
+                ],
+            );
+        }
+
+        assert_eq!(mark_feed_read(&conn, 1.into()).unwrap(), 2);
+        assert_eq!(mark_feed_read(&conn, 1.into()).unwrap(), 0);
+        assert_eq!(
+            get_unread_entries_counts(&conn).unwrap(),
+            HashMap::from([(2.into(), 2)])
+        );
+    }
+
+    #[test]
+    fn it_stars_entries_across_feeds() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        for (feed_link, link) in [
+            ("https://example.com/feed", "https://example.com/1"),
+            ("https://example.org/feed", "https://example.org/1"),
+        ] {
+            feed_with_entries(
+                &mut conn,
+                feed_link,
+                &[
+                    imported_entry(link),
+                    imported_entry(&format!("{link}/more")),
+                ],
+            );
+        }
+
+        let starred_links = |conn: &rusqlite::Connection| {
+            get_starred_entries(conn)
+                .unwrap()
+                .into_iter()
+                .map(|entry_meta| entry_meta.link.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        for entry_id in [1, 3] {
+            get_entry_meta(&conn, entry_id.into())
+                .unwrap()
+                .toggle_starred(&conn)
+                .unwrap();
+        }
+
+        assert_eq!(starred_links(&conn).len(), 2);
+        assert!(starred_links(&conn).contains(&"https://example.org/1".to_string()));
+
+        get_entry_meta(&conn, 3.into())
+            .unwrap()
+            .toggle_starred(&conn)
+            .unwrap();
+
+        assert_eq!(starred_links(&conn), vec!["https://example.com/1"]);
+    }
+
+    #[test]
+    fn feeds_are_shown_and_sorted_by_their_custom_titles() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        for (title, feed_link) in [
+            ("Blog", "https://a.example/feed"),
+            ("Another Blog", "https://b.example/feed"),
+        ] {
+            import_feed(
+                &mut conn,
+                Some(title),
+                feed_link,
+                None,
+                &[],
+                &IngestOptions::default(),
+            )
+            .unwrap();
+        }
+
+        let titles = |conn: &rusqlite::Connection| {
+            get_feeds(conn, FeedSort::Title)
+                .unwrap()
+                .iter()
+                .map(|feed| feed.display_title().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles(&conn), vec!["Another Blog", "Blog"]);
+
+        set_feed_custom_title(&conn, 1.into(), Some("A Friend's Blog")).unwrap();
+
+        assert_eq!(titles(&conn), vec!["A Friend's Blog", "Another Blog"]);
+        // the feed's own title is kept
+        assert_eq!(
+            get_feed(&conn, 1.into()).unwrap().title.as_deref(),
+            Some("Blog")
+        );
+
+        set_feed_custom_title(&conn, 1.into(), None).unwrap();
+
+        assert_eq!(titles(&conn), vec!["Another Blog", "Blog"]);
+    }
+
+    #[test]
+    fn feeds_are_listed_by_title_update_unread_entries_or_when_they_were_added() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let entry = |link: &str, pub_date: &str, read: bool| ImportedEntry {
+            pub_date: Some(pub_date.parse().unwrap()),
+            read_at: read.then(Utc::now),
+            ..imported_entry(link)
+        };
+
+        for (title, feed_link, entries) in [
+            (
+                "Active",
+                "https://a.example/feed",
+                vec![entry("https://a.example/1", "2024-01-01T00:00:00Z", true)],
+            ),
+            (
+                "Busy",
+                "https://b.example/feed",
+                vec![
+                    entry("https://b.example/1", "2020-01-01T00:00:00Z", false),
+                    entry("https://b.example/2", "2020-01-02T00:00:00Z", false),
+                ],
+            ),
+            (
+                "Quiet",
+                "https://c.example/feed",
+                vec![entry("https://c.example/1", "2025-01-01T00:00:00Z", true)],
+            ),
+        ] {
+            import_feed(
+                &mut conn,
+                Some(title),
+                feed_link,
+                None,
+                &entries,
+                &IngestOptions::default(),
+            )
+            .unwrap();
+        }
+
+        let titles = |sort: FeedSort| {
+            get_feeds(&conn, sort)
+                .unwrap()
+                .into_iter()
+                .map(|feed| feed.title.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles(FeedSort::Title), vec!["Active", "Busy", "Quiet"]);
+        assert_eq!(titles(FeedSort::Updated), vec!["Quiet", "Active", "Busy"]);
+        // feeds with as many unread entries are sorted by title
+        assert_eq!(titles(FeedSort::Unread), vec!["Busy", "Active", "Quiet"]);
+        assert_eq!(titles(FeedSort::Added), vec!["Quiet", "Busy", "Active"]);
+
+        let mut sort = FeedSort::default();
+        for _ in 0..4 {
+            assert_eq!(sort.to_string().parse::<FeedSort>().unwrap(), sort);
+            sort = sort.next();
+        }
+        assert_eq!(sort, FeedSort::Title);
+    }
+
+    #[test]
+    fn feeds_sort_their_entries_by_when_they_were_published_or_first_seen() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let entry = |link: &str, pub_date: &str| ImportedEntry {
+            pub_date: Some(pub_date.parse().unwrap()),
+            ..imported_entry(link)
+        };
+
+        let feed_id = feed_with_entries(
+            &mut conn,
+            "https://example.com/feed",
+            &[entry("https://example.com/new", "2024-06-01T00:00:00Z")],
+        );
+        // an old post, backfilled after the new one
+        feed_with_entries(
+            &mut conn,
+            "https://example.com/feed",
+            &[entry("https://example.com/old", "2010-01-01T00:00:00Z")],
+        );
+
+        let links = |conn: &rusqlite::Connection| {
+            get_entries_metas(conn, &ReadMode::All, feed_id, None)
+                .unwrap()
+                .into_iter()
+                .map(|entry_meta| entry_meta.link.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            get_feed(&conn, feed_id).unwrap().entry_sort,
+            EntrySort::Published
+        );
+        assert_eq!(
+            links(&conn),
+            vec!["https://example.com/new", "https://example.com/old"]
+        );
+
+        set_feed_entry_sort(&conn, feed_id, EntrySort::Discovered).unwrap();
+        assert_eq!(
+            links(&conn),
+            vec!["https://example.com/old", "https://example.com/new"]
+        );
+    }
+
+    #[test]
+    fn it_reports_the_slowest_feeds() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        for (title, feed_link) in [
+            ("Fast", "https://example.com/feed"),
+            ("Slow", "https://example.org/feed"),
+        ] {
+            import_feed(
+                &mut conn,
+                Some(title),
+                feed_link,
+                None,
+                &[],
+                &IngestOptions::default(),
+            )
+            .unwrap();
+        }
+
+        let fast: FeedId = 1.into();
+        let slow: FeedId = 2.into();
+
+        record_feed_fetch(&conn, fast, std::time::Duration::from_millis(100), true).unwrap();
+        record_feed_fetch(&conn, slow, std::time::Duration::from_millis(30_000), false).unwrap();
+        // only the most recent fetches count
+        for _ in 0..FETCH_LOG_LEN {
+            record_feed_fetch(&conn, slow, std::time::Duration::from_millis(2_000), true).unwrap();
+        }
+
+        let slowest_feeds = get_slowest_feeds(&conn, 10).unwrap();
+
+        assert_eq!(
+            slowest_feeds,
+            vec![
+                FeedFetchTimes {
+                    feed_id: slow,
+                    name: "Slow".to_string(),
+                    fetches: FETCH_LOG_LEN,
+                    failures: 0,
+                    average: std::time::Duration::from_millis(2_000),
+                    slowest: std::time::Duration::from_millis(2_000),
+                },
+                FeedFetchTimes {
+                    feed_id: fast,
+                    name: "Fast".to_string(),
+                    fetches: 1,
+                    failures: 0,
+                    average: std::time::Duration::from_millis(100),
+                    slowest: std::time::Duration::from_millis(100),
+                },
+            ]
+        );
+        assert_eq!(get_slowest_feeds(&conn, 1).unwrap().len(), 1);
+
+        delete_feed(&mut conn, slow).unwrap();
+        assert_eq!(get_slowest_feeds(&conn, 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn it_counts_unread_entries() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let entry = |link: &str, read_at| ImportedEntry {
+            read_at,
+            ..imported_entry(link)
+        };
+
+        for (feed_link, entries) in [
+            (
+                "https://example.com/feed",
+                vec![
+                    entry("https://example.com/1", None),
+                    entry("https://example.com/2", None),
+                    entry("https://example.com/3", Some(Utc::now())),
+                ],
+            ),
+            (
+                "https://example.org/feed",
+                vec![entry("https://example.org/1", Some(Utc::now()))],
+            ),
+        ] {
+            feed_with_entries(&mut conn, feed_link, &entries);
+        }
+
+        assert_eq!(
+            get_unread_entries_counts(&conn).unwrap(),
+            HashMap::from([(1.into(), 2)])
+        );
+    }
+
+    #[test]
+    fn it_finds_related_entries_by_title_keywords() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let entry = |title: &str| ImportedEntry {
+            title: Some(title.to_owned()),
+            ..imported_entry(&format!("https://example.com/{title}"))
+        };
+
+        feed_with_entries(
+            &mut conn,
+            "https://example.com/feed",
+            &[
+                entry("Announcing Rust 1.80"),
+                entry("Rust 1.80 announcing thread"),
+                entry("Trust and announcing things"),
+                entry("Rust in the kernel"),
+                entry("Kernel"),
+                entry("Something else entirely"),
+            ],
+        );
+
+        let titles = |title: &str| {
+            let entry_id = conn
+                .query_row("SELECT id FROM entries WHERE title = ?1", [title], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            let entry_meta = get_entry_meta(&conn, entry_id).unwrap();
+
+            get_related_entries(&conn, &entry_meta, 5)
+                .unwrap()
+                .into_iter()
+                .map(|entry_meta| entry_meta.title.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            titles("Announcing Rust 1.80"),
+            vec!["Rust 1.80 announcing thread"]
+        );
+        // with only one keyword, sharing it is enough
+        assert_eq!(titles("Kernel"), vec!["Rust in the kernel"]);
+        assert!(titles("Something else entirely").is_empty());
+    }
+
+    #[test]
+    fn it_stores_compressed_content_and_recompresses_entries() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let html = "<p>hello, world</p>".repeat(100);
+
+        let entry = |link: &str| ImportedEntry {
+            content: Some(html.clone()),
+            ..imported_entry(link)
+        };
+
+        import_feed(
+            &mut conn,
+            Some("Example"),
+            "https://example.com/feed",
+            None,
+            &[entry("https://example.com/1")],
+            &IngestOptions {
+                compress_content: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        feed_with_entries(
+            &mut conn,
+            "https://example.com/feed",
+            &[entry("https://example.com/2")],
+        );
+
+        let content_types = |conn: &rusqlite::Connection| -> Vec<String> {
+            conn.prepare("SELECT typeof(content) FROM entry_contents ORDER BY entry_id")
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        assert_eq!(content_types(&conn), vec!["blob", "text"]);
+
+        for entry_id in [1, 2] {
+            let content = get_entry_content(&conn, entry_id.into()).unwrap().content;
+            assert_eq!(content.as_ref(), Some(&html));
+        }
+
+        assert_eq!(recompress_entries(&mut conn, true).unwrap(), 1);
+        assert_eq!(content_types(&conn), vec!["blob", "blob"]);
+        assert_eq!(recompress_entries(&mut conn, true).unwrap(), 0);
+
+        assert_eq!(recompress_entries(&mut conn, false).unwrap(), 2);
+        assert_eq!(content_types(&conn), vec!["text", "text"]);
+        assert_eq!(
+            get_entry_content(&conn, 1.into()).unwrap().content,
+            Some(html)
+        );
+    }
+
+    #[test]
+    fn recompressing_entries_recompresses_their_fetched_full_content() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        feed_with_entries(
+            &mut conn,
+            "https://example.com/feed",
+            &[imported_entry("https://example.com/1")],
+        );
+
+        let article = "<p>the whole article</p>".repeat(100);
+        update_entry_full_content(&conn, 1.into(), article.clone(), &IngestOptions::default())
+            .unwrap();
+
+        let full_content_type = |conn: &rusqlite::Connection| -> String {
+            conn.query_row(
+                "SELECT typeof(full_content) FROM entry_contents",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(full_content_type(&conn), "text");
+        assert_eq!(recompress_entries(&mut conn, true).unwrap(), 1);
+        assert_eq!(full_content_type(&conn), "blob");
+        assert_eq!(
+            get_entry_content(&conn, 1.into()).unwrap().full_content,
+            Some(article)
+        );
+
+        assert_eq!(recompress_entries(&mut conn, false).unwrap(), 1);
+        assert_eq!(full_content_type(&conn), "text");
+    }
+
+    #[test]
+    fn it_tunes_connections() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+
+        let database_options = DatabaseOptions {
+            cache_size_kib: 1024,
+            synchronous: "normal".parse().unwrap(),
+            page_size: Some(8192),
+            ..Default::default()
+        };
+
+        tune_connection(&conn, &database_options).unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let pragma = |name: &str| -> i64 {
+            conn.pragma_query_value(None, name, |row| row.get(0))
+                .unwrap()
+        };
+
+        assert_eq!(pragma("cache_size"), -1024);
+        // NORMAL
+        assert_eq!(pragma("synchronous"), 1);
+        assert_eq!(pragma("page_size"), 8192);
+        assert_eq!(pragma("foreign_keys"), 1);
+
+        assert!("sometimes".parse::<Synchronous>().is_err());
+    }
+
+    #[test]
+    fn read_only_connections_read_while_a_write_is_uncommitted() {
+        let dir = crate::util::TestDir::new("read-only");
+        let path = dir.join("feeds.db");
+
+        let mut conn = rusqlite::Connection::open(&path).unwrap();
+        initialize_db(&mut conn).unwrap();
+        enable_wal(&conn).unwrap();
+
+        let read_conn = open_read_only(&path, &DatabaseOptions::default()).unwrap();
+
+        let feeds_len = || -> i64 {
+            read_conn
+                .query_row("SELECT count(*) FROM feeds", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        let tx = conn.transaction().unwrap();
+        tx.execute("INSERT INTO feeds (title) VALUES ('Example')", [])
+            .unwrap();
+        // reads see what was last committed
+        assert_eq!(feeds_len(), 0);
+        tx.commit().unwrap();
+        assert_eq!(feeds_len(), 1);
+
+        assert!(read_conn
+            .execute("INSERT INTO feeds (title) VALUES ('Example')", [])
+            .is_err());
+    }
+
+    #[test]
+    fn in_memory_databases_are_shared_by_every_connection() {
+        let path = std::path::Path::new(IN_MEMORY_DATABASE_PATH);
+        let count_tables = |conn: &rusqlite::Connection| -> i64 {
+            conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        let mut conn = open(path, rusqlite::OpenFlags::default()).unwrap();
+        initialize_db(&mut conn).unwrap();
+        let read_conn = open_read_only(path, &DatabaseOptions::default()).unwrap();
+        assert!(count_tables(&read_conn) > 0);
+
+        // it is gone with its last connection.
+        // this is checked before there is a pool, which can open connections in the background
+        drop(read_conn);
+        drop(conn);
+        let mut conn = open(path, rusqlite::OpenFlags::default()).unwrap();
+        assert_eq!(count_tables(&conn), 0);
+
+        initialize_db(&mut conn).unwrap();
+        enable_wal(&conn).unwrap();
+
+        let read_conn = open_read_only(path, &DatabaseOptions::default()).unwrap();
+        let pool = r2d2::Pool::builder()
+            .max_size(1)
+            .build(connection_manager(path, &DatabaseOptions::default()))
+            .unwrap();
+
+        pool.get()
+            .unwrap()
+            .execute("INSERT INTO feeds (title) VALUES ('Example')", [])
+            .unwrap();
+
+        let feeds_len: i64 = read_conn
+            .query_row("SELECT count(*) FROM feeds", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(feeds_len, 1);
+    }
+
+    #[test]
+    fn deleting_a_feed_deletes_its_entries() {
+        // from before entries had a foreign key to their feed
+        let mut conn = migrations::database_at_version(9);
+
+        let count_entries = |conn: &rusqlite::Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        // including an orphan, whose feed is gone
+        conn.execute_batch(
+            "INSERT INTO feeds (id, title, feed_link) VALUES (1, 'Example', 'https://example.com/feed');
+            INSERT INTO entries (feed_id, title, link, content)
+            VALUES (1, 'An entry', 'https://example.com/1', 'Some content'), (99, 'orphan', NULL, NULL);",
+        )
+        .unwrap();
+        assert_eq!(count_entries(&conn), 2);
+
+        initialize_db(&mut conn).unwrap();
+        assert_eq!(count_entries(&conn), 1);
+
+        delete_feed(&mut conn, 1.into()).unwrap();
+        assert_eq!(count_entries(&conn), 0);
+
+        let entry_contents_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM entry_contents", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(entry_contents_count, 0);
+    }
+
+    #[test]
+    fn it_removes_entries_left_behind_by_feeds_deleted_without_foreign_keys() {
+        // from before orphaned entries were removed,
+        // on a connection that does not enable foreign keys, like the sqlite3 shell's
+        let mut conn = migrations::database_at_version(24);
+
+        conn.execute_batch(
+            "INSERT INTO feeds (id, title, feed_link) VALUES (1, 'Example', 'https://example.com/feed');
+            INSERT INTO entries (id, feed_id, title, link) VALUES (1, 1, 'An entry', 'https://example.com/1');
+            INSERT INTO entry_contents (entry_id, content) VALUES (1, 'Some content');
+            DELETE FROM feeds;",
+        )
+        .unwrap();
+
+        initialize_db(&mut conn).unwrap();
+
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+        assert_eq!(count("entries"), 0);
+        assert_eq!(count("entry_contents"), 0);
+    }
+
+    #[test]
+    fn feeds_die_after_too_many_failures_and_come_back_to_life() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_id = feed_with_entries(&mut conn, "https://example.com/feed", &[]);
+
+        for _ in 0..DEAD_AFTER_CONSECUTIVE_FAILURES - 1 {
+            record_feed_refresh_failure(&conn, feed_id, false, None).unwrap();
+        }
+        assert!(get_feed(&conn, feed_id).unwrap().dead_at.is_none());
+        assert_eq!(get_refreshable_feed_ids(&conn).unwrap(), vec![feed_id]);
+
+        record_feed_refresh_failure(&conn, feed_id, false, None).unwrap();
+        assert!(get_feed(&conn, feed_id).unwrap().dead_at.is_some());
+        assert!(get_refreshable_feed_ids(&conn).unwrap().is_empty());
+
+        record_feed_refresh_failure(&conn, feed_id, false, Some("Cloudflare")).unwrap();
+        assert_eq!(
+            get_feed(&conn, feed_id).unwrap().blocked_by.as_deref(),
+            Some("Cloudflare")
+        );
+
+        record_feed_refresh_success(&conn, feed_id).unwrap();
+        let feed = get_feed(&conn, feed_id).unwrap();
+        assert!(feed.dead_at.is_none());
+        assert!(feed.blocked_by.is_none());
+        assert_eq!(feed.consecutive_failures, 0);
+
+        // gone is gone
+        record_feed_refresh_failure(&conn, feed_id, true, None).unwrap();
+        assert!(get_feed(&conn, feed_id).unwrap().dead_at.is_some());
+    }
+
+    #[test]
+    fn it_keeps_the_cache_validators_to_send_back() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_id = feed_with_entries(&mut conn, "https://example.com/feed", &[]);
+
+        let mut feed_and_entries = FeedAndEntries::from_str(
+            r#"<rss version="2.0"><channel><title>Example</title><link>https://example.com</link><description></description></channel></rss>"#,
+        )
+        .unwrap();
+        feed_and_entries.set_cache_validators(CacheValidators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some("Mon, 01 Jan 2024 12:00:00 GMT".to_string()),
+        });
+
+        in_transaction(&mut conn, |tx| {
+            update_feed_cache_validators(tx, feed_id, &feed_and_entries.feed)
+        })
+        .unwrap();
+
+        let cache_validators = get_feed_cache_validators(&conn, feed_id).unwrap();
+        assert_eq!(cache_validators.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(
+            cache_validators.last_modified.as_deref(),
+            Some("Mon, 01 Jan 2024 12:00:00 GMT")
+        );
+
+        // a moved feed is a different document
+        move_feed(&conn, feed_id, "https://example.com/new-feed").unwrap();
+        let cache_validators = get_feed_cache_validators(&conn, feed_id).unwrap();
+        assert!(cache_validators.etag.is_none());
+        assert!(cache_validators.last_modified.is_none());
+    }
+
+    #[test]
+    fn it_sends_a_feeds_own_headers() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let feed_link = format!("http://{}/feed", listener.local_addr().unwrap());
+
+        let feed_id = feed_with_entries(&mut conn, &feed_link, &[]);
+
+        let http_settings =
+            FeedHttpSettings::from_str("User-Agent: curl/8.0 | Authorization: Bearer abc |")
+                .unwrap();
+        assert_eq!(
+            http_settings.to_string(),
+            "User-Agent: curl/8.0 | Authorization: Bearer abc"
+        );
+        assert!(FeedHttpSettings::from_str("Authorization Bearer abc").is_err());
+
+        set_feed_http_settings(&conn, feed_id, &http_settings).unwrap();
+        assert_eq!(
+            get_feed_http_settings(&conn, feed_id).unwrap(),
+            http_settings
+        );
+
+        let server = std::thread::spawn(move || {
+            use std::io::{BufRead, Write};
+
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = std::io::BufReader::new(&stream)
+                .lines()
+                .map_while(|line| line.ok().filter(|line| !line.is_empty()))
+                .collect::<Vec<_>>();
+
+            let body = r#"<rss version="2.0"><channel><title>Example</title><link>https://example.com</link><description></description></channel></rss>"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+
+            request
+        });
+
+        refresh_feed(
+            &ureq::agent(),
+            &mut conn,
+            feed_id,
+            &IngestOptions::default(),
+        )
+        .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.contains(&"User-Agent: curl/8.0".to_string()));
+        assert!(request.contains(&"Authorization: Bearer abc".to_string()));
+    }
+
+    #[test]
+    fn subscribing_to_a_site_subscribes_to_the_feed_it_advertises() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let site_link = format!("http://{}/", listener.local_addr().unwrap());
+
+        // the page is fetched to find it is not a feed, and again to find its feed links,
+        // and the feed to find it works, and again to subscribe to it
+        let server = std::thread::spawn(move || {
+            use std::io::{BufRead, Write};
+
+            for stream in listener.incoming().take(4) {
+                let mut stream = stream.unwrap();
+                let request_line = std::io::BufReader::new(&stream)
+                    .lines()
+                    .next()
+                    .unwrap()
+                    .unwrap();
+
+                let body = if request_line.starts_with("GET /feed.xml") {
+                    r#"<rss version="2.0"><channel><title>Example</title><link>https://example.com</link><description></description><item><title>Hello</title><link>https://example.com/hello</link></item></channel></rss>"#
+                } else {
+                    r#"<html><head><link rel="alternate" type="application/rss+xml" href="/feed.xml"></head><body>Example</body></html>"#
+                };
+
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        let feed_id = subscribe_to_feed(
+            &ureq::agent(),
+            &mut conn,
+            &site_link,
+            &IngestOptions::default(),
+            0,
+            |_| Ok(()),
+        )
+        .unwrap();
+
+        server.join().unwrap();
+
+        let feed = get_feed(&conn, feed_id).unwrap();
+        assert_eq!(feed.feed_link, Some(format!("{site_link}feed.xml")));
+        assert_eq!(
+            get_entries_metas(&conn, &ReadMode::All, feed_id, None)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn consistently_redirected_feeds_are_suggested_to_move() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let feed_id = feed_with_entries(&mut conn, "https://example.com/feed", &[]);
+        let new_feed_link = "https://example.com/new-feed";
+
+        for _ in 0..SUGGEST_MOVE_AFTER_REDIRECTS - 1 {
+            record_feed_redirect(&conn, feed_id, Some(new_feed_link)).unwrap();
+        }
+        // a refresh that is not redirected starts the count over
+        record_feed_redirect(&conn, feed_id, None).unwrap();
+        for _ in 0..SUGGEST_MOVE_AFTER_REDIRECTS - 1 {
+            record_feed_redirect(&conn, feed_id, Some(new_feed_link)).unwrap();
+        }
+        assert!(get_feed(&conn, feed_id)
+            .unwrap()
+            .suggested_feed_link
+            .is_none());
+
+        record_feed_redirect(&conn, feed_id, Some(new_feed_link)).unwrap();
+        assert_eq!(
+            get_feed(&conn, feed_id)
+                .unwrap()
+                .suggested_feed_link
+                .as_deref(),
+            Some(new_feed_link)
+        );
+
+        // turned down moves are not suggested again
+        dismiss_feed_move(&conn, feed_id).unwrap();
+        record_feed_redirect(&conn, feed_id, Some(new_feed_link)).unwrap();
+        assert!(get_feed(&conn, feed_id)
+            .unwrap()
+            .suggested_feed_link
+            .is_none());
+
+        move_feed(&conn, feed_id, new_feed_link).unwrap();
+        let feed = get_feed(&conn, feed_id).unwrap();
+        assert_eq!(feed.feed_link.as_deref(), Some(new_feed_link));
+        assert!(feed.suggested_feed_link.is_none());
+    }
+
+    #[test]
+    fn import_feed_merges_read_state_into_existing_entries() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let entry = |link: &str, read_at| ImportedEntry {
+            read_at,
+            ..imported_entry(link)
+        };
+
+        let inserted = import_feed(
+            &mut conn,
+            Some("Example"),
+            "https://example.com/feed",
+            None,
+            &[entry("https://example.com/1", None)],
+            &IngestOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(inserted, 1);
+
+        let inserted = import_feed(
+            &mut conn,
+            Some("Example"),
+            "https://example.com/feed",
+            None,
+            &[
+                entry("https://example.com/1", Some(Utc::now())),
+                entry("https://example.com/2", None),
+            ],
+            &IngestOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(inserted, 1);
+
+        let feed_id = 1.into();
+        assert_eq!(get_feed_ids(&conn).unwrap(), vec![feed_id]);
+        assert_eq!(
+            get_entries_metas(&conn, &ReadMode::ShowRead, feed_id, None)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            get_entries_metas(&conn, &ReadMode::ShowUnread, feed_id, None)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn reimporting_a_feed_does_not_duplicate_entries_without_links() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let linkless_entry = |title: &str| ImportedEntry {
+            title: Some(title.to_owned()),
+            link: None,
+            ..imported_entry("")
+        };
+
+        let entries = [
+            imported_entry("https://example.com/1"),
+            linkless_entry("Linkless"),
+            linkless_entry("Also linkless"),
+        ];
+
+        for expected_inserted in [3, 0] {
+            let inserted = import_feed(
+                &mut conn,
+                Some("Example"),
+                "https://example.com/feed",
+                None,
+                &entries,
+                &IngestOptions::default(),
+            )
+            .unwrap();
+            assert_eq!(inserted, expected_inserted);
+        }
+
+        assert_eq!(
+            get_entries_metas(&conn, &ReadMode::All, 1.into(), None)
+                .unwrap()
+                .len(),
+            3
+        );
     }
 
     #[test]
@@ -0,0 +1,80 @@
+//! Recognizing the pages bot protection services like Cloudflare serve instead of a feed.
+//!
+//! These are HTML pages that run a script or show a captcha before letting a browser through,
+//! so without this they only show up as feeds that fail to parse.
+
+/// The page markers each bot protection service is recognized by,
+/// matched against the page lowercased
+const CHALLENGE_MARKERS: &[(&str, &[&str])] = &[
+    (
+        "Cloudflare",
+        &[
+            "cf-browser-verification",
+            "/cdn-cgi/challenge-platform/",
+            "cf_chl_",
+            "<title>just a moment...</title>",
+            "<title>attention required! | cloudflare</title>",
+        ],
+    ),
+    (
+        "DDoS-Guard",
+        &["<title>ddos-guard</title>", "ddos-guard.net"],
+    ),
+    ("Imperva", &["_incapsula_resource", "incapsula incident id"]),
+    ("Sucuri", &["sucuri website firewall"]),
+    ("DataDome", &["captcha-delivery.com"]),
+    (
+        "bot protection",
+        &["<title>checking your browser", "g-recaptcha", "h-captcha"],
+    ),
+];
+
+/// Who is blocking the feed, if `body` is a bot protection page
+pub(super) fn challenge_provider(body: &str) -> Option<&'static str> {
+    // the markers are all near the top of these pages,
+    // and feeds can be large
+    let head = body
+        .char_indices()
+        .nth(16 * 1024)
+        .map_or(body, |(i, _)| &body[..i])
+        .to_lowercase();
+
+    if !head.contains("<html") {
+        return None;
+    }
+
+    CHALLENGE_MARKERS
+        .iter()
+        .find(|(_, markers)| markers.iter().any(|marker| head.contains(marker)))
+        .map(|(provider, _)| *provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_recognizes_bot_protection_pages() {
+        let cloudflare = r#"<!DOCTYPE html><html lang="en-US"><head><title>Just a moment...</title>
+            <script src="/cdn-cgi/challenge-platform/h/b/orchestrate/chl_page/v1"></script>"#;
+        assert_eq!(challenge_provider(cloudflare), Some("Cloudflare"));
+
+        let ddos_guard = "<html><head><title>DDoS-Guard</title></head></html>";
+        assert_eq!(challenge_provider(ddos_guard), Some("DDoS-Guard"));
+
+        let captcha = r#"<html><body><div class="g-recaptcha"></div></body></html>"#;
+        assert_eq!(challenge_provider(captcha), Some("bot protection"));
+
+        // an ordinary page, and a feed that writes about Cloudflare challenges
+        assert_eq!(
+            challenge_provider("<html><head><title>Blog</title></head></html>"),
+            None
+        );
+        assert_eq!(
+            challenge_provider(
+                "<rss><channel><item><description>&lt;title&gt;Just a moment...&lt;/title&gt; cf_chl_</description></item></channel></rss>"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Optional zstd compression of entry HTML at rest.
+//!
+//! Compressed `content` and `description` are stored as blobs, and uncompressed ones as text,
+//! so a database can hold both, and reading works the same whether compression is on or off.
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::ToSql;
+
+/// zstd's default, which is fast and already gets most of the size reduction for HTML
+const COMPRESSION_LEVEL: i32 = 0;
+
+/// Entry HTML as it is stored in the database
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum StoredText {
+    Plain(String),
+    Compressed(Vec<u8>),
+}
+
+impl StoredText {
+    /// Compresses `text` if `compress` is set and compressing makes it smaller,
+    /// which it does not for very short text
+    pub(super) fn new(text: String, compress: bool) -> Self {
+        if compress {
+            if let Ok(compressed) = zstd::encode_all(text.as_bytes(), COMPRESSION_LEVEL) {
+                if compressed.len() < text.len() {
+                    return StoredText::Compressed(compressed);
+                }
+            }
+        }
+
+        StoredText::Plain(text)
+    }
+
+    pub(super) fn is_compressed(&self) -> bool {
+        matches!(self, StoredText::Compressed(_))
+    }
+
+    pub(super) fn into_string(self) -> FromSqlResult<String> {
+        match self {
+            StoredText::Plain(text) => Ok(text),
+            StoredText::Compressed(compressed) => {
+                let bytes = zstd::decode_all(compressed.as_slice())
+                    .map_err(|e| FromSqlError::Other(Box::new(e)))?;
+
+                String::from_utf8(bytes).map_err(|e| FromSqlError::Other(Box::new(e)))
+            }
+        }
+    }
+}
+
+impl FromSql for StoredText {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Blob(compressed) => Ok(StoredText::Compressed(compressed.to_vec())),
+            value => String::column_result(value).map(StoredText::Plain),
+        }
+    }
+}
+
+impl ToSql for StoredText {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        match self {
+            StoredText::Plain(text) => text.to_sql(),
+            StoredText::Compressed(compressed) => compressed.to_sql(),
+        }
+    }
+}
+
+/// Reads a `content` or `description` column, decompressing it if it was compressed
+pub(super) fn get_text(row: &rusqlite::Row, index: usize) -> rusqlite::Result<Option<String>> {
+    row.get::<_, Option<StoredText>>(index)?
+        .map(|stored| {
+            stored.into_string().map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    index,
+                    rusqlite::types::Type::Blob,
+                    Box::new(e),
+                )
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_compresses_text_that_gets_smaller() {
+        let html = "<p>hello, world</p>".repeat(100);
+
+        let stored = StoredText::new(html.clone(), true);
+        assert!(stored.is_compressed());
+        assert_eq!(stored.into_string().unwrap(), html);
+
+        assert!(!StoredText::new("hi".to_string(), true).is_compressed());
+        assert!(!StoredText::new(html, false).is_compressed());
+    }
+}
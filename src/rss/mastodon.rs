@@ -0,0 +1,161 @@
+//! Mastodon's RSS feeds, which are feeds of posts rather than articles:
+//! posts have no titles, and their media is only in Media RSS elements.
+
+use super::IncomingEntry;
+
+/// How much of a post's text becomes its title
+const TITLE_CHARS: usize = 80;
+
+/// The RSS feed link for a Mastodon account handle like `@user@mastodon.social`
+pub(super) fn account_feed_link(handle: &str) -> Option<String> {
+    let (user, instance) = handle.trim().strip_prefix('@')?.split_once('@')?;
+
+    let is_valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    };
+
+    if !is_valid(user) || !is_valid(instance) || !instance.contains('.') {
+        return None;
+    }
+
+    Some(format!("https://{instance}/@{user}.rss"))
+}
+
+pub(super) fn is_mastodon_feed(channel: &rss::Channel) -> bool {
+    channel
+        .generator()
+        .is_some_and(|generator| generator.starts_with("Mastodon"))
+}
+
+/// Gives a post a title made from its text, an author from its link,
+/// and links to its media at the end of its description
+pub(super) fn adapt_entry(entry: &mut IncomingEntry, item: &rss::Item) {
+    let media = media_links(item);
+    if !media.is_empty() {
+        let description = entry.description.get_or_insert_with(String::new);
+        description.push_str("<p>Attached media:</p><ul>");
+        for (url, alt_text) in media {
+            description.push_str(&format!(
+                "<li><a href=\"{url}\">{}</a></li>",
+                html_escape::encode_text(alt_text.as_deref().unwrap_or(&url))
+            ));
+        }
+        description.push_str("</ul>");
+    }
+
+    if entry.title.is_none() {
+        entry.title = entry.description.as_deref().map(post_title);
+    }
+
+    if entry.author.is_none() {
+        entry.author = entry.link.as_deref().and_then(author_handle);
+    }
+}
+
+/// The start of the post's first line of text.
+/// Replies start by mentioning who they reply to, so they are marked as replies
+fn post_title(html: &str) -> String {
+    let text = html2text::from_read(html.as_bytes(), usize::MAX);
+
+    let first_line = text
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or("Post with no text");
+
+    let mut title = if first_line.starts_with('@') {
+        format!("Reply: {first_line}")
+    } else {
+        first_line.to_owned()
+    };
+
+    if let Some((cut, _)) = title.char_indices().nth(TITLE_CHARS) {
+        title.truncate(cut);
+        title.push('…');
+    }
+
+    title
+}
+
+/// `@user@instance`, from a post link like `https://instance/@user/123`
+fn author_handle(link: &str) -> Option<String> {
+    let url = url::Url::parse(link).ok()?;
+    let user = url
+        .path_segments()?
+        .find(|segment| segment.starts_with('@'))?;
+    Some(format!("{user}@{}", url.host_str()?))
+}
+
+/// The url and alt text of each of the post's `media:content` elements
+fn media_links(item: &rss::Item) -> Vec<(String, Option<String>)> {
+    item.extensions()
+        .get("media")
+        .and_then(|media| media.get("content"))
+        .into_iter()
+        .flatten()
+        .filter_map(|content| {
+            let url = content.attrs.get("url")?.to_owned();
+            let alt_text = content
+                .children
+                .get("description")
+                .and_then(|descriptions| descriptions.first())
+                .and_then(|description| description.value.clone())
+                .filter(|alt_text| !alt_text.trim().is_empty());
+            Some((url, alt_text))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn it_adapts_mastodon_posts() {
+        assert_eq!(
+            account_feed_link("@user@mastodon.social").as_deref(),
+            Some("https://mastodon.social/@user.rss")
+        );
+        assert_eq!(account_feed_link("https://example.com/feed"), None);
+        assert_eq!(account_feed_link("@user"), None);
+
+        let channel = rss::Channel::from_str(
+            r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:media="http://search.yahoo.com/mrss/">
+<channel>
+<title>User</title>
+<link>https://mastodon.social/@user</link>
+<generator>Mastodon v4.2.0</generator>
+<item>
+<link>https://mastodon.social/@user/1</link>
+<description>&lt;p&gt;@friend agreed, it is a very good cat&lt;/p&gt;</description>
+<media:content url="https://files.mastodon.social/cat.png" type="image/png" medium="image">
+<media:description type="plain">A cat</media:description>
+</media:content>
+</item>
+</channel>
+</rss>"#,
+        )
+        .unwrap();
+
+        assert!(is_mastodon_feed(&channel));
+
+        let item = &channel.items()[0];
+        let mut entry = IncomingEntry::from(item);
+        adapt_entry(&mut entry, item);
+
+        assert_eq!(
+            entry.title.as_deref(),
+            Some("Reply: @friend agreed, it is a very good cat")
+        );
+        assert_eq!(entry.author.as_deref(), Some("@user@mastodon.social"));
+        assert!(entry
+            .description
+            .unwrap()
+            .contains(r#"<a href="https://files.mastodon.social/cat.png">A cat</a>"#));
+    }
+}
@@ -0,0 +1,476 @@
+//! Upgrades to the database schema, applied in order, each in the same transaction as the others.
+//!
+//! The schema version is SQLite's `user_version`, which is how many of `MIGRATIONS` have been applied,
+//! so a migration is never applied twice, and a database from any earlier version of russ
+//! is brought up to date by the ones it is missing.
+//! To change the schema, add a migration to the end of `MIGRATIONS`, and never change one that has shipped.
+
+use anyhow::{bail, Context, Result};
+use rusqlite::Transaction;
+
+type Migration = fn(&Transaction) -> Result<()>;
+
+const MIGRATIONS: &[Migration] = &[
+    create_feeds_and_entries,
+    add_feeds_latest_etag,
+    index_feeds_feed_link,
+    add_feeds_hub_link,
+    add_entries_starred_at,
+    add_entries_resolved_link,
+    add_feeds_last_viewed_at,
+    add_feeds_failures,
+    add_feeds_redirects,
+    add_entries_feed_foreign_key,
+    index_entries_read_at_and_starred_at,
+    move_entry_contents,
+    add_entries_guid,
+    add_feeds_source,
+    add_feeds_max_new_entries,
+    add_feeds_mark_read_after_days,
+    create_search_index,
+    create_entry_tombstones,
+    add_feeds_channel_metadata,
+    add_feeds_folder,
+    add_feeds_blocked_by,
+    add_entry_contents_full_content,
+    add_feeds_last_modified,
+    create_feed_fetches,
+    remove_orphaned_entries,
+    add_feeds_http_settings,
+    add_feeds_refresh_warnings,
+    add_feeds_retention,
+    add_feeds_entry_sort,
+    add_entries_claimed_pub_date,
+    add_feeds_custom_title,
+];
+
+/// Applies the migrations `tx`'s database has not had yet
+pub(super) fn migrate(tx: &Transaction) -> Result<()> {
+    let schema_version: usize = tx.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    if schema_version > MIGRATIONS.len() {
+        bail!(
+            "the database is at version {schema_version}, from a newer russ than this one, which knows up to version {}",
+            MIGRATIONS.len()
+        );
+    }
+
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(schema_version) {
+        migration(tx).with_context(|| {
+            format!("unable to upgrade the database to version {}", version + 1)
+        })?;
+        tx.pragma_update(None, "user_version", version + 1)?;
+    }
+
+    Ok(())
+}
+
+fn create_feeds_and_entries(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS feeds (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        title TEXT,
+        feed_link TEXT,
+        link TEXT,
+        feed_kind TEXT,
+        refreshed_at TIMESTAMP,
+        inserted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS entries (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        feed_id INTEGER,
+        title TEXT,
+        author TEXT,
+        pub_date TIMESTAMP,
+        description TEXT,
+        content TEXT,
+        link TEXT,
+        read_at TIMESTAMP,
+        inserted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS entries_feed_id_and_pub_date_and_inserted_at_index
+        ON entries (feed_id, pub_date, inserted_at)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_latest_etag(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN latest_etag TEXT", [])?;
+
+    Ok(())
+}
+
+fn index_feeds_feed_link(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS feeds_feed_link ON feeds (feed_link)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_hub_link(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN hub_link TEXT", [])?;
+
+    Ok(())
+}
+
+fn add_entries_starred_at(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE entries ADD COLUMN starred_at TIMESTAMP", [])?;
+
+    Ok(())
+}
+
+fn add_entries_resolved_link(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE entries ADD COLUMN resolved_link TEXT", [])?;
+
+    Ok(())
+}
+
+fn add_feeds_last_viewed_at(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN last_viewed_at TIMESTAMP", [])?;
+
+    Ok(())
+}
+
+fn add_feeds_failures(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "ALTER TABLE feeds ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    tx.execute("ALTER TABLE feeds ADD COLUMN dead_at TIMESTAMP", [])?;
+
+    Ok(())
+}
+
+fn add_feeds_redirects(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN redirected_to TEXT", [])?;
+    tx.execute(
+        "ALTER TABLE feeds ADD COLUMN redirect_count INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    tx.execute("ALTER TABLE feeds ADD COLUMN suggested_feed_link TEXT", [])?;
+    tx.execute("ALTER TABLE feeds ADD COLUMN dismissed_feed_link TEXT", [])?;
+
+    Ok(())
+}
+
+fn add_entries_feed_foreign_key(tx: &Transaction) -> Result<()> {
+    // sqlite cannot add a foreign key to an existing table,
+    // so entries is rebuilt with one, leaving behind entries whose feed is gone
+    tx.execute(
+        "CREATE TABLE entries_with_feed_fk (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        feed_id INTEGER REFERENCES feeds (id) ON DELETE CASCADE,
+        title TEXT,
+        author TEXT,
+        pub_date TIMESTAMP,
+        description TEXT,
+        content TEXT,
+        link TEXT,
+        read_at TIMESTAMP,
+        inserted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+        starred_at TIMESTAMP,
+        resolved_link TEXT
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "INSERT INTO entries_with_feed_fk
+        SELECT id, feed_id, title, author, pub_date, description, content, link, read_at, inserted_at, updated_at, starred_at, resolved_link
+        FROM entries
+        WHERE feed_id IN (SELECT id FROM feeds)",
+        [],
+    )?;
+
+    tx.execute("DROP TABLE entries", [])?;
+    tx.execute("ALTER TABLE entries_with_feed_fk RENAME TO entries", [])?;
+
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS entries_feed_id_and_pub_date_and_inserted_at_index
+        ON entries (feed_id, pub_date, inserted_at)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn index_entries_read_at_and_starred_at(tx: &Transaction) -> Result<()> {
+    // for filtering a feed's entries by read mode, and counting unread entries
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS entries_feed_id_and_read_at_index
+        ON entries (feed_id, read_at)",
+        [],
+    )?;
+
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS entries_starred_at_index
+        ON entries (starred_at)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn move_entry_contents(tx: &Transaction) -> Result<()> {
+    // entries are listed far more often than they are read,
+    // so their content lives in its own table, and list queries never have to page through it
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS entry_contents (
+        entry_id INTEGER PRIMARY KEY REFERENCES entries (id) ON DELETE CASCADE,
+        content TEXT,
+        description TEXT
+        )",
+        [],
+    )?;
+
+    tx.execute(
+        "INSERT INTO entry_contents (entry_id, content, description)
+        SELECT id, content, description FROM entries",
+        [],
+    )?;
+
+    tx.execute("ALTER TABLE entries DROP COLUMN content", [])?;
+    tx.execute("ALTER TABLE entries DROP COLUMN description", [])?;
+
+    Ok(())
+}
+
+fn add_entries_guid(tx: &Transaction) -> Result<()> {
+    // entries stored before this have no guid until `russ db backfill-guids` finds them
+    tx.execute("ALTER TABLE entries ADD COLUMN guid TEXT", [])?;
+
+    tx.execute(
+        "CREATE INDEX IF NOT EXISTS entries_feed_id_and_guid_index
+        ON entries (feed_id, guid)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_source(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "ALTER TABLE feeds ADD COLUMN source TEXT NOT NULL DEFAULT 'http'",
+        [],
+    )?;
+
+    tx.execute(
+        "UPDATE feeds SET source = 'file' WHERE feed_link LIKE 'file://%'",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_max_new_entries(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN max_new_entries INTEGER", [])?;
+
+    tx.execute(
+        "ALTER TABLE feeds ADD COLUMN skipped_entries INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_mark_read_after_days(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "ALTER TABLE feeds ADD COLUMN mark_read_after_days INTEGER",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn create_search_index(tx: &Transaction) -> Result<()> {
+    super::search::create_index(tx)?;
+
+    Ok(())
+}
+
+fn create_entry_tombstones(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE entry_tombstones (
+            id INTEGER PRIMARY KEY,
+            feed_link TEXT NOT NULL,
+            link TEXT,
+            guid TEXT,
+            read_at TIMESTAMP NOT NULL
+        );
+
+        CREATE INDEX entry_tombstones_feed_link_index ON entry_tombstones (feed_link);",
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_channel_metadata(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE feeds ADD COLUMN description TEXT;
+        ALTER TABLE feeds ADD COLUMN language TEXT;
+        ALTER TABLE feeds ADD COLUMN last_build_date TIMESTAMP;",
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_folder(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN folder TEXT", [])?;
+
+    Ok(())
+}
+
+fn add_feeds_blocked_by(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN blocked_by TEXT", [])?;
+
+    Ok(())
+}
+
+fn add_entry_contents_full_content(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "ALTER TABLE entry_contents ADD COLUMN full_content TEXT",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_last_modified(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN last_modified TEXT", [])?;
+
+    Ok(())
+}
+
+fn create_feed_fetches(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE feed_fetches (
+            id INTEGER PRIMARY KEY,
+            feed_id INTEGER NOT NULL REFERENCES feeds(id) ON DELETE CASCADE,
+            fetched_at TIMESTAMP NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            succeeded INTEGER NOT NULL
+        );
+
+        CREATE INDEX feed_fetches_feed_id_index ON feed_fetches (feed_id);",
+    )?;
+
+    Ok(())
+}
+
+fn remove_orphaned_entries(tx: &Transaction) -> Result<()> {
+    // connections that do not enable foreign keys, like the sqlite3 shell's by default,
+    // leave a deleted feed's entries behind
+    tx.execute_batch(
+        "DELETE FROM entries WHERE feed_id IS NULL OR feed_id NOT IN (SELECT id FROM feeds);
+        DELETE FROM entry_contents WHERE entry_id NOT IN (SELECT id FROM entries);",
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_http_settings(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "ALTER TABLE feeds ADD COLUMN user_agent TEXT;
+        ALTER TABLE feeds ADD COLUMN http_headers TEXT;",
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_refresh_warnings(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN refresh_warnings TEXT", [])?;
+
+    Ok(())
+}
+
+fn add_feeds_retention(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN retention TEXT", [])?;
+
+    Ok(())
+}
+
+fn add_feeds_entry_sort(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "ALTER TABLE feeds ADD COLUMN entry_sort TEXT NOT NULL DEFAULT 'published'",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_entries_claimed_pub_date(tx: &Transaction) -> Result<()> {
+    tx.execute(
+        "ALTER TABLE entries ADD COLUMN claimed_pub_date TIMESTAMP",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_feeds_custom_title(tx: &Transaction) -> Result<()> {
+    tx.execute("ALTER TABLE feeds ADD COLUMN custom_title TEXT", [])?;
+
+    Ok(())
+}
+
+/// A new in-memory database with only the first `version` migrations applied,
+/// like one made by an older russ
+#[cfg(test)]
+pub(super) fn database_at_version(version: usize) -> rusqlite::Connection {
+    let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+
+    let tx = conn.transaction().unwrap();
+    for migration in &MIGRATIONS[..version] {
+        migration(&tx).unwrap();
+    }
+    tx.pragma_update(None, "user_version", version).unwrap();
+    tx.commit().unwrap();
+
+    conn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_applies_each_migration_once() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+
+        let schema_version = |conn: &rusqlite::Connection| -> usize {
+            conn.pragma_query_value(None, "user_version", |row| row.get(0))
+                .unwrap()
+        };
+
+        let tx = conn.transaction().unwrap();
+        migrate(&tx).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(schema_version(&conn), MIGRATIONS.len());
+
+        // none of them run again, which would fail on things like adding a column that is already there
+        let tx = conn.transaction().unwrap();
+        migrate(&tx).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(schema_version(&conn), MIGRATIONS.len());
+
+        conn.pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        let tx = conn.transaction().unwrap();
+        assert!(migrate(&tx).is_err());
+    }
+}
@@ -0,0 +1,309 @@
+//! A small builder for queries over entries,
+//! so every way of listing entries filters and sorts them the same way.
+
+use super::{EntryId, EntryMetadata, FeedId};
+use crate::modes::ReadMode;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::ToSql;
+
+/// The columns `EntryMetadata` is read from, in the order `entry_meta_from_row` expects
+pub(super) const ENTRY_META_COLUMNS: &str = "id,
+    feed_id,
+    title,
+    author,
+    pub_date,
+    claimed_pub_date,
+    link,
+    resolved_link,
+    read_at,
+    starred_at,
+    inserted_at,
+    updated_at,
+    guid";
+
+pub(super) fn entry_meta_from_row(row: &rusqlite::Row) -> rusqlite::Result<EntryMetadata> {
+    Ok(EntryMetadata {
+        id: row.get(0)?,
+        feed_id: row.get(1)?,
+        title: row.get(2)?,
+        author: row.get(3)?,
+        pub_date: row.get(4)?,
+        claimed_pub_date: row.get(5)?,
+        link: row.get(6)?,
+        resolved_link: row.get(7)?,
+        read_at: row.get(8)?,
+        starred_at: row.get(9)?,
+        inserted_at: row.get(10)?,
+        updated_at: row.get(11)?,
+        guid: row.get(12)?,
+    })
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EntriesOrder {
+    /// Most recently published first.
+    /// We get weird pubDate formats from feeds,
+    /// so this falls back to when entries were inserted, as that is a stable order at least
+    #[default]
+    Newest,
+    /// Most recently starred first
+    RecentlyStarred,
+    /// Most recently inserted first, whenever the entries say they were published
+    Discovered,
+}
+
+impl EntriesOrder {
+    fn to_sql(self) -> &'static str {
+        match self {
+            EntriesOrder::Newest => "pub_date DESC, inserted_at DESC",
+            EntriesOrder::RecentlyStarred => "starred_at DESC",
+            EntriesOrder::Discovered => "inserted_at DESC, id DESC",
+        }
+    }
+}
+
+/// Which entries to get, and in what order.
+/// Filters that are not set match every entry.
+#[derive(Clone, Debug, Default)]
+pub struct EntriesQuery {
+    feed_id: Option<FeedId>,
+    read: Option<bool>,
+    starred: Option<bool>,
+    published_after: Option<DateTime<Utc>>,
+    published_before: Option<DateTime<Utc>>,
+    order: EntriesOrder,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+impl EntriesQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(mut self, feed_id: FeedId) -> Self {
+        self.feed_id = Some(feed_id);
+        self
+    }
+
+    /// Only the entries the read mode shows
+    pub fn read_mode(mut self, read_mode: &ReadMode) -> Self {
+        self.read = match read_mode {
+            ReadMode::ShowUnread => Some(false),
+            ReadMode::ShowRead => Some(true),
+            ReadMode::All => None,
+        };
+        self
+    }
+
+    pub fn starred(mut self, starred: bool) -> Self {
+        self.starred = Some(starred);
+        self
+    }
+
+    pub fn order(mut self, order: EntriesOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Entries published in `[after, before)`.
+    /// Entries without a publication date go by when they were inserted.
+    pub fn published_between(
+        mut self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.published_after = after;
+        self.published_before = before;
+        self
+    }
+
+    pub fn metas(&self, conn: &rusqlite::Connection) -> Result<Vec<EntryMetadata>> {
+        self.query(conn, ENTRY_META_COLUMNS, entry_meta_from_row)
+    }
+
+    pub fn ids(&self, conn: &rusqlite::Connection) -> Result<Vec<EntryId>> {
+        self.query(conn, "id", |row| row.get(0))
+    }
+
+    pub fn links(&self, conn: &rusqlite::Connection) -> Result<Vec<Option<String>>> {
+        self.query(conn, "link", |row| row.get(0))
+    }
+
+    fn query<T, F>(&self, conn: &rusqlite::Connection, columns: &str, f: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&rusqlite::Row) -> rusqlite::Result<T>,
+    {
+        let (sql, params) = self.to_sql(columns);
+
+        // entry lists are queried on nearly every keypress,
+        // and there are only a few distinct queries, so they cache well
+        let mut statement = conn.prepare_cached(&sql)?;
+
+        let rows = statement
+            .query_map(rusqlite::params_from_iter(params), f)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    fn to_sql(&self, columns: &str) -> (String, Vec<Box<dyn ToSql>>) {
+        let mut predicates = vec![];
+        let mut params: Vec<Box<dyn ToSql>> = vec![];
+
+        let mut param = |predicate: &str, value: Box<dyn ToSql>| {
+            params.push(value);
+            predicates.push(predicate.replace('?', &format!("?{}", params.len())));
+        };
+
+        if let Some(feed_id) = self.feed_id {
+            param("feed_id = ?", Box::new(feed_id));
+        }
+
+        if let Some(published_after) = self.published_after {
+            param(
+                "COALESCE(pub_date, inserted_at) >= ?",
+                Box::new(published_after),
+            );
+        }
+
+        if let Some(published_before) = self.published_before {
+            param(
+                "COALESCE(pub_date, inserted_at) < ?",
+                Box::new(published_before),
+            );
+        }
+
+        match self.read {
+            Some(true) => predicates.push("read_at IS NOT NULL".to_string()),
+            Some(false) => predicates.push("read_at IS NULL".to_string()),
+            None => (),
+        }
+
+        match self.starred {
+            Some(true) => predicates.push("starred_at IS NOT NULL".to_string()),
+            Some(false) => predicates.push("starred_at IS NULL".to_string()),
+            None => (),
+        }
+
+        let mut sql = format!("SELECT {columns} FROM entries");
+
+        if !predicates.is_empty() {
+            sql.push_str("\nWHERE ");
+            sql.push_str(&predicates.join("\nAND "));
+        }
+
+        sql.push_str("\nORDER BY ");
+        sql.push_str(self.order.to_sql());
+
+        // sqlite only takes an OFFSET after a LIMIT, where -1 is no limit
+        if self.limit.is_some() || self.offset > 0 {
+            let limit = self.limit.map(|limit| limit as i64).unwrap_or(-1);
+            sql.push_str(&format!("\nLIMIT {limit} OFFSET {}", self.offset));
+        }
+
+        (sql, params)
+    }
+}
+
+// for paging, which nothing does yet.
+// search ranks its matches, so it has its own query
+#[allow(dead_code)]
+impl EntriesQuery {
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_entries_queries() {
+        let (sql, params) = EntriesQuery::new()
+            .feed(1.into())
+            .read_mode(&ReadMode::ShowUnread)
+            .starred(true)
+            .published_between(Some(Utc::now()), None)
+            .order(EntriesOrder::Discovered)
+            .offset(20)
+            .to_sql("id");
+
+        assert_eq!(
+            sql,
+            "SELECT id FROM entries
+WHERE feed_id = ?1
+AND COALESCE(pub_date, inserted_at) >= ?2
+AND read_at IS NULL
+AND starred_at IS NOT NULL
+ORDER BY inserted_at DESC, id DESC
+LIMIT -1 OFFSET 20"
+        );
+        assert_eq!(params.len(), 2);
+
+        let (sql, params) = EntriesQuery::new().to_sql("id");
+        assert_eq!(
+            sql,
+            "SELECT id FROM entries\nORDER BY pub_date DESC, inserted_at DESC"
+        );
+        assert!(params.is_empty());
+    }
+
+    /// The tables `sql` scans in full, rather than searching or scanning an index
+    fn full_scans(
+        conn: &rusqlite::Connection,
+        sql: &str,
+        params: Vec<Box<dyn ToSql>>,
+    ) -> Vec<String> {
+        let mut statement = conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}")).unwrap();
+
+        let details = statement
+            .query_map(rusqlite::params_from_iter(params), |row| {
+                row.get::<_, String>(3)
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        details
+            .into_iter()
+            .filter(|detail| detail.starts_with("SCAN") && !detail.contains("INDEX"))
+            .collect()
+    }
+
+    #[test]
+    fn entries_queries_use_indexes() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+
+        let queries = [
+            EntriesQuery::new()
+                .feed(1.into())
+                .read_mode(&ReadMode::ShowUnread),
+            EntriesQuery::new()
+                .feed(1.into())
+                .read_mode(&ReadMode::ShowRead),
+            EntriesQuery::new().feed(1.into()).read_mode(&ReadMode::All),
+            EntriesQuery::new()
+                .starred(true)
+                .order(EntriesOrder::RecentlyStarred),
+        ];
+
+        for query in queries {
+            let (sql, params) = query.to_sql(ENTRY_META_COLUMNS);
+            assert_eq!(
+                full_scans(&conn, &sql, params),
+                Vec::<String>::new(),
+                "{sql}"
+            );
+        }
+    }
+}
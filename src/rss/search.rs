@@ -0,0 +1,190 @@
+//! Full text search over entries' titles and text, with SQLite's FTS5.
+//!
+//! Entry HTML may be stored compressed, which FTS5 cannot read,
+//! so the index keeps its own copy of each entry's text with the tags taken out,
+//! written wherever entries are inserted. A trigger takes entries out of it when they are deleted.
+
+use super::compression::StoredText;
+use super::{EntryId, EntryMetadata};
+use anyhow::Result;
+use rusqlite::params;
+
+/// Creates the index and fills it with the entries already stored
+pub(super) fn create_index(tx: &rusqlite::Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE VIRTUAL TABLE entries_search USING fts5(entry_title, entry_text);
+
+        CREATE TRIGGER entries_search_delete AFTER DELETE ON entries
+        BEGIN
+            DELETE FROM entries_search WHERE rowid = old.id;
+        END;",
+    )?;
+
+    let mut statement = tx.prepare(
+        "SELECT entries.id, entries.title, entry_contents.content, entry_contents.description
+        FROM entries
+        LEFT JOIN entry_contents ON entry_contents.entry_id = entries.id",
+    )?;
+
+    let mut rows = statement.query([])?;
+
+    while let Some(row) = rows.next()? {
+        let entry_id: EntryId = row.get(0)?;
+        let title: Option<String> = row.get(1)?;
+        let content: Option<StoredText> = row.get(2)?;
+        let description: Option<StoredText> = row.get(3)?;
+
+        let html = content
+            .or(description)
+            .map(|stored| stored.into_string())
+            .transpose()?;
+
+        index_entry(tx, entry_id, title.as_deref(), html.as_deref())?;
+    }
+
+    Ok(())
+}
+
+/// Adds a newly inserted entry to the index.
+/// `html` is what is shown for the entry, its content or else its description
+pub(super) fn index_entry(
+    tx: &rusqlite::Transaction,
+    entry_id: EntryId,
+    title: Option<&str>,
+    html: Option<&str>,
+) -> Result<()> {
+    tx.prepare_cached(
+        "INSERT INTO entries_search (rowid, entry_title, entry_text) VALUES (?1, ?2, ?3)",
+    )?
+    .execute(params![entry_id, title, html.map(html_text)])?;
+
+    Ok(())
+}
+
+/// `html` without its tags, so searching does not match tag and attribute names
+fn html_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+
+    text
+}
+
+/// The FTS5 query matching entries with every word of `query`, or words starting with them.
+/// Each word is quoted, so what people type is never taken for FTS5 syntax
+fn match_expression(query: &str) -> Option<String> {
+    let words = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" "))
+    }
+}
+
+/// The entries in any feed matching `query`, best matches first
+pub fn search_entries(
+    conn: &rusqlite::Connection,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<EntryMetadata>> {
+    let Some(match_expression) = match_expression(query) else {
+        return Ok(vec![]);
+    };
+
+    let mut statement = conn.prepare_cached(&format!(
+        "SELECT {} FROM entries
+        JOIN entries_search ON entries_search.rowid = entries.id
+        WHERE entries_search MATCH ?1
+        ORDER BY rank
+        LIMIT ?2",
+        super::query::ENTRY_META_COLUMNS
+    ))?;
+
+    let entries = statement
+        .query_map(
+            params![match_expression, limit as i64],
+            super::query::entry_meta_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_searches_entries() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+
+        let feed_id = crate::rss::in_transaction(&mut conn, |tx| {
+            tx.execute(
+                "INSERT INTO feeds (title, feed_link, feed_kind) VALUES ('Feed', 'https://example.com/feed', 'RSS')",
+                [],
+            )?;
+            let feed_id = tx.last_insert_rowid().into();
+
+            let entry = |title: &str, content: &str| super::super::IncomingEntry {
+                title: Some(title.to_owned()),
+                author: None,
+                pub_date: None,
+                description: None,
+                content: Some(content.to_owned()),
+                link: Some(format!("https://example.com/{title}")),
+                resolved_link: None,
+                guid: None,
+            };
+
+            super::super::add_entries_to_feed(
+                tx,
+                feed_id,
+                &[
+                    entry("Compilers", "<p class=\"rust\">Writing a parser by hand</p>"),
+                    entry("Parsing", "<p>Parser combinators in Haskell</p>"),
+                ],
+                &super::super::IngestOptions {
+                    compress_content: true,
+                    ..Default::default()
+                },
+            )?;
+
+            Ok(feed_id)
+        })
+        .unwrap();
+
+        let titles = |query| {
+            search_entries(&conn, query, 10)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.title.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles("parser hand"), vec!["Compilers"]);
+        assert_eq!(titles("pars").len(), 2);
+        // tags are not searched, and quotes are not syntax
+        assert!(titles("rust").is_empty());
+        assert_eq!(titles("\"haskell"), vec!["Parsing"]);
+        assert!(titles("  ").is_empty());
+
+        crate::rss::delete_feed(&mut conn, feed_id).unwrap();
+        assert!(search_entries(&conn, "parser", 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+//! A small journal of UI state, saved every few seconds while reading,
+//! so a session that ends in a crash or a killed terminal can be picked back up.
+//!
+//! The journal is removed when russ exits normally,
+//! so finding one at startup means the last session did not.
+
+use crate::modes::{Mode, ReadMode};
+use crate::rss::{EntryId, FeedId};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// How often the journal is saved, if anything has changed
+pub(crate) const JOURNAL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SessionState {
+    pub(crate) feed_id: Option<FeedId>,
+    pub(crate) entry_id: Option<EntryId>,
+    /// whether the entry was open, rather than just selected in the entries list
+    pub(crate) reading_entry: bool,
+    pub(crate) entry_scroll_position: u16,
+    pub(crate) read_mode: ReadMode,
+    pub(crate) mode: Mode,
+    /// a feed link that was being typed in, but not yet subscribed to
+    pub(crate) feed_subscription_input: String,
+}
+
+/// The journal lives next to the database it is for,
+/// so sessions using different databases do not restore each other.
+/// In-memory databases have no journal, as they are gone by the next session.
+pub(crate) fn journal_path(database_path: &Path) -> Option<PathBuf> {
+    if crate::rss::is_in_memory(database_path) {
+        None
+    } else {
+        Some(database_path.with_extension("session.json"))
+    }
+}
+
+pub(crate) fn save(journal_path: &Path, state: &SessionState) -> Result<()> {
+    // write then rename, so a crash while saving does not leave a half-written journal
+    let tmp_path = journal_path.with_extension("json.tmp");
+
+    let mut file = std::fs::File::create(&tmp_path)
+        .with_context(|| format!("unable to create {}", tmp_path.display()))?;
+    file.write_all(serde_json::to_string(state)?.as_bytes())?;
+    file.sync_all()?;
+
+    std::fs::rename(&tmp_path, journal_path)
+        .with_context(|| format!("unable to save session to {}", journal_path.display()))?;
+
+    Ok(())
+}
+
+/// The state of the last session, if it did not exit normally
+pub(crate) fn load(journal_path: &Path) -> Result<Option<SessionState>> {
+    let journal = match std::fs::read_to_string(journal_path) {
+        Ok(journal) => journal,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(e).with_context(|| format!("unable to read {}", journal_path.display()))
+        }
+    };
+
+    let state = serde_json::from_str(&journal)
+        .with_context(|| format!("unable to parse session journal {}", journal_path.display()))?;
+
+    Ok(Some(state))
+}
+
+pub(crate) fn remove(journal_path: &Path) -> Result<()> {
+    match std::fs::remove_file(journal_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e).with_context(|| {
+            format!(
+                "unable to remove session journal {}",
+                journal_path.display()
+            )
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Asks on the terminal, before the TUI starts, whether to restore the previous session
+pub(crate) fn confirm_restore() -> Result<bool> {
+    print!("russ did not exit cleanly last time. Restore previous session? [y/N] ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_saves_and_loads_sessions() {
+        let dir = crate::util::TestDir::new("session");
+        let journal_path = journal_path(&dir.join("feeds.db")).unwrap();
+
+        assert_eq!(load(&journal_path).unwrap(), None);
+
+        let state = SessionState {
+            feed_id: Some(1.into()),
+            entry_id: Some(2.into()),
+            reading_entry: true,
+            entry_scroll_position: 3,
+            read_mode: ReadMode::ShowRead,
+            mode: Mode::Editing,
+            feed_subscription_input: "https://example.com/fe".to_string(),
+        };
+
+        save(&journal_path, &state).unwrap();
+        assert_eq!(load(&journal_path).unwrap(), Some(state));
+
+        remove(&journal_path).unwrap();
+        assert_eq!(load(&journal_path).unwrap(), None);
+    }
+}
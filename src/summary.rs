@@ -0,0 +1,176 @@
+//! Short summaries of long entries, to help decide what is worth reading in full.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// entries with fewer words than this are short enough to not need a summary
+const LONG_ENTRY_WORDS: usize = 300;
+
+const SUMMARY_SENTENCES: usize = 3;
+
+/// sentences shorter than this tend to be headings or captions, not content
+const MIN_SENTENCE_WORDS: usize = 6;
+
+const STOPWORDS: &[&str] = &[
+    "about", "after", "also", "been", "because", "before", "being", "could", "does", "each",
+    "even", "from", "have", "here", "into", "just", "like", "many", "more", "most", "much", "only",
+    "other", "over", "some", "such", "than", "that", "their", "them", "then", "there", "these",
+    "they", "this", "those", "very", "want", "were", "what", "when", "where", "which", "while",
+    "will", "with", "would", "your",
+];
+
+#[derive(Clone, Debug)]
+pub(crate) enum Summarizer {
+    /// picks the sentences made of the entry's most frequent words
+    Extractive,
+    /// runs a shell command with the entry's text on stdin, using its stdout as the summary
+    Command(String),
+}
+
+impl Summarizer {
+    pub(crate) fn summarize(&self, text: &str) -> Result<String> {
+        match self {
+            Summarizer::Extractive => Ok(summarize_extractively(text, SUMMARY_SENTENCES)),
+            Summarizer::Command(command) => summarize_with_command(command, text),
+        }
+    }
+}
+
+pub(crate) fn is_long(text: &str) -> bool {
+    text.split_whitespace().count() >= LONG_ENTRY_WORDS
+}
+
+fn summarize_extractively(text: &str, sentences_len: usize) -> String {
+    // entry text arrives already wrapped for display
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let sentences = split_sentences(&text);
+
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for word in sentences
+        .iter()
+        .flat_map(|sentence| significant_words(sentence))
+    {
+        *frequencies.entry(word).or_default() += 1;
+    }
+
+    let mut scored_sentences = sentences
+        .iter()
+        .enumerate()
+        .filter(|(_, sentence)| sentence.split_whitespace().count() >= MIN_SENTENCE_WORDS)
+        .filter_map(|(i, sentence)| {
+            let words = significant_words(sentence).collect::<Vec<_>>();
+
+            if words.is_empty() {
+                return None;
+            }
+
+            let score = words.iter().map(|word| frequencies[word]).sum::<usize>() as f64
+                / words.len() as f64;
+
+            Some((i, score))
+        })
+        .collect::<Vec<_>>();
+
+    scored_sentences.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    scored_sentences.truncate(sentences_len);
+    // read better in the order they were written
+    scored_sentences.sort_by_key(|(i, _)| *i);
+
+    scored_sentences
+        .into_iter()
+        .map(|(i, _)| sentences[i])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = vec![];
+    let mut sentence_start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if matches!(c, '.' | '!' | '?') && chars.peek().is_none_or(|(_, next)| next.is_whitespace())
+        {
+            let sentence = text[sentence_start..i + c.len_utf8()].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            sentence_start = i + c.len_utf8();
+        }
+    }
+
+    let rest = text[sentence_start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+
+    sentences
+}
+
+fn significant_words(sentence: &str) -> impl Iterator<Item = String> + '_ {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 3)
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+}
+
+fn summarize_with_command(command: &str, text: &str) -> Result<String> {
+    let mut child = crate::util::shell_command(command)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("unable to run summary command `{command}`"))?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let text = text.to_owned();
+    // write on another thread, so a command that writes before it
+    // has read all of its input does not deadlock with us
+    let writer = std::thread::spawn(move || match stdin.write_all(text.as_bytes()) {
+        // the command does not have to read the entry
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    });
+
+    let output = child.wait_with_output()?;
+
+    writer
+        .join()
+        .expect("unable to join summary command writer thread")
+        .context("unable to write entry to summary command")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "summary command `{command}` failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_summarizes_extractively() {
+        let text = "Rust programs manage memory through ownership and borrowing rules.
+            The weather was nice that day, so we went outside for a walk.
+            Ownership means every value in Rust programs has exactly one owner.
+            Short heading.
+            Borrowing lets Rust programs use values without taking ownership of memory.";
+
+        assert_eq!(
+            summarize_extractively(text, 2),
+            "Rust programs manage memory through ownership and borrowing rules. \
+            Borrowing lets Rust programs use values without taking ownership of memory."
+        );
+
+        assert!(!is_long(text));
+    }
+}
@@ -0,0 +1,190 @@
+//! Templates for how entries are listed, like `{unread_marker} {date:%m-%d} {title} ({author})`,
+//! so the metadata that matters most to you fits in a narrow entries column.
+//!
+//! Fields are written in braces, and `{{` and `}}` are literal braces.
+//! Fields an entry does not have, like an author, are left empty.
+
+use crate::rss::EntryMetadata;
+use anyhow::{anyhow, bail, Result};
+
+/// Lists entries by their titles alone
+pub const DEFAULT_ENTRY_TEMPLATE: &str = "{title}";
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Clone, Debug, PartialEq)]
+enum Field {
+    /// `•` for unread entries, and a space for read ones
+    UnreadMarker,
+    /// when the entry was published, or inserted if it has no publication date,
+    /// in a `strftime` format
+    Date(String),
+    Title,
+    Author,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field(Field),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryTemplate {
+    segments: Vec<Segment>,
+}
+
+impl Default for EntryTemplate {
+    fn default() -> Self {
+        DEFAULT_ENTRY_TEMPLATE
+            .parse()
+            .expect("the default entry template must parse")
+    }
+}
+
+impl std::str::FromStr for EntryTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = vec![];
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut field = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => field.push(c),
+                            None => bail!("unclosed {{ in entry template {s}"),
+                        }
+                    }
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(parse_field(&field)?));
+                }
+                '}' => bail!("unopened }} in entry template {s}, write }}}} for a literal brace"),
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(EntryTemplate { segments })
+    }
+}
+
+fn parse_field(field: &str) -> Result<Field> {
+    let (name, format) = match field.split_once(':') {
+        Some((name, format)) => (name, Some(format)),
+        None => (field, None),
+    };
+
+    let field = match (name, format) {
+        ("unread_marker", None) => Field::UnreadMarker,
+        ("title", None) => Field::Title,
+        ("author", None) => Field::Author,
+        ("date", format) => {
+            let format = format.unwrap_or(DEFAULT_DATE_FORMAT);
+
+            // chrono panics when displaying a date with an invalid format,
+            // so catch it here instead
+            if chrono::format::StrftimeItems::new(format)
+                .any(|item| matches!(item, chrono::format::Item::Error))
+            {
+                bail!("{format} is not a valid date format");
+            }
+
+            Field::Date(format.to_string())
+        }
+        ("unread_marker" | "title" | "author", Some(_)) => {
+            bail!("only {{date}} takes a format, not {{{name}}}")
+        }
+        _ => {
+            return Err(anyhow!(
+                "{{{name}}} is not one of {{unread_marker}}, {{date}}, {{title}}, or {{author}}"
+            ))
+        }
+    };
+
+    Ok(field)
+}
+
+impl EntryTemplate {
+    pub fn render(&self, entry: &EntryMetadata) -> String {
+        let mut rendered = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => rendered.push_str(literal),
+                Segment::Field(Field::UnreadMarker) => {
+                    rendered.push(if entry.read_at.is_none() { '•' } else { ' ' })
+                }
+                Segment::Field(Field::Date(format)) => rendered.push_str(
+                    &entry
+                        .pub_date
+                        .unwrap_or(entry.inserted_at)
+                        .format(format)
+                        .to_string(),
+                ),
+                Segment::Field(Field::Title) => rendered.push_str(&entry.display_title()),
+                Segment::Field(Field::Author) => {
+                    rendered.push_str(entry.author.as_deref().unwrap_or_default())
+                }
+            }
+        }
+
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn it_renders_entries_with_templates() {
+        let entry = EntryMetadata {
+            id: 1.into(),
+            feed_id: 1.into(),
+            title: Some("Hello".to_string()),
+            author: Some("Ann".to_string()),
+            pub_date: Some(chrono::Utc.with_ymd_and_hms(2024, 3, 9, 12, 0, 0).unwrap()),
+            claimed_pub_date: None,
+            link: None,
+            resolved_link: None,
+            read_at: None,
+            starred_at: None,
+            inserted_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            guid: None,
+        };
+
+        let template: EntryTemplate = "{unread_marker} {date:%m-%d} {title} ({author}) {{x}}"
+            .parse()
+            .unwrap();
+        assert_eq!(template.render(&entry), "• 03-09 Hello (Ann) {x}");
+
+        assert_eq!(EntryTemplate::default().render(&entry), "Hello");
+
+        assert!("{title".parse::<EntryTemplate>().is_err());
+        assert!("{summary}".parse::<EntryTemplate>().is_err());
+        assert!("{title:%Y}".parse::<EntryTemplate>().is_err());
+        assert!("{date:%Q}".parse::<EntryTemplate>().is_err());
+    }
+}
@@ -1,43 +1,268 @@
 //! How the UI is rendered, with the Ratatui library.
 
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Rect};
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::{Span, Text};
-use ratatui::widgets::{Block, Borders, LineGauge, List, ListItem, Paragraph, Wrap};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{
+    Block, Borders, Clear, LineGauge, List, ListItem, ListState, Paragraph, Scrollbar,
+    ScrollbarOrientation, ScrollbarState, Wrap,
+};
 use ratatui::Frame;
+use std::ops::Range;
 use std::rc::Rc;
+use unicode_width::UnicodeWidthStr;
 
-use crate::app::AppImpl;
+use crate::app::{AppImpl, FeedsListItem, ListDensity};
+use crate::colors::Theme;
+use crate::error::FetchError;
 use crate::modes::{Mode, ReadMode, Selected};
-use crate::rss::EntryMetadata;
+use crate::rss::{EntryInfo, EntryMetadata, Feed};
+use crate::util;
 
-const PINK: Color = Color::Rgb(255, 150, 167);
+/// Terminals at least this many columns wide get a third column, previewing the selected entry
+pub const WIDE_TERMINAL_WIDTH: u16 = 160;
+
+pub fn predraw(f: &Frame, shows_preview: bool) -> Rc<[Rect]> {
+    let constraints = if shows_preview {
+        vec![
+            Constraint::Percentage(20),
+            Constraint::Percentage(30),
+            Constraint::Percentage(50),
+        ]
+    } else {
+        vec![Constraint::Percentage(30), Constraint::Percentage(70)]
+    };
 
-pub fn predraw(f: &Frame) -> Rc<[Rect]> {
     Layout::default()
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+        .constraints(constraints)
         .direction(Direction::Horizontal)
         .split(f.size())
 }
 
-pub fn draw(f: &mut Frame, chunks: Rc<[Rect]>, app: &mut AppImpl) {
+/// Takes the right half of the last column for the split entry, if there is one
+pub fn split_off_entry(chunks: Rc<[Rect]>, splits_entry: bool) -> (Rc<[Rect]>, Option<Rect>) {
+    if !splits_entry {
+        return (chunks, None);
+    }
+
+    let halves = Layout::default()
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .direction(Direction::Horizontal)
+        .split(chunks[chunks.len() - 1]);
+
+    let mut chunks = chunks.to_vec();
+    let last = chunks.len() - 1;
+    chunks[last] = halves[0];
+
+    (chunks.into(), Some(halves[1]))
+}
+
+pub fn draw(f: &mut Frame, chunks: Rc<[Rect]>, split_entry_area: Option<Rect>, app: &mut AppImpl) {
+    if app.is_locked() {
+        draw_lock_screen(f);
+        return;
+    }
+
     draw_info_column(f, chunks[0], app);
 
-    match &app.selected {
-        Selected::Feeds | Selected::Entries => {
-            draw_entries(f, chunks[1], app);
+    // with three columns, the entries stay listed while reading
+    if chunks.len() == 3 {
+        draw_entries(f, chunks[1], app);
+
+        match &app.selected {
+            Selected::Entry(_entry_meta) => draw_entry(f, chunks[2], app),
+            _ => draw_preview(f, chunks[2], app),
         }
-        Selected::Entry(_entry_meta) => {
-            draw_entry(f, chunks[1], app);
+    } else {
+        match &app.selected {
+            Selected::Feeds | Selected::Entries => {
+                draw_entries(f, chunks[1], app);
+            }
+            Selected::Entry(_entry_meta) => {
+                draw_entry(f, chunks[1], app);
+            }
+            Selected::None => draw_entries(f, chunks[1], app),
         }
-        Selected::None => draw_entries(f, chunks[1], app),
+    }
+
+    if let Some(area) = split_entry_area {
+        draw_split_entry(f, area, app);
+    }
+
+    if let Some(entry_info) = &app.entry_info {
+        draw_entry_info_popup(f, entry_info, app.theme);
+    }
+
+    if app.directory_results.is_some() {
+        draw_directory_popup(f, app);
+    }
+
+    if app.shows_visual_alert() {
+        f.render_widget(
+            Block::default().style(Style::default().add_modifier(Modifier::REVERSED)),
+            f.size(),
+        );
     }
 }
 
+/// Covers everything, so nothing can be read over your shoulder while you are away
+fn draw_lock_screen(f: &mut Frame) {
+    f.render_widget(Clear, f.size());
+
+    let paragraph = Paragraph::new(Text::from("Locked after being idle. Press any key"))
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+
+    f.render_widget(paragraph, centered_rect(f.size(), 100, 10));
+}
+
+fn draw_entry_info_popup(f: &mut Frame, entry_info: &EntryInfo, theme: Theme) {
+    let entry_meta = &entry_info.meta;
+    let mut text = String::new();
+
+    let mut push_field = |name: &str, value: Option<String>| {
+        text.push_str(name);
+        text.push_str(": ");
+        text.push_str(value.as_deref().unwrap_or("-"));
+        text.push('\n');
+    };
+
+    push_field("Title", Some(entry_meta.display_title()));
+    push_field("Author", entry_meta.author.clone());
+    push_field("Guid", entry_meta.guid.clone());
+    push_field("Link", entry_meta.link.clone());
+    push_field("Resolved link", entry_meta.resolved_link.clone());
+    push_field("Feed", entry_info.feed_title.clone());
+    push_field("Feed link", entry_info.feed_link.clone());
+    push_field(
+        "Pub. date",
+        entry_meta.pub_date.map(|date| date.to_string()),
+    );
+    push_field(
+        "Claimed pub. date",
+        entry_meta.claimed_pub_date.map(|date| date.to_string()),
+    );
+    push_field("Inserted at", Some(entry_meta.inserted_at.to_string()));
+    push_field("Updated at", Some(entry_meta.updated_at.to_string()));
+    push_field("Read at", entry_meta.read_at.map(|date| date.to_string()));
+    push_field(
+        "Starred at",
+        entry_meta.starred_at.map(|date| date.to_string()),
+    );
+    push_field(
+        "Content length",
+        entry_info.content_len.map(|len| format!("{len} bytes")),
+    );
+    push_field(
+        "Description length",
+        entry_info.description_len.map(|len| format!("{len} bytes")),
+    );
+
+    let area = centered_rect(f.size(), 70, 60);
+
+    let block = bordered_block(theme.border).title(Span::styled(
+        "Entry info - press 'I' to close",
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD),
+    ));
+
+    let paragraph = Paragraph::new(Text::from(text))
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn draw_directory_popup(f: &mut Frame, app: &mut AppImpl) {
+    let Some(directory_results) = &mut app.directory_results else {
+        return;
+    };
+
+    let items: Vec<ListItem> = directory_results
+        .items
+        .iter()
+        .map(|feed| {
+            ListItem::new(Line::from(vec![
+                Span::raw(feed.title),
+                Span::styled(
+                    format!(" - {}", feed.description),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let title = if items.is_empty() {
+        "No popular feeds match - keep typing, or press tab to close".to_string()
+    } else {
+        format!(
+            "{} popular feeds - enter to subscribe, tab to close",
+            items.len()
+        )
+    };
+
+    let list = List::new(items)
+        .block(
+            bordered_block(app.theme.border).title(Span::styled(
+                title,
+                Style::default()
+                    .fg(app.theme.title)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(app.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let area = centered_rect(f.size(), 70, 60);
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut directory_results.state);
+}
+
+/// A rect in the middle of `area`, taking up the given percentages of its width and height
+fn centered_rect(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::default()
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .direction(Direction::Vertical)
+        .split(area);
+
+    Layout::default()
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .direction(Direction::Horizontal)
+        .split(vertical[1])[1]
+}
+
+/// A block with a border all the way around, in `border_color`
+fn bordered_block(border_color: Color) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border_color))
+}
+
 fn draw_info_column(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     let mut constraints = match &app.mode {
         Mode::Normal => vec![Constraint::Percentage(70), Constraint::Percentage(30)],
-        Mode::Editing => vec![
+        Mode::Editing
+        | Mode::Searching
+        | Mode::DeletingFeed
+        | Mode::MovingFeed
+        | Mode::SwitchingDatabase
+        | Mode::EditingFeedHttpSettings
+        | Mode::EditingFeedTitle => vec![
             Constraint::Percentage(60),
             Constraint::Percentage(20),
             Constraint::Percentage(10),
@@ -59,15 +284,15 @@ fn draw_info_column(f: &mut Frame, area: Rect, app: &mut AppImpl) {
 
         // INFO
         match &app.selected {
-            Selected::Entry(entry) => draw_entry_info(f, chunks[1], entry),
+            Selected::Entry(entry) => draw_entry_info(f, chunks[1], entry, app),
             Selected::Entries => {
                 if let Some(entry_meta) = &app.current_entry_meta {
-                    draw_entry_info(f, chunks[1], entry_meta);
+                    draw_entry_info(f, chunks[1], entry_meta, app);
                 } else {
                     draw_feed_info(f, chunks[1], app);
                 }
             }
-            Selected::None => draw_first_run_helper(f, chunks[1]),
+            Selected::None => draw_first_run_helper(f, chunks[1], app.theme),
             _ => {
                 if app.current_feed.is_some() {
                     draw_feed_info(f, chunks[1], app);
@@ -83,6 +308,48 @@ fn draw_info_column(f: &mut Frame, area: Rect, app: &mut AppImpl) {
             (Mode::Editing, false) => {
                 draw_new_feed_input(f, chunks[2], app);
             }
+            (Mode::Searching, true) => {
+                draw_search_input(f, chunks[2], app);
+                draw_help(f, chunks[3], app);
+            }
+            (Mode::Searching, false) => {
+                draw_search_input(f, chunks[2], app);
+            }
+            (Mode::DeletingFeed, true) => {
+                draw_delete_feed_prompt(f, chunks[2], app);
+                draw_help(f, chunks[3], app);
+            }
+            (Mode::DeletingFeed, false) => {
+                draw_delete_feed_prompt(f, chunks[2], app);
+            }
+            (Mode::MovingFeed, true) => {
+                draw_move_feed_prompt(f, chunks[2], app);
+                draw_help(f, chunks[3], app);
+            }
+            (Mode::MovingFeed, false) => {
+                draw_move_feed_prompt(f, chunks[2], app);
+            }
+            (Mode::SwitchingDatabase, true) => {
+                draw_database_path_input(f, chunks[2], app);
+                draw_help(f, chunks[3], app);
+            }
+            (Mode::SwitchingDatabase, false) => {
+                draw_database_path_input(f, chunks[2], app);
+            }
+            (Mode::EditingFeedHttpSettings, true) => {
+                draw_feed_http_settings_input(f, chunks[2], app);
+                draw_help(f, chunks[3], app);
+            }
+            (Mode::EditingFeedHttpSettings, false) => {
+                draw_feed_http_settings_input(f, chunks[2], app);
+            }
+            (Mode::EditingFeedTitle, true) => {
+                draw_feed_title_input(f, chunks[2], app);
+                draw_help(f, chunks[3], app);
+            }
+            (Mode::EditingFeedTitle, false) => {
+                draw_feed_title_input(f, chunks[2], app);
+            }
             (_, true) => {
                 draw_help(f, chunks[2], app);
             }
@@ -91,12 +358,15 @@ fn draw_info_column(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     }
 }
 
-fn draw_first_run_helper(f: &mut Frame, area: Rect) {
-    let text = "Press 'i', then enter an RSS/Atom feed URL, then hit `Enter`!";
+fn draw_first_run_helper(f: &mut Frame, area: Rect, theme: Theme) {
+    let text = "Press 'i', then enter an RSS/Atom feed URL, then hit `Enter`! \
+                Or press `Tab` to search some popular feeds.";
 
-    let block = Block::default().borders(Borders::ALL).title(Span::styled(
+    let block = bordered_block(theme.border).title(Span::styled(
         "TO SUBSCRIBE TO YOUR FIRST FEED",
-        Style::default().fg(PINK).add_modifier(Modifier::BOLD),
+        Style::default()
+            .fg(theme.highlight)
+            .add_modifier(Modifier::BOLD),
     ));
 
     let paragraph = Paragraph::new(Text::from(text))
@@ -106,13 +376,11 @@ fn draw_first_run_helper(f: &mut Frame, area: Rect) {
     f.render_widget(paragraph, area);
 }
 
-fn draw_entry_info(f: &mut Frame, area: Rect, entry_meta: &EntryMetadata) {
+fn draw_entry_info(f: &mut Frame, area: Rect, entry_meta: &EntryMetadata, app: &AppImpl) {
     let mut text = String::new();
-    if let Some(item) = &entry_meta.title {
-        text.push_str("Title: ");
-        text.push_str(item.to_string().as_str());
-        text.push('\n');
-    };
+    text.push_str("Title: ");
+    text.push_str(&entry_meta.display_title());
+    text.push('\n');
 
     if let Some(item) = &entry_meta.link {
         text.push_str("Link: ");
@@ -120,6 +388,12 @@ fn draw_entry_info(f: &mut Frame, area: Rect, entry_meta: &EntryMetadata) {
         text.push('\n');
     }
 
+    if let Some(item) = &entry_meta.resolved_link {
+        text.push_str("Resolved link: ");
+        text.push_str(item);
+        text.push('\n');
+    }
+
     if let Some(pub_date) = &entry_meta.pub_date {
         text.push_str("Pub. date: ");
         text.push_str(pub_date.to_string().as_str());
@@ -138,10 +412,26 @@ fn draw_entry_info(f: &mut Frame, area: Rect, entry_meta: &EntryMetadata) {
         text.push('\n');
     }
 
-    let block = Block::default().borders(Borders::ALL).title(Span::styled(
+    if let Some(summary) = app.entry_summaries.get(&entry_meta.id) {
+        text.push_str("Summary: ");
+        text.push_str(summary.as_deref().unwrap_or("summarizing..."));
+        text.push('\n');
+    }
+
+    // only for the entry being read, as that is what they were found for
+    if matches!(&app.selected, Selected::Entry(reading) if reading.id == entry_meta.id)
+        && !app.related_entries.is_empty()
+    {
+        text.push_str("Related:\n");
+        for (i, related) in app.related_entries.iter().enumerate() {
+            text.push_str(&format!("{} - {}\n", i + 1, related.display_title()));
+        }
+    }
+
+    let block = bordered_block(app.theme.border).title(Span::styled(
         "Info",
         Style::default()
-            .fg(Color::Cyan)
+            .fg(app.theme.title)
             .add_modifier(Modifier::BOLD),
     ));
 
@@ -152,36 +442,422 @@ fn draw_entry_info(f: &mut Frame, area: Rect, entry_meta: &EntryMetadata) {
     f.render_widget(paragraph, area);
 }
 
+/// How many columns the rows of a list in `area` have,
+/// inside its borders and after the highlight symbol shown while the list is selected
+fn list_width(area: Rect, is_selected: bool) -> usize {
+    usize::from(area.width.saturating_sub(if is_selected { 4 } else { 2 }))
+}
+
+/// The columns a feed's title can take up in a row `list_width` wide
+fn feed_title_width(app: &AppImpl, feed: &Feed, list_width: usize) -> usize {
+    let indent_width = if app.group_feeds_by_domain { 2 } else { 0 };
+    let unread_entries_count_width = app
+        .unread_entries_counts
+        .get(&feed.id)
+        .map_or(0, |count| format!(" ({count})").len());
+    let new_entries_count_width = app
+        .new_entries_counts
+        .get(&feed.id)
+        .map_or(0, |count| format!(" +{count}").len());
+
+    list_width.saturating_sub(indent_width + unread_entries_count_width + new_entries_count_width)
+}
+
+/// The columns an entry's title can take up in a row `list_width` wide
+fn entry_title_width(app: &AppImpl, entry: &EntryMetadata, list_width: usize) -> usize {
+    let new_marker_width = if app.is_refreshed_entry(entry) { 4 } else { 0 };
+    let queue_position_width = app
+        .reading_queue
+        .iter()
+        .position(|queued_entry| queued_entry.id == entry.id)
+        .map_or(0, |position| format!("[{}] ", position + 1).len());
+    let star_width = if entry.starred_at.is_some() { 2 } else { 0 };
+    let future_marker_width = if entry.is_future_dated() {
+        FUTURE_MARKER.len()
+    } else {
+        0
+    };
+    let feed_title_width = if app.lists_entries_across_feeds() {
+        entry_feed_title(app, entry).map_or(0, |feed_title| feed_title.width() + 2)
+    } else {
+        0
+    };
+
+    list_width.saturating_sub(
+        new_marker_width
+            + future_marker_width
+            + queue_position_width
+            + star_width
+            + feed_title_width,
+    )
+}
+
+/// Marks entries dated in the future, which sort above entries that really are newer
+const FUTURE_MARKER: &str = "FUTURE ";
+
+/// A feed's domain and when it was last refreshed, for list densities that show them
+fn feed_details(feed: &Feed) -> String {
+    let refreshed = match (feed.dead_at, feed.refreshed_at) {
+        (Some(dead_at), _) => format!("dead since {}", dead_at.format("%Y-%m-%d")),
+        (None, _) if feed.blocked_by.is_some() => "blocked by bot protection".to_string(),
+        (None, Some(refreshed_at)) => {
+            format!("refreshed {}", refreshed_at.format("%Y-%m-%d %H:%M"))
+        }
+        (None, None) => "never refreshed".to_string(),
+    };
+
+    format!("{} · {refreshed}", util::feed_domain(feed))
+}
+
+/// When an entry was published and who by, for list densities that show them
+fn entry_details(entry: &EntryMetadata) -> String {
+    let date = entry
+        .pub_date
+        .unwrap_or(entry.inserted_at)
+        .format("%Y-%m-%d")
+        .to_string();
+
+    match &entry.author {
+        Some(author) => format!("{date} · {author}"),
+        None => date,
+    }
+}
+
+/// The columns left for a title `title_width` wide, and whether its details go beside it,
+/// which they do with the normal density if that leaves the title at least half of its room
+fn fit_details(density: ListDensity, title_width: usize, details: &str) -> (usize, bool) {
+    let beside_title_width = title_width.saturating_sub(details.width() + 2);
+
+    if matches!(density, ListDensity::Normal | ListDensity::Table)
+        && beside_title_width >= title_width / 2
+    {
+        (beside_title_width, true)
+    } else {
+        (title_width, false)
+    }
+}
+
+/// How wide the date and author columns of the table density are
+const TABLE_DATE_WIDTH: usize = 10;
+const TABLE_AUTHOR_WIDTH: usize = 20;
+
+/// The narrowest the title column of the table density gets
+/// before the author column, and then the date column, are left out to make room
+const TABLE_MIN_TITLE_WIDTH: usize = 24;
+
+/// An entry's date and read marker, which go before its title in the table density,
+/// its author, which goes after, if there is room for it,
+/// and the columns left for its title in a row `title_width` wide
+fn entry_table_columns(
+    entry: &EntryMetadata,
+    title_width: usize,
+) -> (String, Option<String>, usize) {
+    let marker = if entry.read_at.is_some() { ' ' } else { '•' };
+    // a space between each column
+    let date_width = TABLE_DATE_WIDTH + 1;
+    let marker_width = 2;
+    let author_width = TABLE_AUTHOR_WIDTH + 2;
+
+    let shows_author = title_width.saturating_sub(date_width + marker_width + author_width)
+        >= TABLE_MIN_TITLE_WIDTH;
+    let shows_date = title_width.saturating_sub(date_width + marker_width) >= TABLE_MIN_TITLE_WIDTH;
+
+    let mut before_title = String::new();
+    let mut columns_width = marker_width;
+
+    if shows_date {
+        let date = entry
+            .pub_date
+            .unwrap_or(entry.inserted_at)
+            .format("%Y-%m-%d");
+        before_title.push_str(&format!("{date:TABLE_DATE_WIDTH$} "));
+        columns_width += date_width;
+    }
+    before_title.push(marker);
+    before_title.push(' ');
+
+    let author = if shows_author {
+        columns_width += author_width;
+        Some(format!(
+            "  {}",
+            util::scroll_to_width(
+                entry.author.as_deref().unwrap_or_default(),
+                TABLE_AUTHOR_WIDTH,
+                0
+            )
+        ))
+    } else {
+        None
+    };
+
+    (
+        before_title,
+        author,
+        title_width.saturating_sub(columns_width),
+    )
+}
+
+/// A list row, with its details under it with the comfortable density
+fn list_item<'a>(
+    mut line: Vec<Span<'a>>,
+    details: String,
+    details_beside: bool,
+    density: ListDensity,
+    list_width: usize,
+) -> ListItem<'a> {
+    let details_style = Style::default().fg(Color::DarkGray);
+
+    if details_beside {
+        line.push(Span::styled(format!("  {details}"), details_style));
+    }
+
+    if density == ListDensity::Comfortable {
+        ListItem::new(Text::from(vec![
+            Line::from(line),
+            Line::from(Span::styled(
+                util::scroll_to_width(&details, list_width, 0),
+                details_style,
+            )),
+        ]))
+    } else {
+        ListItem::new(Line::from(line))
+    }
+}
+
+/// How many rows each feed or entry takes up in their lists
+fn list_item_height(density: ListDensity) -> usize {
+    if density == ListDensity::Comfortable {
+        2
+    } else {
+        1
+    }
+}
+
+/// How many rows of a list in `area` there are, inside its borders
+fn list_height(area: Rect) -> usize {
+    usize::from(area.height.saturating_sub(2))
+}
+
+/// Where the selected item is in a list of `len` items, like `13/312`, for the list's title
+fn list_position(state: &ListState, len: usize) -> Option<String> {
+    state
+        .selected()
+        .filter(|selected| *selected < len)
+        .map(|selected| format!("{}/{len}", selected + 1))
+}
+
+/// Renders `list`, which is only the `window` of a list of `len` items,
+/// with the selection moved into the window, and a scrollbar on its border if the list does not all fit
+fn render_list_window(
+    f: &mut Frame,
+    list: List,
+    area: Rect,
+    selected: Option<usize>,
+    window: Range<usize>,
+    len: usize,
+) {
+    let mut state = ListState::default().with_selected(
+        selected
+            .filter(|selected| window.contains(selected))
+            .map(|selected| selected - window.start),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+
+    if window.len() < len && list_height(area) > 0 {
+        let mut scrollbar_state = ScrollbarState::new(len - window.len() + 1)
+            .position(window.start)
+            .viewport_content_length(window.len());
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(&Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+}
+
 fn draw_feeds(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let is_selected = matches!(app.selected, Selected::Feeds);
+    let list_width = list_width(area, is_selected);
+    let selected_index = app.feeds.state.selected().filter(|_| is_selected);
+
+    let item_height = list_item_height(app.list_density);
+    let window = util::visible_window(
+        &mut app.feeds.state,
+        app.feeds.items.len(),
+        list_height(area),
+        |i| match app.feeds.items[i] {
+            FeedsListItem::Group { .. } => 1,
+            FeedsListItem::Feed(_) => item_height,
+        },
+    );
+
+    // so scrolling back starts moving right away, even after scrolling too far
+    if let Some(FeedsListItem::Feed(feed)) = selected_index.and_then(|i| app.feeds.items.get(i)) {
+        let (title_width, _) = fit_details(
+            app.list_density,
+            feed_title_width(app, feed, list_width),
+            &feed_details(feed),
+        );
+        let max_offset =
+            util::max_scroll_offset(feed.display_title().unwrap_or("No title"), title_width);
+        app.title_scroll_offset = app.title_scroll_offset.min(max_offset);
+    }
+
+    // only the feeds that fit are rendered
     let feeds = app
         .feeds
         .items
         .iter()
-        .flat_map(|feed| feed.title.as_ref())
-        .map(Span::raw)
-        .map(ListItem::new)
+        .enumerate()
+        .skip(window.start)
+        .take(window.len())
+        .map(|(i, item)| match item {
+            FeedsListItem::Feed(feed) => {
+                let offset = if selected_index == Some(i) {
+                    app.title_scroll_offset
+                } else {
+                    0
+                };
+
+                let indent = if app.group_feeds_by_domain { "  " } else { "" };
+                let details = feed_details(feed);
+
+                let (title_width, details_beside) = fit_details(
+                    app.list_density,
+                    feed_title_width(app, feed, list_width),
+                    &details,
+                );
+
+                let title = util::scroll_to_width(
+                    feed.display_title().unwrap_or("No title"),
+                    title_width,
+                    offset,
+                );
+
+                let mut line = vec![Span::raw(indent)];
+
+                let unread_entries_count = app.unread_entries_counts.get(&feed.id);
+
+                if feed.dead_at.is_some() {
+                    line.push(Span::styled(
+                        title,
+                        Style::default()
+                            .fg(Color::DarkGray)
+                            .add_modifier(Modifier::CROSSED_OUT),
+                    ));
+                } else if unread_entries_count.is_none() {
+                    // nothing to read here
+                    line.push(Span::styled(title, Style::default().fg(Color::DarkGray)));
+                } else {
+                    line.push(Span::raw(title));
+                }
+
+                if let Some(unread_entries_count) = unread_entries_count {
+                    line.push(Span::raw(format!(" ({unread_entries_count})")));
+                }
+
+                if let Some(new_entries_count) = app.new_entries_counts.get(&feed.id) {
+                    line.push(Span::styled(
+                        format!(" +{new_entries_count}"),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+
+                list_item(
+                    line,
+                    // under the title, the details line up with it
+                    if details_beside {
+                        details
+                    } else {
+                        format!("{indent}{details}")
+                    },
+                    details_beside,
+                    app.list_density,
+                    list_width,
+                )
+            }
+            FeedsListItem::Group {
+                name,
+                feeds_len,
+                collapsed,
+            } => {
+                let marker = if *collapsed { "▸" } else { "▾" };
+
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{marker} {name}"),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!(" ({feeds_len})"),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            }
+        })
         .collect::<Vec<ListItem>>();
 
-    let default_title = String::from("Feeds");
+    let mut default_title = String::from("Feeds");
+    if app.feed_sort != crate::rss::FeedSort::Title {
+        default_title.push_str(&format!(" — by {}", app.feed_sort));
+    }
+    if app.group_feeds_by_domain {
+        default_title.push_str(" — grouped");
+    }
+    if app.offline {
+        default_title.push_str(" (offline)");
+    }
+    match app.refresh_warnings_len() {
+        0 => (),
+        1 => default_title.push_str(" — 1 warning"),
+        warnings_len => default_title.push_str(&format!(" — {warnings_len} warnings")),
+    }
+    if let Some(position) = list_position(&app.feeds.state, app.feeds.items.len()) {
+        default_title.push_str(" — ");
+        default_title.push_str(&position);
+    }
     let title = app.flash.as_ref().unwrap_or(&default_title);
 
+    // warnings are yellow, rather than red like errors, which get a whole pane
+    let title_color = if app.flash.is_some() && app.flash_is_warning {
+        Color::Yellow
+    } else {
+        app.theme.title
+    };
+
     let feeds = List::new(feeds).block(
-        Block::default().borders(Borders::ALL).title(Span::styled(
+        bordered_block(app.theme.border).title(Span::styled(
             title,
             Style::default()
-                .fg(Color::Cyan)
+                .fg(title_color)
                 .add_modifier(Modifier::BOLD),
         )),
     );
 
     let feeds = match app.selected {
         Selected::Feeds => feeds
-            .highlight_style(Style::default().fg(PINK).add_modifier(Modifier::BOLD))
+            .highlight_style(
+                Style::default()
+                    .fg(app.theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
             .highlight_symbol("> "),
         _ => feeds,
     };
 
-    f.render_stateful_widget(feeds, area, &mut app.feeds.state);
+    render_list_window(
+        f,
+        feeds,
+        area,
+        app.feeds.state.selected(),
+        window,
+        app.feeds.items.len(),
+    );
 }
 
 fn draw_feed_info(f: &mut Frame, area: Rect, app: &mut AppImpl) {
@@ -189,9 +865,41 @@ fn draw_feed_info(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     if let Some(item) = app
         .current_feed
         .as_ref()
+        .and_then(|feed| feed.display_title())
+    {
+        text.push_str("Title (E): ");
+        text.push_str(item);
+        text.push('\n');
+    }
+
+    // the feed's own title, when it is shown with another
+    if let Some(item) = app
+        .current_feed
+        .as_ref()
+        .filter(|feed| feed.custom_title.is_some())
         .and_then(|feed| feed.title.as_ref())
     {
-        text.push_str("Title: ");
+        text.push_str("Original title: ");
+        text.push_str(item);
+        text.push('\n');
+    }
+
+    if let Some(item) = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.folder.as_ref())
+    {
+        text.push_str("Folder: ");
+        text.push_str(item);
+        text.push('\n');
+    }
+
+    if let Some(item) = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.description.as_ref())
+    {
+        text.push_str("Description: ");
         text.push_str(item);
         text.push('\n');
     }
@@ -201,7 +909,7 @@ fn draw_feed_info(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         .as_ref()
         .and_then(|feed| feed.link.as_ref())
     {
-        text.push_str("Link: ");
+        text.push_str("Site (o/c): ");
         text.push_str(item);
         text.push('\n');
     }
@@ -211,7 +919,7 @@ fn draw_feed_info(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         .as_ref()
         .and_then(|feed| feed.feed_link.as_ref())
     {
-        text.push_str("Feed link: ");
+        text.push_str("Feed XML (O/C): ");
         text.push_str(item);
         text.push('\n');
     }
@@ -236,24 +944,133 @@ fn draw_feed_info(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         text.push('\n');
     }
 
+    if let Some(last_build_date) = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.last_build_date)
+    {
+        text.push_str(&format!("Feed last built at: {last_build_date}\n"));
+    }
+
+    if let Some(item) = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.language.as_ref())
+    {
+        text.push_str("Language: ");
+        text.push_str(item);
+        text.push('\n');
+    }
+
     match app.read_mode {
         ReadMode::ShowUnread => text.push_str("Unread entries: "),
         ReadMode::ShowRead => text.push_str("Read entries: "),
-        ReadMode::All => unreachable!("ReadMode::All should never be possible from the UI!"),
+        ReadMode::All => text.push_str("Entries: "),
     }
     text.push_str(app.entries.items.len().to_string().as_str());
     text.push('\n');
 
-    if let Some(feed_kind) = app.current_feed.as_ref().map(|feed| feed.feed_kind) {
+    if let Some(feed) = app
+        .current_feed
+        .as_ref()
+        .filter(|feed| feed.skipped_entries > 0)
+    {
+        text.push_str(&format!(
+            "Last refresh left out {} entries over the limit of {}\n",
+            feed.skipped_entries,
+            feed.max_new_entries
+                .map(|max_new_entries| max_new_entries.to_string())
+                .unwrap_or_else(|| "--max-new-entries".to_string())
+        ));
+    }
+
+    if let Some(mark_read_after_days) = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.mark_read_after_days)
+    {
+        text.push_str(&format!(
+            "Refreshes mark entries older than {mark_read_after_days} days as read\n"
+        ));
+    }
+
+    if let Some(retention) = app.current_feed.as_ref().and_then(|feed| feed.retention) {
+        match retention {
+            crate::rss::Retention::Entries(entries) => text.push_str(&format!(
+                "Refreshes delete read entries past the newest {entries}\n"
+            )),
+            crate::rss::Retention::Days(days) => text.push_str(&format!(
+                "Refreshes delete read entries older than {days} days\n"
+            )),
+        }
+    }
+
+    if let Some(feed) = &app.current_feed {
+        let new_entries_count = app.new_entries_counts.get(&feed.id).unwrap_or(&0);
+        text.push_str(&format!("New since last visit: {new_entries_count}"));
+        if let Some(last_viewed_at) = feed.last_viewed_at {
+            text.push_str(&format!(" (visited {last_viewed_at})"));
+        }
+        text.push('\n');
+    }
+
+    if let Some(feed_link) = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.suggested_feed_link.as_ref())
+    {
+        text.push_str(&format!(
+            "This feed appears to have moved to {feed_link} — press M to update it\n"
+        ));
+    }
+
+    if let Some(feed) = &app.current_feed {
+        if let Some(dead_at) = feed.dead_at {
+            text.push_str(&format!(
+                "Dead since {dead_at}, after {} failed refreshes. Press R to look for a new feed on its site\n",
+                feed.consecutive_failures
+            ));
+        } else if feed.consecutive_failures > 0 {
+            text.push_str(&format!(
+                "Failed refreshes in a row: {}\n",
+                feed.consecutive_failures
+            ));
+        }
+
+        if let Some(blocked_by) = &feed.blocked_by {
+            text.push_str(&format!(
+                "Blocked by bot protection ({blocked_by}) on its last refresh — consider a custom User-Agent or cookies from a browser with --cookie-jar\n"
+            ));
+        }
+
+        for warning in &feed.refresh_warnings {
+            text.push_str(&format!("Warning from its last refresh: {warning}\n"));
+        }
+    }
+
+    if let Some(feed) = &app.current_feed {
         text.push_str("Feed kind: ");
-        text.push_str(&feed_kind.to_string());
+        text.push_str(&feed.feed_kind.to_string());
+        if feed.source != crate::rss::FeedSource::Http {
+            text.push_str(&format!(" (from {})", feed.source));
+        }
         text.push('\n');
     }
 
-    let block = Block::default().borders(Borders::ALL).title(Span::styled(
+    if let Some(item) = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.hub_link.as_ref())
+    {
+        text.push_str("WebSub hub: ");
+        text.push_str(item);
+        text.push('\n');
+    }
+
+    let block = bordered_block(app.theme.border).title(Span::styled(
         "Info",
         Style::default()
-            .fg(Color::Cyan)
+            .fg(app.theme.title)
             .add_modifier(Modifier::BOLD),
     ));
 
@@ -269,25 +1086,62 @@ fn draw_help(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     match app.selected {
         Selected::Feeds => {
             text.push_str("r - refresh selected feed; x - refresh all feeds\n");
-            text.push_str("c - copy link; o - open link in browser\n")
+            text.push_str("X - count new entries without refreshing\n");
+            text.push_str("A - mark all of the feed's entries read\n");
+            text.push_str("g - group feeds by folder/domain; R - find moved feed\n");
+            text.push_str("F - sort feeds by title/updated/unread/added\n");
+            text.push_str("c/o - copy/open site; C/O - copy/open feed XML\n");
+            text.push_str("p - show/hide preview; N - offline mode\n");
+            text.push_str("H - the feed's own HTTP headers; E - rename feed\n");
+            text.push_str("t - sort entries by published/first seen\n");
+            text.push_str("</> - scroll long title\n")
         }
         _ => {
-            text.push_str("r - mark entry read/un; a - toggle view read/un\n");
-            text.push_str("c - copy link; o - open link in browser\n")
+            text.push_str("r - mark entry read/un; a - cycle unread/read/all\n");
+            text.push_str("s - star/unstar entry; * - starred entries of every feed\n");
+            text.push_str("t - sort entries by published/first seen\n");
+            text.push_str("c - copy link; o - open link; L - resolve link\n");
+            text.push_str("f - fetch full article from link\n");
+            text.push_str("S - split entry off/close split; w - switch pane\n");
+            text.push_str("+ - queue entry; n - read next queued entry\n");
+            text.push_str("ctrl-o/ctrl-i - back/forward through entries read\n");
+            text.push_str("u - undo read/un; m - record macro; @ - replay\n");
+            text.push_str("p - show/hide preview; I - entry info\n");
+            text.push_str("1-5 - read related entry\n");
+            text.push_str("T - as of yesterday/now; [/] - day earlier/later\n");
+            text.push_str("</> - scroll long title\n")
         }
     }
     match app.mode {
-        Mode::Normal => text.push_str("i - edit mode; q - exit\n"),
+        Mode::Normal => {
+            text.push_str("dd - delete feed; D - switch database\n");
+            text.push_str("i - edit mode; / - search entries; q - exit\n")
+        }
         Mode::Editing => {
             text.push_str("enter - fetch feed; del - delete feed\n");
-            text.push_str("esc - normal mode\n")
+            text.push_str("tab - search popular feeds; esc - normal mode\n")
+        }
+        Mode::Searching => text.push_str("enter - search all feeds; esc - normal mode\n"),
+        Mode::DeletingFeed => text.push_str("y - delete feed; any other key - keep it\n"),
+        Mode::MovingFeed => {
+            text.push_str("y - move feed; n - keep it where it is\n");
+            text.push_str("any other key - ask again later\n")
+        }
+        Mode::SwitchingDatabase => text.push_str("enter - switch to database; esc - normal mode\n"),
+        Mode::EditingFeedHttpSettings => {
+            text.push_str("Name: value | Name: value\n");
+            text.push_str("enter - save headers; esc - normal mode\n")
+        }
+        Mode::EditingFeedTitle => {
+            text.push_str("empty - the feed's own title\n");
+            text.push_str("enter - save title; esc - normal mode\n")
         }
     }
 
     text.push_str("? - show/hide help");
 
     let help_message =
-        Paragraph::new(Text::from(text.as_str())).block(Block::default().borders(Borders::ALL));
+        Paragraph::new(Text::from(text.as_str())).block(bordered_block(app.theme.border));
     f.render_widget(help_message, area);
 }
 
@@ -297,79 +1151,422 @@ fn draw_new_feed_input(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     let input = Paragraph::new(text)
         .style(Style::default().fg(Color::Yellow))
         .block(
-            Block::default().borders(Borders::ALL).title(Span::styled(
+            bordered_block(app.theme.border).title(Span::styled(
                 "Add a feed",
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(app.theme.title)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        );
+    f.render_widget(input, area);
+}
+
+fn draw_delete_feed_prompt(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let title = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.display_title())
+        .unwrap_or("this feed");
+
+    let prompt = Paragraph::new(Text::from(format!(
+        "Delete {title} and all of its entries? (y/n)"
+    )))
+    .wrap(Wrap { trim: true })
+    .block(
+        bordered_block(app.theme.error).title(Span::styled(
+            "Delete feed",
+            Style::default()
+                .fg(app.theme.error)
+                .add_modifier(Modifier::BOLD),
+        )),
+    );
+    f.render_widget(prompt, area);
+}
+
+fn draw_move_feed_prompt(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let feed_link = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.suggested_feed_link.as_deref())
+        .unwrap_or("its new link");
+
+    let prompt = Paragraph::new(Text::from(format!("Move this feed to {feed_link}? (y/n)")))
+        .wrap(Wrap { trim: true })
+        .block(
+            bordered_block(app.theme.border).title(Span::styled(
+                "Move feed",
+                Style::default()
+                    .fg(app.theme.title)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        );
+    f.render_widget(prompt, area);
+}
+
+fn draw_search_input(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let text = Text::from(app.search_input.as_str());
+    let input = Paragraph::new(text)
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            bordered_block(app.theme.border).title(Span::styled(
+                "Search entries",
+                Style::default()
+                    .fg(app.theme.title)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        );
+    f.render_widget(input, area);
+}
+
+fn draw_database_path_input(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let text = Text::from(app.database_path_input.as_str());
+    let input = Paragraph::new(text)
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            bordered_block(app.theme.border).title(Span::styled(
+                "Switch to database",
+                Style::default()
+                    .fg(app.theme.title)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        );
+    f.render_widget(input, area);
+}
+
+fn draw_feed_http_settings_input(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let text = Text::from(app.feed_http_settings_input.as_str());
+    let input = Paragraph::new(text)
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            bordered_block(app.theme.border).title(Span::styled(
+                "Headers for this feed, like User-Agent or Authorization",
+                Style::default()
+                    .fg(app.theme.title)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        );
+    f.render_widget(input, area);
+}
+
+fn draw_feed_title_input(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let text = Text::from(app.feed_title_input.as_str());
+    let input = Paragraph::new(text)
+        .style(Style::default().fg(Color::Yellow))
+        .block(
+            bordered_block(app.theme.border).title(Span::styled(
+                "Title to show this feed with",
+                Style::default()
+                    .fg(app.theme.title)
                     .add_modifier(Modifier::BOLD),
             )),
         );
     f.render_widget(input, area);
 }
 
+/// The title of the feed an entry is in, to tell search results from different feeds apart
+fn entry_feed_title<'a>(app: &'a AppImpl, entry: &EntryMetadata) -> Option<&'a str> {
+    app.all_feeds
+        .iter()
+        .find(|feed| feed.id == entry.feed_id)
+        .and_then(|feed| feed.display_title())
+}
+
 fn draw_entries(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    // errors take the bottom of the pane
+    let (area, error_area) = if app.error_flash.is_empty() {
+        (area, None)
+    } else {
+        let chunks = Layout::default()
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+            .direction(Direction::Vertical)
+            .split(area);
+        (chunks[0], Some(chunks[1]))
+    };
+
+    let is_selected = matches!(app.selected, Selected::Entries);
+    let list_width = list_width(area, is_selected);
+    let selected_index = app.entries.state.selected().filter(|_| is_selected);
+
+    let item_height = list_item_height(app.list_density);
+    let window = util::visible_window(
+        &mut app.entries.state,
+        app.entries.items.len(),
+        list_height(area),
+        |_| item_height,
+    );
+
+    if let Some(entry) = selected_index.and_then(|i| app.entries.items.get(i)) {
+        let title_width = if app.list_density == ListDensity::Table {
+            entry_table_columns(entry, entry_title_width(app, entry, list_width)).2
+        } else {
+            fit_details(
+                app.list_density,
+                entry_title_width(app, entry, list_width),
+                &entry_details(entry),
+            )
+            .0
+        };
+        let max_offset = util::max_scroll_offset(&app.entry_template.render(entry), title_width);
+        app.title_scroll_offset = app.title_scroll_offset.min(max_offset);
+    }
+
+    // only the entries that fit are rendered
     let entries = app
         .entries
         .items
         .iter()
-        .map(|entry| {
-            ListItem::new(Span::raw(entry.title.as_ref().map_or_else(
-                || std::borrow::Cow::from("No title"),
-                std::borrow::Cow::from,
-            )))
+        .enumerate()
+        .skip(window.start)
+        .take(window.len())
+        .map(|(i, entry)| {
+            let offset = if selected_index == Some(i) {
+                app.title_scroll_offset
+            } else {
+                0
+            };
+
+            let details = entry_details(entry);
+
+            let (table_columns, title_width, details_beside) =
+                if app.list_density == ListDensity::Table {
+                    let (before_title, author, title_width) =
+                        entry_table_columns(entry, entry_title_width(app, entry, list_width));
+                    (Some((before_title, author)), title_width, false)
+                } else {
+                    let (title_width, details_beside) = fit_details(
+                        app.list_density,
+                        entry_title_width(app, entry, list_width),
+                        &details,
+                    );
+                    (None, title_width, details_beside)
+                };
+
+            let mut title =
+                util::scroll_to_width(&app.entry_template.render(entry), title_width, offset);
+
+            let mut line = vec![];
+
+            if app.lists_entries_across_feeds() {
+                if let Some(feed_title) = entry_feed_title(app, entry) {
+                    line.push(Span::styled(
+                        format!("{feed_title}: "),
+                        Style::default().fg(app.theme.title),
+                    ));
+                }
+            }
+
+            if app.is_refreshed_entry(entry) {
+                line.push(Span::styled(
+                    "NEW ",
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            if entry.is_future_dated() {
+                line.push(Span::styled(
+                    FUTURE_MARKER,
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+
+            if let Some(queue_position) = app
+                .reading_queue
+                .iter()
+                .position(|queued_entry| queued_entry.id == entry.id)
+            {
+                line.push(Span::raw(format!("[{}] ", queue_position + 1)));
+            }
+
+            if entry.starred_at.is_some() {
+                line.push(Span::styled("★ ", Style::default().fg(Color::Yellow)));
+            }
+
+            let author = match table_columns {
+                Some((before_title, author)) => {
+                    line.push(Span::styled(
+                        before_title,
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                    // so the author column lines up
+                    if author.is_some() {
+                        title.push_str(&" ".repeat(title_width.saturating_sub(title.width())));
+                    }
+                    author
+                }
+                None => None,
+            };
+
+            // with every entry shown, read ones are dimmed so unread ones stand out
+            if app.read_mode == ReadMode::All && entry.read_at.is_some() {
+                line.push(Span::styled(title, Style::default().fg(Color::DarkGray)));
+            } else {
+                line.push(Span::raw(title));
+            }
+
+            if let Some(author) = author {
+                line.push(Span::styled(author, Style::default().fg(Color::DarkGray)));
+            }
+
+            list_item(line, details, details_beside, app.list_density, list_width)
         })
         .collect::<Vec<ListItem>>();
 
-    let default_title = "Entries".to_string();
+    let mut title = match &app.search_query {
+        Some(query) => format!("Search: {query}"),
+        None if app.shows_starred => "Starred".to_string(),
+        None => app
+            .current_feed
+            .as_ref()
+            .and_then(|feed| feed.display_title())
+            .unwrap_or("Entries")
+            .to_owned(),
+    };
 
-    let title = app
-        .current_feed
-        .as_ref()
-        .and_then(|feed| feed.title.as_ref())
-        .unwrap_or(&default_title);
+    let view_state = entries_view_state(app);
+    if !view_state.is_empty() {
+        title.push_str(" — ");
+        title.push_str(&view_state.join(", "));
+    }
+    if let Some(position) = list_position(&app.entries.state, app.entries.items.len()) {
+        title.push_str(" — ");
+        title.push_str(&position);
+    }
 
     let entries_titles = List::new(entries).block(
-        Block::default().borders(Borders::ALL).title(Span::styled(
+        bordered_block(app.theme.border).title(Span::styled(
             title,
             Style::default()
-                .fg(Color::Cyan)
+                .fg(app.theme.title)
                 .add_modifier(Modifier::BOLD),
         )),
     );
 
     let entries_titles = match app.selected {
         Selected::Entries => entries_titles
-            .highlight_style(Style::default().fg(PINK).add_modifier(Modifier::BOLD))
+            .highlight_style(
+                Style::default()
+                    .fg(app.theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
             .highlight_symbol("> "),
         _ => entries_titles,
     };
 
-    if !&app.error_flash.is_empty() {
-        let chunks = Layout::default()
-            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
-            .direction(Direction::Vertical)
-            .split(area);
-        {
-            let error_text = error_text(&app.error_flash);
+    render_list_window(
+        f,
+        entries_titles,
+        area,
+        app.entries.state.selected(),
+        window,
+        app.entries.items.len(),
+    );
 
-            let block = Block::default().borders(Borders::ALL).title(Span::styled(
-                "Error - press 'q' to close",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ));
+    if let Some(error_area) = error_area {
+        // less the borders
+        let error_text = error_text(
+            &app.error_flash,
+            error_area.width.saturating_sub(2),
+            &app.theme,
+        );
 
-            let error_widget = Paragraph::new(error_text)
-                .block(block)
-                .wrap(Wrap { trim: false })
-                .scroll((0, 0));
+        let block = bordered_block(app.theme.error).title(Span::styled(
+            "Error - press 'q' to close",
+            Style::default()
+                .fg(app.theme.error)
+                .add_modifier(Modifier::BOLD),
+        ));
 
-            f.render_stateful_widget(entries_titles, chunks[0], &mut app.entries.state);
-            f.render_widget(error_widget, chunks[1]);
-        }
-    } else {
-        f.render_stateful_widget(entries_titles, area, &mut app.entries.state);
+        let error_widget = Paragraph::new(error_text)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((0, 0));
+
+        f.render_widget(error_widget, error_area);
+    }
+}
+
+/// Whatever is changing which entries are listed, or how, from the default of every unread entry.
+/// This goes in the entries pane title, so entries never seem to be missing for no reason
+fn entries_view_state(app: &AppImpl) -> Vec<String> {
+    let mut view_state = vec![];
+
+    if app.entries_loading {
+        view_state.push("loading...".to_string());
+    }
+
+    if app.read_mode != ReadMode::ShowUnread {
+        view_state.push(app.read_mode.description().to_string());
+    }
+
+    if !app.lists_entries_across_feeds()
+        && app.current_feed.as_ref().map(|feed| feed.entry_sort)
+            == Some(crate::rss::EntrySort::Discovered)
+    {
+        view_state.push("by first seen".to_string());
+    }
+
+    if let Some(date) = app.time_travel_date {
+        view_state.push(format!("as of {date}"));
     }
+
+    if !app.reading_queue.is_empty() {
+        view_state.push(format!("{} queued", app.reading_queue.len()));
+    }
+
+    view_state
+}
+
+fn draw_preview(f: &mut Frame, area: Rect, app: &AppImpl) {
+    let title = app
+        .current_entry_meta
+        .as_ref()
+        .map(|entry_meta| entry_meta.display_title())
+        .unwrap_or_else(|| "Preview".to_string());
+
+    let block = bordered_block(app.theme.border)
+        .title(Span::styled(title, Style::default().fg(Color::DarkGray)));
+
+    let paragraph = Paragraph::new(app.preview_text.as_str())
+        .block(block)
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+fn draw_split_entry(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let focused = app.split_entry_focused;
+
+    let Some(split_entry) = &mut app.split_entry else {
+        return;
+    };
+
+    let title = format!(
+        "{} - {}",
+        split_entry.entry_meta.display_title(),
+        split_entry.feed_title.as_deref().unwrap_or("No feed title")
+    );
+
+    // like the entry being read when it has focus, and like the preview when it does not
+    let title_style = if focused {
+        Style::default()
+            .add_modifier(Modifier::BOLD)
+            .fg(app.theme.title)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+
+    let block = bordered_block(app.theme.border).title(Span::styled(title, title_style));
+
+    split_entry.lines_rendered_len = area.height.saturating_sub(2);
+
+    let paragraph = Paragraph::new(split_entry.text.as_str())
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((split_entry.scroll_position, 0));
+
+    f.render_widget(paragraph, area);
 }
 
 fn draw_entry(f: &mut Frame, area: Rect, app: &mut AppImpl) {
@@ -380,25 +1577,25 @@ fn draw_entry(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         panic!("draw_entry should only be called when app.selected was Selected::Entry")
     };
 
-    let entry_title = entry_meta.title.as_deref().unwrap_or("No entry title");
+    let entry_title = entry_meta.display_title();
 
     let feed_title = app
         .current_feed
         .as_ref()
-        .and_then(|feed| feed.title.as_deref())
+        .and_then(|feed| feed.display_title())
         .unwrap_or("No feed title");
 
     let mut title = String::new();
     title.reserve_exact(entry_title.len() + feed_title.len() + 3);
-    title.push_str(entry_title);
+    title.push_str(&entry_title);
     title.push_str(" - ");
     title.push_str(feed_title);
 
-    let block = Block::default().borders(Borders::ALL).title(Span::styled(
+    let block = bordered_block(app.theme.border).title(Span::styled(
         &title,
         Style::default()
             .add_modifier(Modifier::BOLD)
-            .fg(Color::Cyan),
+            .fg(app.theme.title),
     ));
 
     let paragraph = Paragraph::new(app.current_entry_text.as_str())
@@ -435,7 +1632,7 @@ fn draw_entry(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     let ratio = percent as f64 / 100.0;
     let gauge = LineGauge::default()
         .block(Block::default().borders(Borders::NONE))
-        .gauge_style(Style::default().fg(PINK))
+        .gauge_style(Style::default().fg(app.theme.highlight))
         .ratio(ratio)
         .label(label);
 
@@ -452,12 +1649,17 @@ fn draw_entry(f: &mut Frame, area: Rect, app: &mut AppImpl) {
             .direction(Direction::Vertical)
             .split(area);
         {
-            let error_text = error_text(&app.error_flash);
-            let block = Block::default().borders(Borders::ALL).title(Span::styled(
+            // less the borders
+            let error_text = error_text(
+                &app.error_flash,
+                chunks[2].width.saturating_sub(2),
+                &app.theme,
+            );
+            let block = bordered_block(app.theme.error).title(Span::styled(
                 "Error - press 'q' to close",
                 Style::default()
                     .add_modifier(Modifier::BOLD)
-                    .fg(Color::Cyan),
+                    .fg(app.theme.error),
             ));
 
             let error_widget = Paragraph::new(error_text)
@@ -486,17 +1688,118 @@ fn draw_entry(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     }
 }
 
-fn error_text(errors: &[anyhow::Error]) -> String {
-    errors
-        .iter()
-        .flat_map(|e| {
-            let mut s = format!("{e:?}")
-                .split('\n')
-                .map(|s| s.to_owned())
-                .collect::<Vec<String>>();
-            s.push("\n".to_string());
-            s
-        })
-        .collect::<Vec<String>>()
-        .join("\n")
+/// What went wrong, as far as someone reading the error pane cares
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ErrorCategory {
+    /// fetching something, like a feed or a page, failed
+    Network,
+    /// something was fetched, but it is not a feed russ can read
+    Parse,
+    Database,
+    Other,
+}
+
+impl ErrorCategory {
+    /// From the first error in `error`'s chain of causes that says what kind of error it is
+    fn of(error: &anyhow::Error) -> Self {
+        error
+            .chain()
+            .find_map(|cause| {
+                if let Some(fetch_error) = cause.downcast_ref::<FetchError>() {
+                    Some(match fetch_error {
+                        FetchError::NotAFeed { .. } => ErrorCategory::Parse,
+                        _ => ErrorCategory::Network,
+                    })
+                } else if cause.is::<ureq::Error>() || cause.is::<ureq::Transport>() {
+                    Some(ErrorCategory::Network)
+                } else if cause.is::<::rss::Error>()
+                    || cause.is::<atom_syndication::Error>()
+                    || cause.is::<serde_json::Error>()
+                {
+                    Some(ErrorCategory::Parse)
+                } else if cause.is::<rusqlite::Error>() || cause.is::<r2d2::Error>() {
+                    Some(ErrorCategory::Database)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(ErrorCategory::Other)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ErrorCategory::Network => "network",
+            ErrorCategory::Parse => "parse",
+            ErrorCategory::Database => "database",
+            ErrorCategory::Other => "error",
+        }
+    }
+
+    fn color(self, theme: &Theme) -> Color {
+        match self {
+            ErrorCategory::Network => Color::Yellow,
+            ErrorCategory::Parse => Color::Magenta,
+            ErrorCategory::Database => Color::Blue,
+            ErrorCategory::Other => theme.error,
+        }
+    }
+}
+
+/// Each error's message, labeled with its category, and then what caused it, indented beneath,
+/// wrapped to `width` so wrapped lines stay indented with the rest of their message
+fn error_text(errors: &[anyhow::Error], width: u16, theme: &Theme) -> Text<'static> {
+    let width = usize::from(width);
+    let mut lines = vec![];
+
+    for (i, error) in errors.iter().enumerate() {
+        if i > 0 {
+            lines.push(Line::default());
+        }
+
+        let category = ErrorCategory::of(error);
+
+        push_wrapped(
+            &mut lines,
+            &error.to_string(),
+            Span::styled(
+                format!("[{}] ", category.label()),
+                Style::default()
+                    .fg(category.color(theme))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+            width,
+        );
+
+        for cause in error.chain().skip(1) {
+            push_wrapped(
+                &mut lines,
+                &cause.to_string(),
+                Span::raw("  - "),
+                Style::default(),
+                width,
+            );
+        }
+    }
+
+    Text::from(lines)
+}
+
+/// Pushes `message` wrapped to `width`, after `prefix` on its first line,
+/// and indented as far as `prefix` on the lines it wraps onto
+fn push_wrapped(
+    lines: &mut Vec<Line<'static>>,
+    message: &str,
+    prefix: Span<'static>,
+    style: Style,
+    width: usize,
+) {
+    let indent = " ".repeat(prefix.width());
+    let mut prefix = Some(prefix);
+
+    for line in util::wrap_words(message, width.saturating_sub(indent.len())) {
+        let prefix = prefix.take().unwrap_or_else(|| Span::raw(indent.clone()));
+
+        lines.push(Line::from(vec![prefix, Span::styled(line, style)]));
+    }
 }
@@ -1,6 +1,8 @@
 //! miscellaneous functions that feel like they don't fit anywhere else
 
 use ratatui::widgets::ListState;
+use std::{collections::HashMap, ops::Range};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 #[derive(Debug)]
 pub struct StatefulList<T> {
@@ -16,7 +18,12 @@ impl<T> StatefulList<T> {
         }
     }
 
+    /// Selects the next item, wrapping around to the first, or nothing if there are no items
     pub fn next(&mut self) {
+        if self.items.is_empty() {
+            return self.unselect();
+        }
+
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.items.len() - 1 {
@@ -30,7 +37,12 @@ impl<T> StatefulList<T> {
         self.state.select(Some(i));
     }
 
+    /// Selects the previous item, wrapping around to the last, or nothing if there are no items
     pub fn previous(&mut self) {
+        if self.items.is_empty() {
+            return self.unselect();
+        }
+
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -53,6 +65,49 @@ impl<T> StatefulList<T> {
     }
 }
 
+/// Which of a list's `len` items fit in `height` rows, scrolling `state`'s offset only as far as
+/// it takes to show the selected item, so only they are rendered, however long the list gets.
+/// `item_height` is how many rows the item at an index takes up
+pub fn visible_window(
+    state: &mut ListState,
+    len: usize,
+    height: usize,
+    item_height: impl Fn(usize) -> usize,
+) -> Range<usize> {
+    if len == 0 || height == 0 {
+        *state.offset_mut() = 0;
+        return 0..0;
+    }
+
+    let mut offset = state.offset().min(len - 1);
+
+    if let Some(selected) = state.selected().filter(|selected| *selected < len) {
+        // every item takes up at least a row, so nothing further back fits with the selected item
+        offset = offset.clamp(selected.saturating_sub(height - 1), selected);
+        while offset < selected && (offset..=selected).map(&item_height).sum::<usize>() > height {
+            offset += 1;
+        }
+    }
+
+    let mut end = offset;
+    let mut rows = 0;
+    while end < len && rows + item_height(end) <= height {
+        rows += item_height(end);
+        end += 1;
+    }
+
+    // like after the list gets shorter, rather than leaving rows empty at the bottom
+    while offset > 0 && end == len && rows + item_height(offset - 1) <= height {
+        offset -= 1;
+        rows += item_height(offset);
+    }
+
+    *state.offset_mut() = offset;
+
+    // an item taller than the list is still shown, cut off
+    offset..end.max(offset + 1)
+}
+
 impl<T> From<Vec<T>> for StatefulList<T> {
     fn from(other: Vec<T>) -> Self {
         StatefulList::with_items(other)
@@ -79,3 +134,632 @@ pub(crate) fn set_wsl_clipboard_contents(s: &str) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Copies `s` with the OSC 52 terminal escape sequence,
+/// which works over SSH and without a display server, if the terminal allows it
+pub(crate) fn set_osc52_clipboard_contents(s: &str) -> anyhow::Result<()> {
+    use base64::Engine;
+    use std::io::Write;
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(s);
+
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{encoded}\x07")?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Runs `command` with the platform's shell, so it can use pipes, quoting, and so on
+pub(crate) fn shell_command(command: &str) -> std::process::Command {
+    if cfg!(windows) {
+        let mut shell = std::process::Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = std::process::Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+/// Removes tracking query parameters, like `utm_source` or `fbclid`, from `link`.
+/// A parameter in `tracking_parameters` ending in `*` matches any parameter with that prefix.
+///
+/// Links that cannot be parsed, or that have no tracking parameters, are returned unchanged.
+pub(crate) fn strip_tracking_parameters(link: &str, tracking_parameters: &[String]) -> String {
+    let is_tracking_parameter = |name: &str| {
+        tracking_parameters.iter().any(|tracking_parameter| {
+            match tracking_parameter.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == tracking_parameter,
+            }
+        })
+    };
+
+    let Ok(mut url) = url::Url::parse(link) else {
+        return link.to_owned();
+    };
+
+    let query_pairs = url.query_pairs().into_owned().collect::<Vec<_>>();
+
+    let kept_query_pairs = query_pairs
+        .iter()
+        .filter(|(name, _value)| !is_tracking_parameter(name))
+        .collect::<Vec<_>>();
+
+    // avoid re-serializing links we did not change
+    if kept_query_pairs.len() == query_pairs.len() {
+        return link.to_owned();
+    }
+
+    if kept_query_pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept_query_pairs);
+    }
+
+    url.to_string()
+}
+
+/// `s` as it should be shown on one line, like in a list row or a panel title:
+/// with its HTML entities, like `&amp;`, decoded, and every run of whitespace,
+/// including newlines, collapsed into a single space
+pub(crate) fn normalize_text(s: &str) -> String {
+    html_escape::decode_html_entities(s)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The host a feed's site, or failing that the feed itself, is on, without any `www.`.
+/// Feeds without a parseable link are grouped under `other`.
+pub(crate) fn feed_domain(feed: &crate::rss::Feed) -> String {
+    feed.link
+        .iter()
+        .chain(feed.feed_link.iter())
+        .filter_map(|link| url::Url::parse(link).ok())
+        .find_map(|url| url.host_str().map(|host| host.to_owned()))
+        .map(|host| host.strip_prefix("www.").unwrap_or(&host).to_owned())
+        .unwrap_or_else(|| "other".to_string())
+}
+
+/// What a feed is grouped under when feeds are grouped: its folder, or its domain if it is in none
+pub(crate) fn feed_group(feed: &crate::rss::Feed) -> String {
+    feed.folder.clone().unwrap_or_else(|| feed_domain(feed))
+}
+
+/// The part of `s` that fits in `width` columns, starting `offset` characters in,
+/// with an `…` marking either end that is cut off
+pub(crate) fn scroll_to_width(s: &str, width: usize, offset: usize) -> String {
+    let char_width = |c: char| c.width().unwrap_or(0);
+
+    let mut remaining = width;
+    let mut scrolled = String::new();
+
+    if offset > 0 && remaining > 0 {
+        scrolled.push('…');
+        remaining -= 1;
+    }
+
+    if s.chars().skip(offset).map(char_width).sum::<usize>() <= remaining {
+        scrolled.extend(s.chars().skip(offset));
+        return scrolled;
+    }
+
+    // leave room for the trailing ellipsis
+    let mut budget = remaining.saturating_sub(1);
+    for c in s.chars().skip(offset) {
+        if char_width(c) > budget {
+            break;
+        }
+        budget -= char_width(c);
+        scrolled.push(c);
+    }
+
+    if remaining > 0 {
+        scrolled.push('…');
+    }
+
+    scrolled
+}
+
+/// How far `s` can be scrolled with `scroll_to_width` before its end is showing
+pub(crate) fn max_scroll_offset(s: &str, width: usize) -> usize {
+    let mut rest_width = s.width();
+    if rest_width <= width {
+        return 0;
+    }
+
+    let mut offset = 0;
+    // once scrolled, the leading ellipsis takes a column
+    for c in s.chars() {
+        if rest_width < width {
+            break;
+        }
+        rest_width -= c.width().unwrap_or(0);
+        offset += 1;
+    }
+
+    offset
+}
+
+/// `s` broken into lines at most `width` columns wide, between words,
+/// or inside words too long for a line of their own. Line breaks in `s` are kept
+pub(crate) fn wrap_words(s: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+
+    for paragraph in s.lines() {
+        let mut line = String::new();
+
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.width() + 1 + word.width() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+
+            for c in word.chars() {
+                if !line.is_empty() && line.width() + c.width().unwrap_or(0) > width {
+                    lines.push(std::mem::take(&mut line));
+                }
+                line.push(c);
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Elements that never have a closing tag
+pub(crate) const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Removes every element named in `elements` from `html`, along with everything inside it.
+///
+/// This is not a general purpose HTML sanitizer: entry content is only ever rendered as text,
+/// so this exists to keep things like scripts, stylesheets, and enormous inline images
+/// out of the database and out of the rendered entry, not to make HTML safe for a browser.
+pub(crate) fn strip_html_elements(html: &str, elements: &[String]) -> String {
+    if elements.is_empty() {
+        return html.to_owned();
+    }
+
+    let mut out = String::with_capacity(html.len());
+    let mut position = 0;
+    let mut tags = tags(html);
+
+    while let Some(tag) = tags.next() {
+        if !elements
+            .iter()
+            .any(|element| element.eq_ignore_ascii_case(&tag.name))
+        {
+            continue;
+        }
+
+        out.push_str(&html[position..tag.range.start]);
+        position = tag.range.end;
+
+        if !tag.is_closing && !tag.is_self_closing() {
+            // if the element is never closed, only the opening tag is removed
+            let mut rest = tags.clone();
+            if let Some(element_end) = rest.skip_element(&tag.name) {
+                position = element_end;
+                tags = rest;
+            }
+        }
+    }
+
+    out.push_str(&html[position..]);
+
+    out
+}
+
+/// A tag in some HTML, as found by [`tags`]
+pub(crate) struct Tag<'a> {
+    /// lowercased
+    pub(crate) name: String,
+    pub(crate) is_closing: bool,
+    /// from the `<` to just past the `>`
+    pub(crate) range: Range<usize>,
+    /// everything between the name and the `>`
+    attributes: &'a str,
+}
+
+impl Tag<'_> {
+    /// Whether the tag ends in `/>` or opens an element that never has a closing tag
+    pub(crate) fn is_self_closing(&self) -> bool {
+        self.attributes.ends_with('/') || VOID_ELEMENTS.contains(&self.name.as_str())
+    }
+
+    /// The tag's attributes, with lowercased names and decoded values, quoted or not
+    pub(crate) fn attributes(&self) -> HashMap<String, String> {
+        let mut attributes = HashMap::new();
+        let mut chars = self.attributes.chars().peekable();
+
+        loop {
+            // skip whitespace and stray slashes
+            while chars.next_if(|c| c.is_whitespace() || *c == '/').is_some() {}
+
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let mut name = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !matches!(c, '=' | '/')) {
+                name.push(c.to_ascii_lowercase());
+            }
+
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+            let mut value = String::new();
+
+            if chars.next_if(|c| *c == '=').is_some() {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+                match chars.next_if(|c| *c == '"' || *c == '\'') {
+                    Some(quote) => {
+                        for c in chars.by_ref() {
+                            if c == quote {
+                                break;
+                            }
+                            value.push(c);
+                        }
+                    }
+                    None => {
+                        while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                            value.push(c);
+                        }
+                    }
+                }
+            }
+
+            if !name.is_empty() {
+                let mut decoded_value = String::new();
+                html_escape::decode_html_entities_to_string(&value, &mut decoded_value);
+                attributes.insert(name, decoded_value);
+            }
+        }
+
+        attributes
+    }
+}
+
+/// The tags in `html`, in order, however they are cased.
+/// Comments, doctypes, and stray `<` are not tags, and whatever is inside them is still searched.
+pub(crate) fn tags(html: &str) -> Tags<'_> {
+    Tags { html, position: 0 }
+}
+
+#[derive(Clone)]
+pub(crate) struct Tags<'a> {
+    html: &'a str,
+    position: usize,
+}
+
+impl Tags<'_> {
+    /// Skips past the tag that closes the `name` element whose opening tag was just returned,
+    /// accounting for nested elements of the same name, and returns the offset just past it
+    pub(crate) fn skip_element(&mut self, name: &str) -> Option<usize> {
+        let mut depth = 1;
+
+        for tag in self.by_ref() {
+            if tag.name != name {
+                continue;
+            }
+
+            if tag.is_closing {
+                depth -= 1;
+            } else if !tag.is_self_closing() {
+                depth += 1;
+            }
+
+            if depth == 0 {
+                return Some(tag.range.end);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a> Iterator for Tags<'a> {
+    type Item = Tag<'a>;
+
+    fn next(&mut self) -> Option<Tag<'a>> {
+        while let Some(offset) = self.html[self.position..].find('<') {
+            let tag_start = self.position + offset;
+            let (name, is_closing) = tag_name(&self.html[tag_start + 1..]);
+
+            if name.is_empty() {
+                self.position = tag_start + 1;
+                continue;
+            }
+
+            let tag_end = find_tag_end(self.html, tag_start);
+            self.position = tag_end;
+
+            let attributes_start = tag_start + "<".len() + usize::from(is_closing) + name.len();
+            let attributes = &self.html[attributes_start..tag_end];
+
+            return Some(Tag {
+                name: name.to_ascii_lowercase(),
+                is_closing,
+                range: tag_start..tag_end,
+                attributes: attributes
+                    .strip_suffix('>')
+                    .unwrap_or(attributes)
+                    .trim_end(),
+            });
+        }
+
+        self.position = self.html.len();
+
+        None
+    }
+}
+
+/// Returns the tag name at the start of `s`, which begins just after a `<`,
+/// and whether it is a closing tag
+fn tag_name(s: &str) -> (&str, bool) {
+    let (s, is_closing_tag) = match s.strip_prefix('/') {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+
+    let name_len = s
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(s.len());
+
+    (&s[..name_len], is_closing_tag)
+}
+
+/// Returns the offset just past the `>` that ends the tag starting at `tag_start`,
+/// skipping over any `>` in quoted attribute values
+fn find_tag_end(html: &str, tag_start: usize) -> usize {
+    let mut quote = None;
+
+    for (i, c) in html[tag_start..].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return tag_start + i + 1,
+            _ => (),
+        }
+    }
+
+    html.len()
+}
+
+/// A directory for a test's files, which is removed along with them when it is dropped,
+/// even if the test fails
+#[cfg(test)]
+pub(crate) struct TestDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    /// An empty directory, named for the test that uses it
+    pub(crate) fn new(name: &str) -> TestDir {
+        let path = std::env::temp_dir().join(format!("russ-{name}-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        TestDir(path)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TestDir {
+    type Target = std::path::Path;
+
+    fn deref(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_items_around_the_selection_are_visible() {
+        let one_row = |_| 1;
+        let mut state = ListState::default();
+
+        assert_eq!(visible_window(&mut state, 0, 10, one_row), 0..0);
+        assert_eq!(visible_window(&mut state, 300, 10, one_row), 0..10);
+
+        // scrolls just far enough to show the selected item, and no further
+        state.select(Some(12));
+        assert_eq!(visible_window(&mut state, 300, 10, one_row), 3..13);
+        state.select(Some(5));
+        assert_eq!(visible_window(&mut state, 300, 10, one_row), 3..13);
+        state.select(Some(2));
+        assert_eq!(visible_window(&mut state, 300, 10, one_row), 2..12);
+        state.select(Some(299));
+        assert_eq!(visible_window(&mut state, 300, 10, one_row), 290..300);
+
+        // the list got shorter
+        state.select(Some(3));
+        assert_eq!(visible_window(&mut state, 4, 10, one_row), 0..4);
+
+        // two-row items, after a one-row group header
+        let mut state = ListState::default().with_selected(Some(5));
+        let item_height = |i| if i == 0 { 1 } else { 2 };
+        assert_eq!(visible_window(&mut state, 300, 10, item_height), 1..6);
+    }
+
+    #[test]
+    fn moving_through_an_empty_list_selects_nothing() {
+        let mut list: StatefulList<()> = StatefulList::with_items(vec![]);
+        list.state.select(Some(0));
+
+        list.next();
+        assert_eq!(list.state.selected(), None);
+
+        list.reset();
+        list.previous();
+        assert_eq!(list.state.selected(), None);
+
+        let mut list = StatefulList::with_items(vec![1, 2]);
+        list.previous();
+        assert_eq!(list.state.selected(), Some(0));
+        list.previous();
+        assert_eq!(list.state.selected(), Some(1));
+        list.next();
+        assert_eq!(list.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn it_strips_tracking_parameters() {
+        let tracking_parameters = vec!["utm_*".to_string(), "fbclid".to_string()];
+
+        assert_eq!(
+            strip_tracking_parameters(
+                "https://example.com/post?id=1&utm_source=rss&utm_medium=feed&fbclid=abc",
+                &tracking_parameters
+            ),
+            "https://example.com/post?id=1"
+        );
+        assert_eq!(
+            strip_tracking_parameters(
+                "https://example.com/post?utm_source=rss",
+                &tracking_parameters
+            ),
+            "https://example.com/post"
+        );
+        assert_eq!(
+            strip_tracking_parameters("https://example.com/post?id=1", &tracking_parameters),
+            "https://example.com/post?id=1"
+        );
+        assert_eq!(
+            strip_tracking_parameters("not a url", &tracking_parameters),
+            "not a url"
+        );
+    }
+
+    #[test]
+    fn it_scrolls_text_to_fit_a_width() {
+        assert_eq!(scroll_to_width("short", 10, 0), "short");
+        assert_eq!(scroll_to_width("a long title", 7, 0), "a long…");
+        assert_eq!(scroll_to_width("a long title", 7, 2), "…long …");
+
+        let max_offset = max_scroll_offset("a long title", 7);
+        assert_eq!(scroll_to_width("a long title", 7, max_offset), "… title");
+        assert_eq!(max_scroll_offset("short", 10), 0);
+
+        // wide characters take up two columns
+        assert_eq!(scroll_to_width("日本語のタイトル", 7, 0), "日本語…");
+    }
+
+    #[test]
+    fn it_normalizes_text_for_one_line() {
+        assert_eq!(
+            normalize_text("  Salt &amp; Pepper\n\t&#8212;  a\r\nreview  "),
+            "Salt & Pepper — a review"
+        );
+        assert_eq!(normalize_text(""), "");
+    }
+
+    #[test]
+    fn it_wraps_words_to_a_width() {
+        assert_eq!(
+            wrap_words("the quick brown fox", 9),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(
+            wrap_words("at https://example.com/feed.xml", 8),
+            vec!["at", "https://", "example.", "com/feed", ".xml"]
+        );
+        assert_eq!(
+            wrap_words("status code 500\n\nbad gateway", 20),
+            vec!["status code 500", "", "bad gateway"]
+        );
+    }
+
+    #[test]
+    fn it_finds_tags_and_their_attributes() {
+        let html = r#"<!-- <b> --><A HREF="/a?x=1&amp;y=2" Title='a > b'>a < b</a><br/><img src=c.png alt=>"#;
+
+        let found = tags(html)
+            .map(|tag| (tag.name.clone(), tag.is_closing, tag.is_self_closing()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                ("b".to_owned(), false, false),
+                ("a".to_owned(), false, false),
+                ("a".to_owned(), true, false),
+                ("br".to_owned(), false, true),
+                ("img".to_owned(), false, true),
+            ]
+        );
+
+        let a = tags(html).nth(1).unwrap();
+        assert_eq!(
+            &html[a.range.clone()],
+            r#"<A HREF="/a?x=1&amp;y=2" Title='a > b'>"#
+        );
+        assert_eq!(
+            a.attributes(),
+            HashMap::from([
+                ("href".to_owned(), "/a?x=1&y=2".to_owned()),
+                ("title".to_owned(), "a > b".to_owned()),
+            ])
+        );
+
+        let img = tags(html).last().unwrap();
+        assert_eq!(
+            img.attributes(),
+            HashMap::from([
+                ("src".to_owned(), "c.png".to_owned()),
+                ("alt".to_owned(), "".to_owned()),
+            ])
+        );
+    }
+
+    #[test]
+    fn it_strips_html_elements() {
+        let elements = vec!["script".to_string(), "style".to_string(), "img".to_string()];
+
+        assert_eq!(
+            strip_html_elements(
+                r#"<p>a<script type="text/javascript">if (1 > 0) { track("</p>"); }</script>b</p>"#,
+                &elements
+            ),
+            "<p>ab</p>"
+        );
+        assert_eq!(
+            strip_html_elements(
+                r#"<STYLE>p { color: red }</STYLE><p>a<img src="data:image/png;base64,AAAA" alt=">">b</p>"#,
+                &elements
+            ),
+            "<p>ab</p>"
+        );
+        assert_eq!(
+            strip_html_elements("<p>a<img/>b<br>c</p>", &elements),
+            "<p>ab<br>c</p>"
+        );
+        assert_eq!(
+            strip_html_elements("<p>a < b</p><pre>x</pre>", &elements),
+            "<p>a < b</p><pre>x</pre>"
+        );
+        assert_eq!(
+            strip_html_elements("<script>unclosed", &elements),
+            "unclosed"
+        );
+    }
+}